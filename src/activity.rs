@@ -0,0 +1,458 @@
+// Extraction of what a session did: its time window and the files its
+// tool calls touched
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io::BufRead;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::compress::open_session_file;
+use crate::discovery::{last_modified, session_file_path};
+use crate::session::{clean_message, is_meta_message, Session};
+
+/// Tools whose `file_path` input modifies the file.
+const EDIT_TOOLS: &[&str] = &["Edit", "MultiEdit", "Write", "NotebookEdit"];
+
+/// Tools whose `file_path` input only reads the file.
+const READ_TOOLS: &[&str] = &["Read", "NotebookRead"];
+
+/// Maximum length of the prompt kept with each tool call.
+const PROMPT_PREVIEW_LEN: usize = 120;
+
+/// A tool call that read or modified a file.
+#[derive(Debug, Clone)]
+pub struct FileToolUse {
+    pub tool: String,
+    pub file_path: String,
+    pub timestamp: Option<DateTime<Utc>>,
+    /// UUID of the assistant message that made the call.
+    pub uuid: Option<String>,
+    /// Start of the user prompt the call answered.
+    pub prompt: Option<String>,
+    /// Text replaced by the call (empty for reads).
+    pub changes: Vec<TextChange>,
+}
+
+/// One replacement made by an edit tool. Whole-file writes have an empty
+/// `old` text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextChange {
+    pub old: String,
+    pub new: String,
+}
+
+impl FileToolUse {
+    /// Whether the tool call modified the file.
+    pub fn is_edit(&self) -> bool {
+        EDIT_TOOLS.contains(&self.tool.as_str())
+    }
+}
+
+/// Token usage summed over a session's assistant messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub input: u64,
+    pub output: u64,
+    pub cache_read: u64,
+    pub cache_creation: u64,
+}
+
+impl TokenUsage {
+    fn from_value(usage: &Value) -> Self {
+        let field = |key: &str| usage.get(key).and_then(Value::as_u64).unwrap_or(0);
+        Self {
+            input: field("input_tokens"),
+            output: field("output_tokens"),
+            cache_read: field("cache_read_input_tokens"),
+            cache_creation: field("cache_creation_input_tokens"),
+        }
+    }
+
+    /// All input tokens, including cached ones.
+    pub fn total_input(&self) -> u64 {
+        self.input + self.cache_read + self.cache_creation
+    }
+}
+
+/// Time window, turns, token usage, and tool calls of a session.
+#[derive(Debug, Clone, Default)]
+pub struct SessionActivity {
+    pub first_timestamp: Option<DateTime<Utc>>,
+    pub last_timestamp: Option<DateTime<Utc>>,
+    /// Real user prompts (excluding tool results and meta messages).
+    pub user_turns: usize,
+    /// Distinct assistant messages.
+    pub assistant_turns: usize,
+    pub tokens: TokenUsage,
+    /// Models that answered, in order of first use.
+    pub models: Vec<String>,
+    /// Number of calls per tool name.
+    pub tool_counts: BTreeMap<String, usize>,
+    /// File tool calls in the order they were made.
+    pub file_uses: Vec<FileToolUse>,
+    /// Real user prompts in order, with the replies each received.
+    pub prompts: Vec<PromptTurns>,
+}
+
+/// A user prompt and the number of assistant messages that followed it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptTurns {
+    pub text: String,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub assistant_turns: usize,
+}
+
+impl SessionActivity {
+    /// Time between the first and last message.
+    pub fn duration(&self) -> Option<chrono::Duration> {
+        Some(self.last_timestamp? - self.first_timestamp?)
+    }
+
+    /// Absolute paths of all files the session modified.
+    pub fn edited_files(&self) -> BTreeSet<String> {
+        self.file_uses
+            .iter()
+            .filter(|u| u.is_edit())
+            .map(|u| u.file_path.clone())
+            .collect()
+    }
+
+    /// Files the session touched with how many times each was edited and
+    /// read, edited files first, then by path.
+    pub fn file_summary(&self) -> Vec<FileSummary> {
+        let mut files: HashMap<&str, FileSummary> = HashMap::new();
+        for u in &self.file_uses {
+            let entry = files.entry(&u.file_path).or_insert_with(|| FileSummary {
+                path: u.file_path.clone(),
+                edits: 0,
+                reads: 0,
+            });
+            if u.is_edit() {
+                entry.edits += 1;
+            } else {
+                entry.reads += 1;
+            }
+        }
+        let mut files: Vec<FileSummary> = files.into_values().collect();
+        files.sort_by(|a, b| (b.edits > 0).cmp(&(a.edits > 0)).then_with(|| a.path.cmp(&b.path)));
+        files
+    }
+}
+
+/// How often a session edited and read one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSummary {
+    pub path: String,
+    pub edits: usize,
+    pub reads: usize,
+}
+
+/// How often a session edited and read one file, and when it last did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileTouches {
+    pub edits: usize,
+    pub reads: usize,
+    pub last_touched: Option<DateTime<Utc>>,
+}
+
+/// Files a session's tool calls touched, cached between `files` lookups.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionFileSet {
+    /// Modification time of the session file the set was built from.
+    pub modified: Option<DateTime<Utc>>,
+    /// Touches per recorded path.
+    pub files: BTreeMap<String, FileTouches>,
+}
+
+impl SessionFileSet {
+    pub fn new(activity: &SessionActivity, modified: Option<DateTime<Utc>>) -> Self {
+        let mut files: BTreeMap<String, FileTouches> = BTreeMap::new();
+        for u in &activity.file_uses {
+            let touches = files.entry(u.file_path.clone()).or_default();
+            if u.is_edit() {
+                touches.edits += 1;
+            } else {
+                touches.reads += 1;
+            }
+            touches.last_touched = touches.last_touched.max(u.timestamp);
+        }
+        SessionFileSet { modified, files }
+    }
+}
+
+/// A session that touched a looked-up file.
+#[derive(Debug, Clone)]
+pub struct FileLookupMatch {
+    pub session: Session,
+    pub edits: usize,
+    pub reads: usize,
+    /// Time of the most recent matching tool call.
+    pub last_touched: DateTime<Utc>,
+}
+
+/// Scan a session file for its time window and file tool calls.
+pub fn scan_session_activity(path: &Path) -> SessionActivity {
+    let mut activity = SessionActivity::default();
+    let Ok(reader) = open_session_file(path) else {
+        return activity;
+    };
+
+    let mut prompt: Option<String> = None;
+    // Streamed replies repeat one message ID across entries; the last
+    // entry of each carries its final usage
+    let mut usage_by_message: HashMap<String, TokenUsage> = HashMap::new();
+    for line in reader.lines().map_while(Result::ok) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(entry) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        let entry_type = entry.get("type").and_then(Value::as_str).unwrap_or_default();
+        if entry_type != "user" && entry_type != "assistant" {
+            continue;
+        }
+
+        let timestamp: Option<DateTime<Utc>> = entry
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .and_then(|t| t.parse().ok());
+        if let Some(ts) = timestamp {
+            activity.first_timestamp = Some(activity.first_timestamp.map_or(ts, |f| f.min(ts)));
+            activity.last_timestamp = Some(activity.last_timestamp.map_or(ts, |l| l.max(ts)));
+        }
+
+        if entry_type == "user" {
+            if let Some(text) = user_prompt_text(&entry) {
+                activity.prompts.push(PromptTurns {
+                    text: text.clone(),
+                    timestamp,
+                    assistant_turns: 0,
+                });
+                prompt = Some(text);
+                activity.user_turns += 1;
+            }
+            continue;
+        }
+
+        let message_id = entry
+            .pointer("/message/id")
+            .and_then(Value::as_str)
+            .or_else(|| entry.get("uuid").and_then(Value::as_str))
+            .unwrap_or_default()
+            .to_string();
+        let usage = entry
+            .pointer("/message/usage")
+            .map(TokenUsage::from_value)
+            .unwrap_or_default();
+        if !usage_by_message.contains_key(&message_id) {
+            if let Some(last) = activity.prompts.last_mut() {
+                last.assistant_turns += 1;
+            }
+        }
+        usage_by_message.insert(message_id, usage);
+        if let Some(model) = entry.pointer("/message/model").and_then(Value::as_str) {
+            if !activity.models.iter().any(|m| m == model) && model != "<synthetic>" {
+                activity.models.push(model.to_string());
+            }
+        }
+
+        let uuid = entry.get("uuid").and_then(Value::as_str).map(str::to_string);
+        let blocks = entry
+            .pointer("/message/content")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        for block in blocks {
+            if block.get("type").and_then(Value::as_str) != Some("tool_use") {
+                continue;
+            }
+            let Some(tool) = block.get("name").and_then(Value::as_str) else {
+                continue;
+            };
+            *activity.tool_counts.entry(tool.to_string()).or_default() += 1;
+            if !EDIT_TOOLS.contains(&tool) && !READ_TOOLS.contains(&tool) {
+                continue;
+            }
+            let Some(input) = block.get("input") else {
+                continue;
+            };
+            let file_path = input
+                .get("file_path")
+                .or_else(|| input.get("notebook_path"))
+                .and_then(Value::as_str);
+            if let Some(file_path) = file_path {
+                activity.file_uses.push(FileToolUse {
+                    tool: tool.to_string(),
+                    file_path: file_path.to_string(),
+                    timestamp,
+                    uuid: uuid.clone(),
+                    prompt: prompt.clone(),
+                    changes: text_changes(tool, input),
+                });
+            }
+        }
+    }
+
+    activity.assistant_turns = usage_by_message.len();
+    for usage in usage_by_message.values() {
+        activity.tokens.input += usage.input;
+        activity.tokens.output += usage.output;
+        activity.tokens.cache_read += usage.cache_read;
+        activity.tokens.cache_creation += usage.cache_creation;
+    }
+    activity
+}
+
+/// Display text of a real user prompt, or None for tool results and meta
+/// messages.
+fn user_prompt_text(entry: &Value) -> Option<String> {
+    let content = entry.pointer("/message/content")?;
+    let raw = match content {
+        Value::String(s) => s.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter(|b| b.get("type").and_then(Value::as_str) == Some("text"))
+            .filter_map(|b| b.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join(""),
+        _ => return None,
+    };
+    if is_meta_message(&raw) {
+        return None;
+    }
+    Some(clean_message(&raw).chars().take(PROMPT_PREVIEW_LEN).collect())
+}
+
+/// Text replacements described by an edit tool's input.
+pub fn text_changes(tool: &str, input: &Value) -> Vec<TextChange> {
+    let text = |v: &Value, key: &str| {
+        v.get(key).and_then(Value::as_str).unwrap_or_default().to_string()
+    };
+    match tool {
+        "Edit" => vec![TextChange {
+            old: text(input, "old_string"),
+            new: text(input, "new_string"),
+        }],
+        "MultiEdit" => input
+            .get("edits")
+            .and_then(Value::as_array)
+            .map(|edits| {
+                edits
+                    .iter()
+                    .map(|e| TextChange {
+                        old: text(e, "old_string"),
+                        new: text(e, "new_string"),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        "Write" => vec![TextChange {
+            old: String::new(),
+            new: text(input, "content"),
+        }],
+        "NotebookEdit" => vec![TextChange {
+            old: String::new(),
+            new: text(input, "new_source"),
+        }],
+        _ => Vec::new(),
+    }
+}
+
+/// File sets of all sessions, keyed by session ID.
+///
+/// With a `cache` path, sets saved there are reused for session files that
+/// were not modified since, and the updated sets are saved back.
+pub fn build_file_index(
+    claude_home: &Path,
+    sessions: &[Session],
+    cache: Option<&Path>,
+) -> HashMap<String, SessionFileSet> {
+    let cached: HashMap<String, SessionFileSet> = cache
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+
+    let index: HashMap<String, SessionFileSet> = sessions
+        .par_iter()
+        .map(|s| {
+            let path = session_file_path(claude_home, s);
+            let modified = last_modified(&path);
+            let set = match cached.get(&s.id) {
+                Some(set) if modified.is_some() && set.modified == modified => set.clone(),
+                _ => SessionFileSet::new(&scan_session_activity(&path), modified),
+            };
+            (s.id.clone(), set)
+        })
+        .collect();
+
+    if let Some(path) = cache.filter(|_| index != cached) {
+        // A cache that cannot be written only costs time on the next lookup
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Ok(text) = serde_json::to_string(&index) {
+            let _ = fs::write(path, text);
+        }
+    }
+    index
+}
+
+/// Check whether a path recorded in a session refers to `target`.
+///
+/// Paths are compared literally first, then after resolving symlinks.
+pub fn same_file(recorded: &str, target: &Path) -> bool {
+    let recorded = Path::new(recorded);
+    if recorded == target {
+        return true;
+    }
+    if recorded.file_name() != target.file_name() {
+        return false;
+    }
+    match (recorded.canonicalize(), target.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Sessions whose tool calls touched `target` (an absolute path), most
+/// recently touched first.
+///
+/// Only edits count unless `include_reads` is set.
+pub fn sessions_touching_file(
+    sessions: &[Session],
+    index: &HashMap<String, SessionFileSet>,
+    target: &Path,
+    include_reads: bool,
+) -> Vec<FileLookupMatch> {
+    let mut matches: Vec<FileLookupMatch> = sessions
+        .iter()
+        .filter_map(|session| {
+            let set = index.get(&session.id)?;
+            let mut touches = FileTouches::default();
+            for (recorded, t) in &set.files {
+                if same_file(recorded, target) {
+                    touches.edits += t.edits;
+                    touches.reads += t.reads;
+                    touches.last_touched = touches.last_touched.max(t.last_touched);
+                }
+            }
+            if touches.edits == 0 && !(include_reads && touches.reads > 0) {
+                return None;
+            }
+            Some(FileLookupMatch {
+                session: session.clone(),
+                edits: touches.edits,
+                reads: touches.reads,
+                last_touched: touches.last_touched.unwrap_or(session.timestamp),
+            })
+        })
+        .collect();
+    matches.sort_by_key(|m| std::cmp::Reverse(m.last_touched));
+    matches
+}
@@ -0,0 +1,183 @@
+// Attribution of file lines to the session edits that wrote them
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use rayon::prelude::*;
+
+use crate::activity::{same_file, scan_session_activity};
+use crate::discovery::session_file_path;
+use crate::session::Session;
+
+/// The session edit that introduced a line.
+#[derive(Debug, Clone)]
+pub struct BlameSource {
+    pub session: Session,
+    pub tool: String,
+    /// UUID of the assistant message that made the edit.
+    pub uuid: Option<String>,
+    pub timestamp: Option<DateTime<Utc>>,
+    /// Start of the user prompt the edit answered.
+    pub prompt: Option<String>,
+}
+
+/// A run of consecutive lines with the same source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameHunk {
+    /// First line, 1-based.
+    pub start: usize,
+    /// Last line, 1-based and inclusive.
+    pub end: usize,
+    /// Index into `Blame::sources`, or None if no edit matched.
+    pub source: Option<usize>,
+}
+
+/// Blame result for a whole file.
+#[derive(Debug, Clone)]
+pub struct Blame {
+    pub lines: Vec<String>,
+    pub sources: Vec<BlameSource>,
+    /// Source index of each line, or None if unknown.
+    pub line_sources: Vec<Option<usize>>,
+}
+
+impl Blame {
+    /// Group consecutive lines with the same source into hunks.
+    pub fn hunks(&self) -> Vec<BlameHunk> {
+        let mut hunks: Vec<BlameHunk> = Vec::new();
+        for (i, source) in self.line_sources.iter().enumerate() {
+            match hunks.last_mut() {
+                Some(last) if last.source == *source => last.end = i + 1,
+                _ => hunks.push(BlameHunk {
+                    start: i + 1,
+                    end: i + 1,
+                    source: *source,
+                }),
+            }
+        }
+        hunks
+    }
+}
+
+/// Whether a line is distinctive enough to anchor a match on its own.
+///
+/// Blank lines and lone braces appear everywhere; they are only attributed
+/// when they line up with a distinctive line of the same edit.
+fn is_distinctive(line: &str) -> bool {
+    line.chars().count() >= 4 && line.chars().any(char::is_alphanumeric)
+}
+
+/// Attribute each line of `lines` to one of `written`, the texts written by
+/// edits in chronological order.
+///
+/// An edit claims the lines it aligns with: every offset at which one of
+/// its distinctive lines occurs in the file is tried, and all lines of the
+/// edit that match at that offset are attributed to it. Later edits take
+/// precedence. Lines are compared with surrounding whitespace trimmed.
+pub fn attribute_lines(lines: &[&str], written: &[Vec<&str>]) -> Vec<Option<usize>> {
+    let trimmed: Vec<&str> = lines.iter().map(|l| l.trim()).collect();
+    let mut positions: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, line) in trimmed.iter().enumerate() {
+        if is_distinctive(line) {
+            positions.entry(line).or_default().push(i);
+        }
+    }
+
+    let mut result = vec![None; lines.len()];
+    for (k, hunk) in written.iter().enumerate() {
+        let hunk: Vec<&str> = hunk.iter().map(|l| l.trim()).collect();
+        let mut offsets: Vec<isize> = hunk
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| is_distinctive(line))
+            .flat_map(|(j, line)| {
+                positions
+                    .get(line)
+                    .into_iter()
+                    .flatten()
+                    .map(move |&i| i as isize - j as isize)
+            })
+            .collect();
+        offsets.sort_unstable();
+        offsets.dedup();
+
+        for offset in offsets {
+            for (j, line) in hunk.iter().enumerate() {
+                let i = offset + j as isize;
+                if i >= 0 && (i as usize) < trimmed.len() && trimmed[i as usize] == *line {
+                    result[i as usize] = Some(k);
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Blame the current contents of `target` (an absolute path) against the
+/// `Edit`, `MultiEdit`, `Write`, and `NotebookEdit` calls of the sessions
+/// whose project contains it.
+pub fn blame_file(claude_home: &Path, sessions: &[Session], target: &Path) -> Result<Blame, String> {
+    let content = fs::read_to_string(target)
+        .map_err(|e| format!("cannot read {}: {e}", target.display()))?;
+    let lines: Vec<String> = content.lines().map(str::to_string).collect();
+
+    let candidates: Vec<&Session> = sessions
+        .iter()
+        .filter(|s| {
+            [&s.project_path, &s.cwd]
+                .iter()
+                .any(|dir| !dir.is_empty() && target.starts_with(dir.as_str()))
+        })
+        .collect();
+
+    let mut edits: Vec<(BlameSource, String)> = candidates
+        .par_iter()
+        .flat_map_iter(|session| {
+            let activity = scan_session_activity(&session_file_path(claude_home, session));
+            activity
+                .file_uses
+                .into_iter()
+                .filter(|u| u.is_edit() && same_file(&u.file_path, target))
+                .flat_map(|u| {
+                    let source = BlameSource {
+                        session: (*session).clone(),
+                        tool: u.tool.clone(),
+                        uuid: u.uuid.clone(),
+                        timestamp: u.timestamp,
+                        prompt: u.prompt.clone(),
+                    };
+                    u.changes
+                        .into_iter()
+                        .map(move |c| (source.clone(), c.new))
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    edits.sort_by_key(|(source, _)| source.timestamp);
+
+    let line_refs: Vec<&str> = lines.iter().map(String::as_str).collect();
+    let written: Vec<Vec<&str>> = edits.iter().map(|(_, text)| text.lines().collect()).collect();
+    let mut line_sources = attribute_lines(&line_refs, &written);
+
+    // Keep only the sources that ended up owning a line, merging the
+    // changes of one MultiEdit call into a single source
+    let mut sources: Vec<BlameSource> = Vec::new();
+    let mut remap = HashMap::new();
+    for source in line_sources.iter_mut().flatten() {
+        let edit = &edits[*source].0;
+        let key = (edit.session.id.as_str(), edit.uuid.as_deref(), edit.timestamp);
+        let idx = *remap.entry(key).or_insert_with(|| {
+            sources.push(edit.clone());
+            sources.len() - 1
+        });
+        *source = idx;
+    }
+
+    Ok(Blame {
+        lines,
+        sources,
+        line_sources,
+    })
+}
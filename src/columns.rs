@@ -0,0 +1,141 @@
+// Columns and row density of the session list, and fitting columns to a width
+
+use serde::{Deserialize, Serialize};
+
+/// Space between columns.
+pub const COLUMN_GAP: usize = 2;
+
+/// Narrowest a text column is truncated to before it is dropped.
+const MIN_TRUNCATED_WIDTH: usize = 8;
+
+/// A column shown after the prompt in the session list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Column {
+    /// `▶ running` for sessions open in another terminal.
+    Running,
+    Project,
+    Branch,
+    /// Model that answered first.
+    Model,
+    /// Number of user prompts.
+    Turns,
+    Tokens,
+    Duration,
+    /// First 8 characters of the session ID.
+    Id,
+    /// Claude Code version that wrote the session.
+    Version,
+    /// Age of the session.
+    Time,
+}
+
+/// Columns shown when the config does not list any.
+pub const DEFAULT_COLUMNS: [Column; 3] = [Column::Running, Column::Project, Column::Time];
+
+impl Column {
+    /// Columns with a lower priority are dropped first when the row is too
+    /// narrow.
+    pub fn priority(self) -> u8 {
+        match self {
+            Column::Running => 9,
+            Column::Time => 8,
+            Column::Project => 7,
+            Column::Branch => 6,
+            Column::Turns => 5,
+            Column::Duration => 4,
+            Column::Tokens => 3,
+            Column::Model => 2,
+            Column::Id => 1,
+            Column::Version => 0,
+        }
+    }
+
+    /// Widest the column gets; longer text is truncated.
+    pub fn max_width(self) -> usize {
+        match self {
+            Column::Project | Column::Branch => 24,
+            Column::Model => 18,
+            Column::Id => 8,
+            _ => 16,
+        }
+    }
+
+    /// Whether long text in the column can be truncated to make room.
+    fn truncates(self) -> bool {
+        matches!(self, Column::Project | Column::Branch | Column::Model)
+    }
+
+    /// Whether the column shows counts from a full scan of the session file.
+    pub fn needs_stats(self) -> bool {
+        matches!(
+            self,
+            Column::Model | Column::Turns | Column::Tokens | Column::Duration
+        )
+    }
+
+    /// Whether the column's text is aligned to the right (numbers and ages).
+    pub fn right_aligned(self) -> bool {
+        matches!(
+            self,
+            Column::Turns | Column::Tokens | Column::Duration | Column::Time
+        )
+    }
+}
+
+/// Lines per session row.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Density {
+    /// Prompt and columns on one line.
+    #[default]
+    OneLine,
+    /// Prompt on the first line, columns on the second.
+    TwoLine,
+}
+
+impl Density {
+    pub fn row_height(self) -> usize {
+        match self {
+            Density::OneLine => 1,
+            Density::TwoLine => 2,
+        }
+    }
+}
+
+/// Fit columns into `available` characters, including the gap before each.
+///
+/// `widths` holds the widest text of each column, 0 for columns with nothing
+/// to show. Text columns are first truncated, lowest priority first, then
+/// whole columns are dropped. Returns the width of each column; 0 means
+/// hidden.
+pub fn fit_columns(columns: &[Column], widths: &[usize], available: usize) -> Vec<usize> {
+    let mut widths: Vec<usize> = columns
+        .iter()
+        .zip(widths)
+        .map(|(column, &width)| width.min(column.max_width()))
+        .collect();
+    let used = |widths: &[usize]| -> usize {
+        widths.iter().filter(|&&w| w > 0).map(|w| w + COLUMN_GAP).sum()
+    };
+
+    let mut by_priority: Vec<usize> = (0..columns.len()).collect();
+    by_priority.sort_by_key(|&i| columns[i].priority());
+
+    for &i in &by_priority {
+        let over = used(&widths).saturating_sub(available);
+        if over == 0 {
+            return widths;
+        }
+        if columns[i].truncates() && widths[i] > MIN_TRUNCATED_WIDTH {
+            widths[i] -= over.min(widths[i] - MIN_TRUNCATED_WIDTH);
+        }
+    }
+    for &i in &by_priority {
+        if used(&widths) <= available {
+            break;
+        }
+        widths[i] = 0;
+    }
+    widths
+}
@@ -0,0 +1,224 @@
+// Transparent access to compressed session files and in-place compression
+
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+
+/// Compression formats supported for session files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// gzip (`.jsonl.gz`)
+    Gzip,
+    /// Zstandard (`.jsonl.zst`)
+    Zstd,
+}
+
+impl Format {
+    /// File extension appended to `.jsonl` for this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Gzip => "gz",
+            Format::Zstd => "zst",
+        }
+    }
+}
+
+/// File name suffixes recognized as session files, plain or compressed.
+pub const SESSION_SUFFIXES: &[&str] = &[".jsonl", ".jsonl.gz", ".jsonl.zst"];
+
+/// Extract the session ID from a session file name.
+///
+/// Strips `.jsonl` together with any compression suffix, so
+/// `abc.jsonl.zst` yields `abc`. Returns None for non-session files.
+pub fn session_id_from_path(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    SESSION_SUFFIXES
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .filter(|id| !id.is_empty())
+        .map(|id| id.to_string())
+}
+
+/// Check whether a path names a session file (plain or compressed).
+pub fn is_session_file(path: &Path) -> bool {
+    session_id_from_path(path).is_some()
+}
+
+/// Detect the compression format of a session file from its extension.
+pub fn compression_of(path: &Path) -> Option<Format> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("gz") => Some(Format::Gzip),
+        Some("zst") => Some(Format::Zstd),
+        _ => None,
+    }
+}
+
+/// Open a session file for line-by-line reading, decompressing on the fly.
+pub fn open_session_file(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    let file = fs::File::open(path)?;
+    decoder(compression_of(path), file)
+}
+
+fn decoder<R: Read + 'static>(format: Option<Format>, reader: R) -> io::Result<Box<dyn BufRead>> {
+    Ok(match format {
+        None => Box::new(BufReader::new(reader)),
+        Some(Format::Gzip) => Box::new(BufReader::new(MultiGzDecoder::new(reader))),
+        Some(Format::Zstd) => Box::new(BufReader::new(zstd::Decoder::new(reader)?)),
+    })
+}
+
+/// Compress a plain `.jsonl` session file in place.
+///
+/// The compressed copy is written to a temporary file, synced, and verified
+/// by decompressing it and comparing against the original before it is
+/// renamed into place. Only then is the original removed. The original
+/// modification time is preserved so age-based filters keep working.
+pub fn compress_file(path: &Path, format: Format) -> Result<PathBuf, String> {
+    if compression_of(path).is_some() {
+        return Err(format!("{} is already compressed", path.display()));
+    }
+    let target = with_suffix(path, &format!(".{}", format.extension()));
+    if target.exists() {
+        return Err(format!("{} already exists", target.display()));
+    }
+
+    let original = fs::read(path).map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+    let mtime = modified_time(path);
+
+    let encoded = match format {
+        Format::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder
+                .write_all(&original)
+                .and_then(|_| encoder.finish())
+                .map_err(|e| format!("gzip compression failed: {e}"))?
+        }
+        Format::Zstd => zstd::encode_all(original.as_slice(), 0)
+            .map_err(|e| format!("zstd compression failed: {e}"))?,
+    };
+
+    let mut roundtrip = Vec::with_capacity(original.len());
+    decoder(Some(format), io::Cursor::new(encoded.clone()))
+        .and_then(|mut r| r.read_to_end(&mut roundtrip))
+        .map_err(|e| format!("verification failed: {e}"))?;
+    if roundtrip != original {
+        return Err("verification failed: decompressed content differs".to_string());
+    }
+
+    replace_file(path, &target, &encoded, mtime)?;
+    Ok(target)
+}
+
+/// Decompress a `.jsonl.gz` or `.jsonl.zst` session file back to plain `.jsonl`.
+///
+/// Needed before resuming, since Claude Code only reads plain session files.
+pub fn decompress_file(path: &Path) -> Result<PathBuf, String> {
+    let format = compression_of(path)
+        .ok_or_else(|| format!("{} is not compressed", path.display()))?;
+    let target = path.with_extension("");
+    if target.exists() {
+        return Err(format!("{} already exists", target.display()));
+    }
+
+    let mtime = modified_time(path);
+    let file = fs::File::open(path).map_err(|e| format!("failed to open {}: {e}", path.display()))?;
+    let mut decoded = Vec::new();
+    decoder(Some(format), file)
+        .and_then(|mut r| r.read_to_end(&mut decoded))
+        .map_err(|e| format!("failed to decompress {}: {e}", path.display()))?;
+
+    replace_file(path, &target, &decoded, mtime)?;
+    Ok(target)
+}
+
+/// Write `contents` to `target` via a synced temporary file, then remove `source`.
+fn replace_file(
+    source: &Path,
+    target: &Path,
+    contents: &[u8],
+    mtime: Option<SystemTime>,
+) -> Result<(), String> {
+    let tmp = with_suffix(target, ".tmp");
+    let write = || -> io::Result<()> {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(contents)?;
+        if let Some(t) = mtime {
+            file.set_modified(t)?;
+        }
+        file.sync_all()?;
+        fs::rename(&tmp, target)
+    };
+    if let Err(e) = write() {
+        let _ = fs::remove_file(&tmp);
+        return Err(format!("failed to write {}: {e}", target.display()));
+    }
+    fs::remove_file(source).map_err(|e| format!("failed to remove {}: {e}", source.display()))
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// A plain session file eligible for compression.
+#[derive(Debug, Clone)]
+pub struct CompressCandidate {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Find uncompressed session files whose last modification is older than `older_than`.
+///
+/// Uses the file modification time rather than the session start, so
+/// sessions that were resumed recently are left alone.
+pub fn find_compress_candidates(
+    claude_home: &Path,
+    older_than: chrono::Duration,
+) -> Vec<CompressCandidate> {
+    let cutoff = SystemTime::now()
+        .checked_sub(older_than.to_std().unwrap_or_default())
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+
+    let mut candidates: Vec<CompressCandidate> =
+        crate::discovery::collect_session_files(&claude_home.join("projects"))
+            .into_iter()
+            .filter(|path| compression_of(path).is_none())
+            .filter_map(|path| {
+                let meta = fs::metadata(&path).ok()?;
+                let modified = meta.modified().ok()?;
+                (modified < cutoff).then_some(CompressCandidate {
+                    path,
+                    size: meta.len(),
+                })
+            })
+            .collect();
+
+    candidates.sort_by(|a, b| a.path.cmp(&b.path));
+    candidates
+}
+
+/// Format a byte count as a short human-readable size (e.g. `1.2 MB`).
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
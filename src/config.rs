@@ -0,0 +1,125 @@
+// User settings and the directories cc-session keeps its own files in
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use toml_edit::DocumentMut;
+
+use crate::columns::{Column, Density, DEFAULT_COLUMNS};
+use crate::remap::PathRule;
+use crate::sort::SortKey;
+
+/// Default number of queries kept in each search history.
+pub const DEFAULT_HISTORY_SIZE: usize = 500;
+
+/// Settings read from `config.toml` in the config directory. Missing keys
+/// take their defaults.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Queries kept in each search history; 0 disables the history.
+    pub history_size: usize,
+    /// Order of the session list.
+    pub sort: SortKey,
+    /// Reverse the sort order.
+    pub sort_reverse: bool,
+    /// Columns of the session list, in order.
+    pub columns: Vec<Column>,
+    /// Lines per session row.
+    pub density: Density,
+    /// Rewrites of recorded project paths, applied in order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub remap: Vec<PathRule>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            history_size: DEFAULT_HISTORY_SIZE,
+            sort: SortKey::default(),
+            sort_reverse: false,
+            columns: DEFAULT_COLUMNS.to_vec(),
+            density: Density::default(),
+            remap: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Load the config from `path`. A missing file gives the defaults.
+    pub fn load_from(path: &Path) -> Result<Config, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).map_err(|e| format!("{}: {e}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(format!("failed to read {}: {e}", path.display())),
+        }
+    }
+
+    /// Load the config from the config directory.
+    pub fn load() -> Result<Config, String> {
+        Config::load_from(&config_path())
+    }
+
+    /// Write the values of `keys` to the config file at `path`, leaving its
+    /// other keys, comments, and formatting as they are. Keys whose value
+    /// is not written out (such as an empty `remap`) are removed.
+    pub fn save_keys(&self, path: &Path, keys: &[&str]) -> Result<(), String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("failed to read {}: {e}", path.display())),
+        };
+        let mut doc: DocumentMut = text.parse().map_err(|e| format!("{}: {e}", path.display()))?;
+        let encoded = toml::to_string(self).map_err(|e| format!("failed to encode config: {e}"))?;
+        let values: DocumentMut = encoded
+            .parse()
+            .map_err(|e| format!("failed to encode config: {e}"))?;
+        for key in keys {
+            match values.get(key) {
+                Some(item) => {
+                    doc.insert(key, item.clone());
+                }
+                None => {
+                    doc.remove(key);
+                }
+            }
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+        }
+        fs::write(path, doc.to_string()).map_err(|e| format!("failed to save {}: {e}", path.display()))
+    }
+}
+
+/// Path of `config.toml`.
+pub fn config_path() -> PathBuf {
+    config_dir().join("config.toml")
+}
+
+/// Directory of `config.toml`.
+///
+/// Checks the `CC_SESSION_CONFIG_DIR` env var first, then falls back to
+/// `cc-session` in the platform config directory.
+pub fn config_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("CC_SESSION_CONFIG_DIR") {
+        return PathBuf::from(dir);
+    }
+    dirs::config_dir()
+        .expect("could not determine config directory")
+        .join("cc-session")
+}
+
+/// Directory of state kept between runs, such as the search histories.
+///
+/// Checks the `CC_SESSION_STATE_DIR` env var first, then falls back to
+/// `cc-session` in the platform state (or local data) directory.
+pub fn state_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("CC_SESSION_STATE_DIR") {
+        return PathBuf::from(dir);
+    }
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .expect("could not determine state directory")
+        .join("cc-session")
+}
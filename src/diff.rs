@@ -0,0 +1,124 @@
+// Unified diffs of Edit and MultiEdit tool calls, embedded in conversation
+// text as fenced code blocks
+
+use similar::TextDiff;
+
+use crate::activity::TextChange;
+
+/// Lines of unchanged context kept around each change.
+const CONTEXT_LINES: usize = 3;
+
+/// Fence language of a diff block. An optional file path follows it,
+/// e.g. ```` ```diff src/main.rs ````.
+pub const DIFF_LANGUAGE: &str = "diff";
+
+/// Line separating two hunks in a diff block.
+pub const HUNK_SEPARATOR: &str = "@@";
+
+/// Kind of a line in a diff block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    Added,
+    Removed,
+    Context,
+    /// Hunk separator or a `\ No newline` marker.
+    Meta,
+}
+
+/// Classify a diff line and return its content without the prefix.
+pub fn classify_diff_line(line: &str) -> (DiffLineKind, &str) {
+    if line.starts_with(HUNK_SEPARATOR) || line.starts_with('\\') {
+        return (DiffLineKind::Meta, line);
+    }
+    match line.chars().next() {
+        Some('+') => (DiffLineKind::Added, &line[1..]),
+        Some('-') => (DiffLineKind::Removed, &line[1..]),
+        Some(' ') => (DiffLineKind::Context, &line[1..]),
+        _ => (DiffLineKind::Context, line),
+    }
+}
+
+/// Diff lines (prefixed with `+`, `-`, or a space) turning `old` into `new`,
+/// with hunks separated by `@@` lines.
+pub fn unified_diff(old: &str, new: &str) -> Vec<String> {
+    let diff = TextDiff::from_lines(old, new);
+    let mut lines = Vec::new();
+    for (i, group) in diff.grouped_ops(CONTEXT_LINES).iter().enumerate() {
+        if i > 0 {
+            lines.push(HUNK_SEPARATOR.to_string());
+        }
+        for op in group {
+            for change in diff.iter_changes(op) {
+                let sign = match change.tag() {
+                    similar::ChangeTag::Delete => '-',
+                    similar::ChangeTag::Insert => '+',
+                    similar::ChangeTag::Equal => ' ',
+                };
+                let value = change.value();
+                lines.push(format!("{sign}{}", value.strip_suffix('\n').unwrap_or(value)));
+            }
+        }
+    }
+    lines
+}
+
+/// A fenced diff block for the changes of one edit call on `path`.
+pub fn edit_diff_block(path: &str, changes: &[TextChange]) -> String {
+    let mut block = format!("```{DIFF_LANGUAGE} {path}\n");
+    for (i, change) in changes.iter().enumerate() {
+        if i > 0 {
+            block.push_str(HUNK_SEPARATOR);
+            block.push('\n');
+        }
+        for line in unified_diff(&change.old, &change.new) {
+            block.push_str(&line);
+            block.push('\n');
+        }
+    }
+    block.push_str("```");
+    block
+}
+
+/// File path of a diff fence line such as ```` ```diff src/main.rs ````.
+pub fn diff_fence_path(fence_line: &str) -> Option<&str> {
+    let info = fence_line.trim().trim_start_matches('`');
+    let (language, path) = info.split_once(char::is_whitespace)?;
+    let path = path.trim();
+    (language == DIFF_LANGUAGE && !path.is_empty()).then_some(path)
+}
+
+/// Whether `line` is a code fence. Every line of a diff block starts with
+/// a diff column, so inside one only a fence at the very start of the line
+/// closes it; a context line such as `` ```rust`` is part of the diff.
+pub fn is_fence(line: &str, in_diff: bool) -> bool {
+    if in_diff {
+        line.starts_with("```")
+    } else {
+        line.trim().starts_with("```")
+    }
+}
+
+/// All diff blocks with a file path in a message text, in order.
+pub fn diff_blocks(text: &str) -> Vec<(String, Vec<String>)> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(String, Vec<String>)> = None;
+    let mut in_other_fence = false;
+    for line in text.lines() {
+        if let Some((_, lines)) = &mut current {
+            if is_fence(line, true) {
+                blocks.extend(current.take());
+            } else {
+                lines.push(line.to_string());
+            }
+        } else if is_fence(line, false) {
+            if in_other_fence {
+                in_other_fence = false;
+            } else if let Some(path) = diff_fence_path(line) {
+                current = Some((path.to_string(), Vec::new()));
+            } else {
+                in_other_fence = true;
+            }
+        }
+    }
+    blocks
+}
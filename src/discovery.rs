@@ -1,17 +1,23 @@
 // Session discovery: scanning ~/.claude/projects/ for session JSONL files
 
 use std::fs;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Utc};
 use rayon::prelude::*;
 
+use crate::activity::text_changes;
+use crate::compress::{is_session_file, open_session_file, session_id_from_path, SESSION_SUFFIXES};
+use crate::diff::edit_diff_block;
 use crate::session::{
     clean_message, clean_message_multiline, is_meta_message, ConversationMessage, MessageRole,
-    Session, SessionFileEntry,
+    Session, SessionFileEntry, StringOrArray,
 };
 
+/// Lines of a session file searched for its first user entry.
+pub const METADATA_LINES: usize = 50;
+
 /// Return the Claude home directory.
 ///
 /// Checks the `CLAUDE_HOME` env var first, then falls back to `~/.claude`.
@@ -31,51 +37,77 @@ pub fn discover_sessions(claude_home: &Path) -> Vec<Session> {
         return Vec::new();
     }
 
-    // Collect all .jsonl file paths
-    let mut jsonl_files: Vec<PathBuf> = Vec::new();
-    if let Ok(entries) = fs::read_dir(&projects_dir) {
+    // Parse files in parallel
+    let mut sessions: Vec<Session> = collect_session_files(&projects_dir)
+        .par_iter()
+        .filter_map(|path| parse_session_file(path))
+        .collect();
+
+    // Sort by timestamp descending (newest first)
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.timestamp));
+    sessions
+}
+
+/// Collect all session files (`.jsonl`, `.jsonl.gz`, `.jsonl.zst`) one level
+/// below `projects_dir`.
+pub fn collect_session_files(projects_dir: &Path) -> Vec<PathBuf> {
+    let mut session_files: Vec<PathBuf> = Vec::new();
+    if let Ok(entries) = fs::read_dir(projects_dir) {
         for entry in entries.flatten() {
             let path = entry.path();
             if path.is_dir() {
                 if let Ok(files) = fs::read_dir(&path) {
                     for file in files.flatten() {
                         let fpath = file.path();
-                        if fpath.extension().and_then(|e| e.to_str()) == Some("jsonl") {
-                            jsonl_files.push(fpath);
+                        if is_session_file(&fpath) {
+                            session_files.push(fpath);
                         }
                     }
                 }
             }
         }
     }
+    session_files
+}
 
-    // Parse files in parallel
-    let mut sessions: Vec<Session> = jsonl_files
-        .par_iter()
-        .filter_map(|path| parse_session_file(path))
-        .collect();
-
-    // Sort by timestamp descending (newest first)
-    sessions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-    sessions
+/// Locate the JSONL file backing a session, plain or compressed.
+///
+/// Looks next to the file the session was discovered from, so sessions
+/// compressed or decompressed since discovery are still found. Sessions
+/// without a recorded file fall back to the project directory derived from
+/// the working directory. Returns the plain `.jsonl` path if no variant
+/// exists on disk.
+pub fn session_file_path(claude_home: &Path, session: &Session) -> PathBuf {
+    let dir = match session.file_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => {
+            let encoded_dir = session.project_path.replace('/', "-");
+            claude_home.join("projects").join(encoded_dir)
+        }
+    };
+    SESSION_SUFFIXES
+        .iter()
+        .map(|suffix| dir.join(format!("{}{}", session.id, suffix)))
+        .find(|path| path.exists())
+        .unwrap_or_else(|| dir.join(format!("{}.jsonl", session.id)))
 }
 
 /// Parse a single JSONL session file and extract the first user message.
-fn parse_session_file(path: &Path) -> Option<Session> {
-    let session_id = path.file_stem()?.to_str()?.to_string();
+pub fn parse_session_file(path: &Path) -> Option<Session> {
+    let session_id = session_id_from_path(path)?;
 
-    let file = fs::File::open(path).ok()?;
-    let reader = BufReader::new(file);
+    let reader = open_session_file(path).ok()?;
 
     // Track metadata from the first user entry (for cwd, branch, timestamp)
     // but keep scanning for a non-meta message to display
     let mut cwd = String::new();
     let mut git_branch: Option<String> = None;
+    let mut version: Option<String> = None;
     let mut timestamp: DateTime<Utc> = Utc::now();
     let mut first_message = String::new();
     let mut found_metadata = false;
 
-    for line in reader.lines().take(50) {
+    for line in reader.lines().take(METADATA_LINES) {
         let line = match line {
             Ok(l) => l,
             Err(_) => continue,
@@ -96,6 +128,7 @@ fn parse_session_file(path: &Path) -> Option<Session> {
         if !found_metadata {
             cwd = entry.cwd.clone().unwrap_or_default();
             git_branch = entry.git_branch.clone();
+            version = entry.version.clone();
             timestamp = entry
                 .timestamp
                 .as_deref()
@@ -139,12 +172,20 @@ fn parse_session_file(path: &Path) -> Option<Session> {
         project_name,
         git_branch,
         timestamp,
+        last_activity: last_modified(path).map_or(timestamp, |t| t.max(timestamp)),
         first_message,
         cwd,
         project_exists,
+        version,
+        file_path: path.to_path_buf(),
     })
 }
 
+/// Modification time of a file, if the platform reports one.
+pub fn last_modified(path: &Path) -> Option<DateTime<Utc>> {
+    fs::metadata(path).and_then(|m| m.modified()).ok().map(DateTime::from)
+}
+
 /// Load all conversation messages (user + assistant) from a session JSONL file.
 ///
 /// Returns messages in chronological order. Skips file-history-snapshot entries,
@@ -152,18 +193,13 @@ fn parse_session_file(path: &Path) -> Option<Session> {
 /// the same role are merged into a single message with paragraphs separated by
 /// blank lines.
 pub fn load_conversation(claude_home: &Path, session: &Session) -> Vec<ConversationMessage> {
-    let encoded_dir = session.project_path.replace('/', "-");
-    let file_path = claude_home
-        .join("projects")
-        .join(&encoded_dir)
-        .join(format!("{}.jsonl", session.id));
-
-    let file = match fs::File::open(&file_path) {
-        Ok(f) => f,
+    let file_path = session_file_path(claude_home, session);
+
+    let reader = match open_session_file(&file_path) {
+        Ok(r) => r,
         Err(_) => return Vec::new(),
     };
 
-    let reader = BufReader::new(file);
     let mut messages: Vec<ConversationMessage> = Vec::new();
 
     for line in reader.lines() {
@@ -171,78 +207,176 @@ pub fn load_conversation(claude_home: &Path, session: &Session) -> Vec<Conversat
             Ok(l) => l,
             Err(_) => continue,
         };
-        if line.trim().is_empty() {
-            continue;
-        }
-        let entry: SessionFileEntry = match serde_json::from_str(&line) {
-            Ok(e) => e,
-            Err(_) => continue,
-        };
+        merge_conversation_line(&mut messages, &line);
+    }
 
-        let role = match entry.entry_type.as_str() {
-            "user" => MessageRole::User,
-            "assistant" => MessageRole::Assistant,
-            _ => continue,
-        };
+    messages
+}
 
-        let raw_text = match &entry.message {
-            Some(m) => m.content.text(),
-            None => continue,
-        };
+/// Parse one JSONL line and merge it into `messages`.
+///
+/// Consecutive messages from the same role are merged into the last message.
+/// Returns the index of the message that was added or extended, or None if
+/// the line carried no displayable content.
+pub fn merge_conversation_line(messages: &mut Vec<ConversationMessage>, line: &str) -> Option<usize> {
+    if line.trim().is_empty() {
+        return None;
+    }
+    let entry: SessionFileEntry = serde_json::from_str(line).ok()?;
 
-        // Skip meta messages for user entries
-        if role == MessageRole::User && is_meta_message(&raw_text) {
-            continue;
-        }
+    let role = match entry.entry_type.as_str() {
+        "user" => MessageRole::User,
+        "assistant" => MessageRole::Assistant,
+        _ => return None,
+    };
 
-        let text = clean_message_multiline(&raw_text);
-        if text.is_empty() {
-            continue;
-        }
+    let content = &entry.message.as_ref()?.content;
+    let raw_text = content.text();
 
-        let timestamp: DateTime<Utc> = entry
-            .timestamp
-            .and_then(|t| t.parse().ok())
-            .unwrap_or_else(Utc::now);
-
-        // Merge consecutive messages from the same role, skipping duplicates
-        if let Some(last) = messages.last_mut() {
-            if last.role == role {
-                // Skip if the text is a duplicate of the last segment
-                // (happens with skill expansions that get repeated in JSONL)
-                if !last.text.ends_with(&text) {
-                    last.text.push_str("\n\n");
-                    last.text.push_str(&text);
-                }
-                // Keep the latest timestamp
-                last.timestamp = timestamp;
-                continue;
+    // Skip meta messages for user entries
+    if role == MessageRole::User && is_meta_message(&raw_text) {
+        return None;
+    }
+
+    let mut text = clean_message_multiline(&raw_text);
+    if role == MessageRole::Assistant {
+        // Diffs are appended after cleaning so code is never stripped of tags
+        for block in edit_diff_blocks(content) {
+            if !text.is_empty() {
+                text.push_str("\n\n");
             }
+            text.push_str(&block);
         }
+    }
+    if text.is_empty() {
+        return None;
+    }
 
-        messages.push(ConversationMessage {
-            role,
-            text,
-            timestamp,
-        });
+    let timestamp: DateTime<Utc> = entry
+        .timestamp
+        .and_then(|t| t.parse().ok())
+        .unwrap_or_else(Utc::now);
+
+    // Merge consecutive messages from the same role, skipping duplicates
+    if let Some(last) = messages.last_mut() {
+        if last.role == role {
+            // Skip if the text is a duplicate of the last segment
+            // (happens with skill expansions that get repeated in JSONL)
+            if !last.text.ends_with(&text) {
+                last.text.push_str("\n\n");
+                last.text.push_str(&text);
+            }
+            // Keep the latest timestamp
+            last.timestamp = timestamp;
+            return Some(messages.len() - 1);
+        }
     }
 
-    messages
+    messages.push(ConversationMessage {
+        role,
+        text,
+        timestamp,
+    });
+    Some(messages.len() - 1)
+}
+
+/// Edit and MultiEdit calls of a message rendered as fenced diff blocks.
+fn edit_diff_blocks(content: &StringOrArray) -> Vec<String> {
+    let StringOrArray::Array(blocks) = content else {
+        return Vec::new();
+    };
+    blocks
+        .iter()
+        .filter(|b| b.block_type == "tool_use" && (b.name == "Edit" || b.name == "MultiEdit"))
+        .filter_map(|b| {
+            let input: serde_json::Value = serde_json::from_str(b.input.as_ref()?.get()).ok()?;
+            let path = input.get("file_path")?.as_str()?;
+            let changes = text_changes(&b.name, &input);
+            Some(edit_diff_block(path, &changes))
+        })
+        .collect()
 }
 
-/// Apply optional time-based and count-based filters to a session list.
+/// Follows a growing plain session file, parsing only appended lines.
 ///
-/// `since` keeps only sessions newer than `Utc::now() - since`.
-/// `last` keeps only the first N sessions (already sorted newest-first).
-pub fn apply_filters(
-    mut sessions: Vec<Session>,
-    since: Option<chrono::Duration>,
-    last: Option<usize>,
-) -> Vec<Session> {
-    if let Some(duration) = since {
-        let cutoff = Utc::now() - duration;
-        sessions.retain(|s| s.timestamp >= cutoff);
+/// Tracks the byte offset just past the last complete line read. While
+/// following, a partially written trailing line is left for the next read.
+#[derive(Debug, Clone)]
+pub struct ConversationTail {
+    pub path: PathBuf,
+    pub offset: u64,
+}
+
+impl ConversationTail {
+    /// Start following `path` from the beginning of the file.
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, offset: 0 }
+    }
+
+    /// Read the whole file into `messages`, including a final line without
+    /// a trailing newline. Following continues from the last complete line.
+    pub fn read_all(&mut self, messages: &mut Vec<ConversationMessage>) {
+        self.offset = 0;
+        messages.clear();
+        self.read_lines(messages, true);
+    }
+
+    /// Read complete lines appended since the last call and merge them into
+    /// `messages`.
+    ///
+    /// Returns the index of the first message that was added or changed, or
+    /// None if nothing displayable was appended. If the file shrank (it was
+    /// rewritten), `messages` is cleared and the file is read from the start.
+    pub fn read_appended(&mut self, messages: &mut Vec<ConversationMessage>) -> Option<usize> {
+        self.read_lines(messages, false)
     }
+
+    /// Merge lines from the offset on. An incomplete trailing line is merged
+    /// only with `include_partial`, and the offset never moves past it.
+    /// Lines that are not valid UTF-8 are skipped.
+    fn read_lines(&mut self, messages: &mut Vec<ConversationMessage>, include_partial: bool) -> Option<usize> {
+        let mut file = fs::File::open(&self.path).ok()?;
+        let len = file.metadata().ok()?.len();
+        if len < self.offset {
+            self.offset = 0;
+            messages.clear();
+        }
+        if len == self.offset {
+            return None;
+        }
+        file.seek(SeekFrom::Start(self.offset)).ok()?;
+
+        let mut reader = BufReader::new(file);
+        let mut first_changed: Option<usize> = None;
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            let n = match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            };
+            let complete = buf.ends_with(b"\n");
+            if !complete && !include_partial {
+                // Incomplete line still being written
+                break;
+            }
+            if complete {
+                self.offset += n as u64;
+            }
+            let Ok(line) = std::str::from_utf8(&buf) else {
+                continue;
+            };
+            if let Some(idx) = merge_conversation_line(messages, line) {
+                first_changed = Some(first_changed.map_or(idx, |f| f.min(idx)));
+            }
+        }
+        first_changed
+    }
+}
+
+/// Keep only the first `last` sessions (already sorted newest-first), or
+/// all of them if `last` is None.
+pub fn apply_filters(mut sessions: Vec<Session>, last: Option<usize>) -> Vec<Session> {
     if let Some(n) = last {
         sessions.truncate(n);
     }
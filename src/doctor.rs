@@ -0,0 +1,182 @@
+// Auditing session files for lines and sessions the rest of the tool skips
+
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use serde_json::Value;
+
+use crate::compress::open_session_file;
+use crate::discovery::{collect_session_files, parse_session_file, METADATA_LINES};
+use crate::session::SessionFileEntry;
+
+/// Entry types written by Claude Code that the tool knows about.
+pub const KNOWN_ENTRY_TYPES: [&str; 7] = [
+    "user",
+    "assistant",
+    "system",
+    "summary",
+    "file-history-snapshot",
+    "queue-operation",
+    "progress",
+];
+
+/// Label for entries without a `type` field.
+pub const MISSING_TYPE: &str = "(missing)";
+
+/// Drop reason of files that never record a user prompt.
+pub const NO_USER_ENTRIES: &str = "no user entries";
+
+/// A line that could not be read or is not valid JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MalformedLine {
+    /// 1-based line number.
+    pub line: usize,
+    pub error: String,
+}
+
+/// What the audit found in one session file.
+#[derive(Debug, Clone, Default)]
+pub struct FileAudit {
+    pub path: PathBuf,
+    pub malformed: Vec<MalformedLine>,
+    /// Counts of entry types not in `KNOWN_ENTRY_TYPES`.
+    pub unknown_types: BTreeMap<String, usize>,
+    pub user_entries: usize,
+    /// Version on the first entry that records one.
+    pub version: Option<String>,
+    /// Why `discover_sessions` leaves the file out, if it does.
+    pub dropped: Option<String>,
+}
+
+impl FileAudit {
+    pub fn is_clean(&self) -> bool {
+        self.malformed.is_empty() && self.unknown_types.is_empty() && self.dropped.is_none()
+    }
+
+    /// Whether the file was readable but holds no user entries at all.
+    pub fn has_no_user_entries(&self) -> bool {
+        self.dropped.as_deref() == Some(NO_USER_ENTRIES)
+    }
+}
+
+/// Audit every session file under `claude_home`, sorted by path.
+pub fn audit_sessions(claude_home: &Path) -> Vec<FileAudit> {
+    let mut audits: Vec<FileAudit> = collect_session_files(&claude_home.join("projects"))
+        .par_iter()
+        .map(|path| audit_file(path))
+        .collect();
+    audits.sort_by(|a, b| a.path.cmp(&b.path));
+    audits
+}
+
+/// Read a session file line by line, recording what other readers skip.
+pub fn audit_file(path: &Path) -> FileAudit {
+    let mut audit = FileAudit {
+        path: path.to_path_buf(),
+        ..FileAudit::default()
+    };
+    let reader = match open_session_file(path) {
+        Ok(reader) => reader,
+        Err(e) => {
+            audit.dropped = Some(format!("cannot be opened: {e}"));
+            return audit;
+        }
+    };
+
+    // Line of the first user entry discovery can read, or of the first one
+    // it cannot with the error, if that comes earlier
+    let mut first_user: Option<Result<usize, (usize, String)>> = None;
+    let mut read_error: Option<String> = None;
+    for (i, line) in reader.lines().enumerate() {
+        let number = i + 1;
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                audit.malformed.push(MalformedLine {
+                    line: number,
+                    error: e.to_string(),
+                });
+                // A broken compressed stream keeps failing; stop here
+                if e.kind() != std::io::ErrorKind::InvalidData {
+                    read_error = Some(format!("read error on line {number}: {e}"));
+                    break;
+                }
+                continue;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: Value = match serde_json::from_str(&line) {
+            Ok(entry) => entry,
+            Err(e) => {
+                audit.malformed.push(MalformedLine {
+                    line: number,
+                    error: e.to_string(),
+                });
+                continue;
+            }
+        };
+
+        let entry_type = entry.get("type").and_then(Value::as_str).unwrap_or(MISSING_TYPE);
+        if !KNOWN_ENTRY_TYPES.contains(&entry_type) {
+            *audit.unknown_types.entry(entry_type.to_string()).or_default() += 1;
+        }
+        if audit.version.is_none() {
+            audit.version = entry.get("version").and_then(Value::as_str).map(str::to_string);
+        }
+        if entry_type == "user" {
+            audit.user_entries += 1;
+            if !matches!(first_user, Some(Ok(_))) {
+                match serde_json::from_value::<SessionFileEntry>(entry) {
+                    Ok(_) => first_user = Some(Ok(number)),
+                    Err(e) if first_user.is_none() => first_user = Some(Err((number, e.to_string()))),
+                    Err(_) => {}
+                }
+            }
+        }
+    }
+
+    if audit.dropped.is_none() && parse_session_file(path).is_none() {
+        audit.dropped = Some(read_error.unwrap_or_else(|| drop_reason(audit.user_entries, first_user)));
+    }
+    audit
+}
+
+/// Why discovery found no usable first user entry.
+fn drop_reason(user_entries: usize, first_user: Option<Result<usize, (usize, String)>>) -> String {
+    match first_user {
+        _ if user_entries == 0 => NO_USER_ENTRIES.to_string(),
+        Some(Err((line, error))) => format!("user entry on line {line} has an unexpected shape: {error}"),
+        Some(Ok(line)) if line > METADATA_LINES => {
+            format!("first user entry is on line {line}, after the first {METADATA_LINES} lines")
+        }
+        _ => "no readable user entry".to_string(),
+    }
+}
+
+/// Number of files per recorded version, newest version first and
+/// `unknown` for files without one last.
+pub fn version_counts(audits: &[FileAudit]) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<Option<(Vec<u64>, &str)>, usize> = BTreeMap::new();
+    for audit in audits {
+        let key = audit.version.as_deref().map(|v| (version_key(v), v));
+        *counts.entry(key).or_default() += 1;
+    }
+    // None sorts first, so reversing puts it last
+    counts
+        .into_iter()
+        .rev()
+        .map(|(key, n)| (key.map_or("unknown", |(_, v)| v).to_string(), n))
+        .collect()
+}
+
+/// Numeric parts of a version such as `2.1.50`, for ordering.
+fn version_key(version: &str) -> Vec<u64> {
+    version
+        .split(['.', '-'])
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
@@ -34,3 +34,25 @@ pub fn filter_sessions(sessions: &[Session], query: &str) -> Vec<usize> {
         })
         .collect()
 }
+
+/// How well a session's metadata matches `query`: a match in the project
+/// name counts most, then the branch, then the first prompt. 0 if nothing
+/// matches or the query is empty.
+pub fn relevance(session: &Session, query: &str) -> u32 {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return 0;
+    }
+    let matches = |text: &str| text.to_lowercase().contains(&query);
+    let mut score = 0;
+    if matches(&session.project_name) {
+        score += 4;
+    }
+    if session.git_branch.as_deref().is_some_and(matches) {
+        score += 2;
+    }
+    if matches(&session.first_message) {
+        score += 1;
+    }
+    score
+}
@@ -0,0 +1,224 @@
+// Local git history lookups for sessions
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::activity::scan_session_activity;
+use crate::discovery::session_file_path;
+use crate::session::Session;
+
+/// Field separator in the `git log` format (ASCII unit separator).
+const FIELD_SEP: char = '\u{1f}';
+/// Record separator in the `git log` format (ASCII record separator).
+const RECORD_SEP: char = '\u{1e}';
+
+/// A commit from `git log`.
+#[derive(Debug, Clone)]
+pub struct Commit {
+    pub short_hash: String,
+    pub author: String,
+    pub timestamp: DateTime<Utc>,
+    pub subject: String,
+    /// Changed files, relative to the repository root.
+    pub files: Vec<String>,
+    /// Whether the commit changed a file the session edited.
+    pub touches_session_edits: bool,
+}
+
+/// Run git in `repo` and return its stdout.
+fn git(repo: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .map_err(|e| format!("failed to run git: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Root directory of the git repository containing `path`.
+pub fn repo_root(path: &Path) -> Result<PathBuf, String> {
+    let out = git(path, &["rev-parse", "--show-toplevel"])?;
+    Ok(PathBuf::from(out.trim()))
+}
+
+/// URL of the `origin` remote of the repository at `repo`, if it has one.
+///
+/// Reads `.git/config` directly, which is much faster than running git for
+/// many repositories; worktrees and submodules fall back to git.
+pub fn origin_url(repo: &Path) -> Option<String> {
+    let url = match std::fs::read_to_string(repo.join(".git/config")) {
+        Ok(config) => config
+            .lines()
+            .map(str::trim)
+            .skip_while(|line| *line != "[remote \"origin\"]")
+            .skip(1)
+            .take_while(|line| !line.starts_with('['))
+            .find_map(|line| {
+                let (key, value) = line.split_once('=')?;
+                (key.trim() == "url").then(|| value.trim().to_string())
+            })?,
+        Err(_) => git(repo, &["config", "--get", "remote.origin.url"]).ok()?.trim().to_string(),
+    };
+    (!url.is_empty()).then_some(url)
+}
+
+/// Parse output of `git log --name-only` in the format used by `commits_between`.
+pub fn parse_git_log(output: &str) -> Vec<Commit> {
+    output
+        .split(RECORD_SEP)
+        .filter_map(|record| {
+            let mut lines = record.trim_start_matches('\n').lines();
+            let header = lines.next()?;
+            let fields: Vec<&str> = header.split(FIELD_SEP).collect();
+            let [short_hash, author, date, subject] = fields[..] else {
+                return None;
+            };
+            let timestamp = DateTime::parse_from_rfc3339(date).ok()?.with_timezone(&Utc);
+            let files = lines
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(str::to_string)
+                .collect();
+            Some(Commit {
+                short_hash: short_hash.to_string(),
+                author: author.to_string(),
+                timestamp,
+                subject: subject.to_string(),
+                files,
+                touches_session_edits: false,
+            })
+        })
+        .collect()
+}
+
+/// Commits on `branch` (or HEAD) in the repository at `repo` committed
+/// between `since` and `until`, newest first. A branch that no longer
+/// exists falls back to HEAD.
+///
+/// Commits that changed any of `edited_files` (absolute paths) are flagged
+/// with `touches_session_edits`.
+pub fn commits_between(
+    repo: &Path,
+    branch: Option<&str>,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    edited_files: &BTreeSet<String>,
+) -> Result<Vec<Commit>, String> {
+    let root = repo_root(repo)?;
+    let format = format!("--format={RECORD_SEP}%h{FIELD_SEP}%an{FIELD_SEP}%cI{FIELD_SEP}%s");
+    let since = format!("--since={}", since.to_rfc3339_opts(SecondsFormat::Secs, true));
+    let until = format!("--until={}", until.to_rfc3339_opts(SecondsFormat::Secs, true));
+    let revision = branch
+        .filter(|b| !b.is_empty())
+        .filter(|b| git(&root, &["rev-parse", "--verify", "--quiet", &format!("{b}^{{commit}}")]).is_ok())
+        .unwrap_or("HEAD");
+    let out = git(
+        &root,
+        &["log", &format, "--name-only", &since, &until, revision, "--"],
+    )?;
+
+    // Session paths may go through symlinks; git reports the resolved root
+    let edited: BTreeSet<PathBuf> = edited_files
+        .iter()
+        .filter_map(|f| {
+            let path = Path::new(f);
+            if let Ok(relative) = path.strip_prefix(&root) {
+                return Some(relative.to_path_buf());
+            }
+            let parent = path.parent()?.canonicalize().ok()?;
+            let relative = parent.strip_prefix(&root).ok()?;
+            Some(relative.join(path.file_name()?))
+        })
+        .collect();
+
+    let mut commits = parse_git_log(&out);
+    for commit in &mut commits {
+        commit.touches_session_edits = commit.files.iter().any(|f| edited.contains(Path::new(f)));
+    }
+    Ok(commits)
+}
+
+/// Commits that landed in the session's project on its branch while the
+/// session ran, flagging those that touch files the session edited.
+pub fn session_commits(claude_home: &Path, session: &Session) -> Result<Vec<Commit>, String> {
+    let project = Path::new(&session.project_path);
+    if !project.is_dir() {
+        return Err(format!("{} no longer exists", session.project_path));
+    }
+    let activity = scan_session_activity(&session_file_path(claude_home, session));
+    let (Some(since), Some(until)) = (activity.first_timestamp, activity.last_timestamp) else {
+        return Ok(Vec::new());
+    };
+    commits_between(
+        project,
+        session.git_branch.as_deref(),
+        since,
+        until,
+        &activity.edited_files(),
+    )
+}
+
+/// Result of a commit lookup: the session ID and its commits.
+type CommitsResult = (String, Result<Vec<Commit>, String>);
+
+/// Looks up session commits on a background thread, since `git log` can
+/// take a while on large repositories.
+pub struct CommitsLoader {
+    requests: mpsc::Sender<Session>,
+    results: mpsc::Receiver<CommitsResult>,
+    pending: Option<String>,
+}
+
+impl CommitsLoader {
+    pub fn new(claude_home: PathBuf) -> Self {
+        let (request_tx, request_rx) = mpsc::channel::<Session>();
+        let (result_tx, result_rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            while let Ok(session) = request_rx.recv() {
+                let commits = session_commits(&claude_home, &session);
+                if result_tx.send((session.id, commits)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            requests: request_tx,
+            results: result_rx,
+            pending: None,
+        }
+    }
+
+    /// Look up the commits of `session` unless that lookup is running.
+    pub fn request(&mut self, session: &Session) {
+        if self.pending.as_deref() == Some(session.id.as_str()) {
+            return;
+        }
+        if self.requests.send(session.clone()).is_ok() {
+            self.pending = Some(session.id.clone());
+        }
+    }
+
+    /// Lookups finished since the last poll.
+    pub fn poll(&mut self) -> Vec<CommitsResult> {
+        let mut received = Vec::new();
+        while let Ok((id, commits)) = self.results.try_recv() {
+            if self.pending.as_ref() == Some(&id) {
+                self.pending = None;
+            }
+            received.push((id, commits));
+        }
+        received
+    }
+}
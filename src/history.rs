@@ -0,0 +1,87 @@
+// Search query histories kept between runs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Queries entered in one search field, oldest first, without duplicates.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    /// File the history is saved to, or None to keep it in memory.
+    path: Option<PathBuf>,
+    entries: Vec<String>,
+    limit: usize,
+}
+
+impl History {
+    /// An empty history keeping up to `limit` queries in memory only.
+    pub fn new(limit: usize) -> Self {
+        History {
+            path: None,
+            entries: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Load the history saved at `path`, one query per line. A missing or
+    /// unreadable file gives an empty history that is saved there.
+    pub fn load(path: &Path, limit: usize) -> Self {
+        let mut history = History {
+            path: Some(path.to_path_buf()),
+            entries: Vec::new(),
+            limit,
+        };
+        if let Ok(text) = fs::read_to_string(path) {
+            for line in text.lines() {
+                history.push(line);
+            }
+        }
+        history
+    }
+
+    /// Queries, oldest first.
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Record `query` as the newest entry, dropping an earlier copy and
+    /// the oldest entries beyond the limit, and save the history.
+    pub fn add(&mut self, query: &str) -> Result<(), String> {
+        if !self.push(query) {
+            return Ok(());
+        }
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+        }
+        let mut text = self.entries.join("\n");
+        text.push('\n');
+        fs::write(path, text).map_err(|e| format!("failed to save {}: {e}", path.display()))
+    }
+
+    /// Newest entry before index `before` containing `needle`, ignoring case.
+    pub fn find(&self, needle: &str, before: usize) -> Option<usize> {
+        let needle = needle.to_lowercase();
+        self.entries[..before.min(self.entries.len())]
+            .iter()
+            .rposition(|e| e.to_lowercase().contains(&needle))
+    }
+
+    /// Add `query` in memory; returns whether the entries changed.
+    fn push(&mut self, query: &str) -> bool {
+        let query = query.trim();
+        if query.is_empty() || query.contains('\n') || self.limit == 0 {
+            return false;
+        }
+        if self.entries.last().is_some_and(|e| e == query) {
+            return false;
+        }
+        self.entries.retain(|e| e != query);
+        self.entries.push(query.to_string());
+        let excess = self.entries.len().saturating_sub(self.limit);
+        self.entries.drain(..excess);
+        true
+    }
+}
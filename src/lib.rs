@@ -1,7 +1,24 @@
+pub mod activity;
+pub mod blame;
 pub mod clipboard;
+pub mod columns;
+pub mod compress;
+pub mod config;
+pub mod diff;
 pub mod discovery;
+pub mod doctor;
 pub mod filter;
+pub mod git;
+pub mod history;
+pub mod live;
+pub mod open;
+pub mod prune;
+pub mod remap;
 pub mod search;
 pub mod session;
+pub mod sort;
 pub mod theme;
+pub mod timespec;
 pub mod tui;
+pub mod views;
+pub mod watch;
@@ -0,0 +1,267 @@
+// Detection of sessions that are currently running in another terminal
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime};
+
+use crate::compress::is_session_file;
+use crate::discovery::session_file_path;
+use crate::session::Session;
+
+/// A session file written to within this window counts as running,
+/// even if no matching process could be found.
+const RECENT_WRITE: Duration = Duration::from_secs(60);
+
+/// A running `claude` process found in `/proc`.
+#[derive(Debug, Clone)]
+pub struct ClaudeProcess {
+    pub pid: u32,
+    pub cwd: PathBuf,
+    /// Session ID passed on the command line (`--resume <id>`), if any.
+    pub session_id: Option<String>,
+    /// tmux pane (e.g. `%3`) the process runs in, from its `TMUX_PANE`.
+    pub tmux_pane: Option<String>,
+    /// When the process started, if known.
+    pub started: Option<SystemTime>,
+    /// Session files the process holds open.
+    pub open_files: Vec<PathBuf>,
+}
+
+/// A session believed to be open in another terminal.
+#[derive(Debug, Clone)]
+pub struct LiveSession {
+    pub pid: Option<u32>,
+    pub tmux_pane: Option<String>,
+}
+
+/// Check whether a process command line belongs to Claude Code.
+///
+/// Matches the native `claude` binary as well as `node .../claude`.
+pub fn is_claude_command(comm: &str, args: &[String]) -> bool {
+    if comm == "claude" {
+        return true;
+    }
+    args.iter().take(2).any(|arg| {
+        Path::new(arg)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n == "claude")
+    })
+}
+
+/// Extract the session ID from `claude` arguments such as
+/// `-r <id>`, `--resume <id>`, `--resume=<id>`, or `--session-id <id>`.
+pub fn session_id_from_args(args: &[String]) -> Option<String> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-r" | "--resume" | "--session-id" => {
+                if let Some(id) = iter.next().filter(|v| !v.starts_with('-')) {
+                    return Some(id.clone());
+                }
+            }
+            _ => {
+                if let Some(id) = arg
+                    .strip_prefix("--resume=")
+                    .or_else(|| arg.strip_prefix("--session-id="))
+                {
+                    return Some(id.to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Scan `/proc` for running Claude Code processes.
+///
+/// Returns an empty list on systems without `/proc` or when process
+/// details are not readable (e.g. processes of other users).
+pub fn find_claude_processes() -> Vec<ClaudeProcess> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    let own_pid = std::process::id();
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            if pid == own_pid {
+                return None;
+            }
+            let dir = entry.path();
+            let comm = fs::read_to_string(dir.join("comm")).unwrap_or_default();
+            let args: Vec<String> = fs::read(dir.join("cmdline"))
+                .ok()?
+                .split(|&b| b == 0)
+                .filter(|a| !a.is_empty())
+                .map(|a| String::from_utf8_lossy(a).into_owned())
+                .collect();
+            if !is_claude_command(comm.trim(), &args) {
+                return None;
+            }
+            let cwd = fs::read_link(dir.join("cwd")).ok()?;
+            let tmux_pane = fs::read(dir.join("environ")).ok().and_then(|env| {
+                env.split(|&b| b == 0)
+                    .find_map(|var| var.strip_prefix(b"TMUX_PANE="))
+                    .map(|v| String::from_utf8_lossy(v).into_owned())
+            });
+            // The /proc entry of a process is created when it starts
+            let started = fs::metadata(&dir).and_then(|m| m.modified()).ok();
+            let open_files = fs::read_dir(dir.join("fd"))
+                .map(|fds| {
+                    fds.flatten()
+                        .filter_map(|fd| fs::read_link(fd.path()).ok())
+                        .filter(|target| is_session_file(target))
+                        .collect()
+                })
+                .unwrap_or_default();
+            Some(ClaudeProcess {
+                pid,
+                cwd,
+                session_id: session_id_from_args(&args),
+                tmux_pane,
+                started,
+                open_files,
+            })
+        })
+        .collect()
+}
+
+/// Determine which sessions are currently running, keyed by session ID.
+///
+/// A session is running if a `claude` process was started with its ID or
+/// holds its file open, or if it is the most recently written session in
+/// the working directory of a `claude` process and was written to after the
+/// process started. A session whose file was written to within the last
+/// minute also counts as running.
+pub fn detect_live_sessions(
+    claude_home: &Path,
+    sessions: &[Session],
+    processes: &[ClaudeProcess],
+) -> HashMap<String, LiveSession> {
+    let now = SystemTime::now();
+    let paths: Vec<PathBuf> = sessions.iter().map(|s| session_file_path(claude_home, s)).collect();
+    let mtimes: Vec<Option<SystemTime>> = paths
+        .iter()
+        .map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect();
+
+    let mut live = HashMap::new();
+
+    for process in processes {
+        let matched = match &process.session_id {
+            Some(id) => sessions.iter().position(|s| &s.id == id),
+            None => paths
+                .iter()
+                .position(|path| process.open_files.contains(path))
+                .or_else(|| {
+                    // A process that has not written yet owns no session
+                    let started = process.started?;
+                    sessions
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, s)| {
+                            Path::new(&s.cwd) == process.cwd && mtimes[*i].is_some_and(|t| t >= started)
+                        })
+                        .max_by_key(|(i, _)| mtimes[*i])
+                        .map(|(i, _)| i)
+                }),
+        };
+        if let Some(i) = matched {
+            live.insert(
+                sessions[i].id.clone(),
+                LiveSession {
+                    pid: Some(process.pid),
+                    tmux_pane: process.tmux_pane.clone(),
+                },
+            );
+        }
+    }
+
+    for (session, mtime) in sessions.iter().zip(&mtimes) {
+        let recent = mtime
+            .and_then(|t| now.duration_since(t).ok())
+            .is_some_and(|age| age < RECENT_WRITE);
+        if recent {
+            live.entry(session.id.clone()).or_insert(LiveSession {
+                pid: None,
+                tmux_pane: None,
+            });
+        }
+    }
+
+    live
+}
+
+/// Detects running sessions on a background thread, since scanning `/proc`
+/// and every session file is too slow for the UI thread.
+pub struct LiveScanner {
+    requests: mpsc::Sender<Vec<Session>>,
+    results: mpsc::Receiver<HashMap<String, LiveSession>>,
+    pending: bool,
+}
+
+impl LiveScanner {
+    pub fn new(claude_home: PathBuf) -> Self {
+        let (request_tx, request_rx) = mpsc::channel::<Vec<Session>>();
+        let (result_tx, result_rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            while let Ok(sessions) = request_rx.recv() {
+                let processes = find_claude_processes();
+                let live = detect_live_sessions(&claude_home, &sessions, &processes);
+                if result_tx.send(live).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            requests: request_tx,
+            results: result_rx,
+            pending: false,
+        }
+    }
+
+    /// Start a scan of `sessions` unless one is already running.
+    pub fn request(&mut self, sessions: &[Session]) {
+        if !self.pending && self.requests.send(sessions.to_vec()).is_ok() {
+            self.pending = true;
+        }
+    }
+
+    /// Result of the finished scan, if one finished since the last poll.
+    pub fn poll(&mut self) -> Option<HashMap<String, LiveSession>> {
+        let live = self.results.try_recv().ok()?;
+        self.pending = false;
+        Some(live)
+    }
+}
+
+/// Switch the current tmux client to the given pane.
+pub fn switch_to_tmux_pane(pane: &str) -> Result<(), String> {
+    if std::env::var_os("TMUX").is_none() {
+        return Err(format!("not running inside tmux (session is in pane {pane})"));
+    }
+    for args in [
+        ["switch-client", "-t", pane],
+        ["select-window", "-t", pane],
+        ["select-pane", "-t", pane],
+    ] {
+        let output = Command::new("tmux")
+            .args(args)
+            .output()
+            .map_err(|e| format!("failed to run tmux: {e}"))?;
+        if !output.status.success() {
+            return Err(format!(
+                "tmux {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    }
+    Ok(())
+}
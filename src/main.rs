@@ -1,14 +1,35 @@
+mod activity;
+mod blame;
 mod clipboard;
+mod columns;
+mod compress;
+mod config;
+mod diff;
 mod discovery;
+mod doctor;
 mod filter;
+mod git;
+mod history;
+mod live;
+mod open;
+mod prune;
+mod remap;
 mod search;
 mod session;
+mod sort;
 mod theme;
+mod timespec;
 mod tui;
+mod views;
+mod watch;
 
-use clap::Parser;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand};
 
 use discovery::{apply_filters, discover_sessions, get_claude_home};
+use timespec::{parse_duration, parse_time};
 
 /// Fast CLI tool for finding and resuming Claude Code sessions.
 #[derive(Parser, Debug)]
@@ -18,14 +39,38 @@ use discovery::{apply_filters, discover_sessions, get_claude_home};
     about
 )]
 struct Cli {
-    /// Only show sessions newer than duration (e.g. 7d, 2w, 1m)
-    #[arg(long)]
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Only show sessions since a duration ago or a local date/time
+    /// (e.g. 12h, 1w3d, today, yesterday, this-week, 2025-03-01)
+    #[arg(long, value_name = "WHEN")]
     since: Option<String>,
 
-    /// Show at most N sessions
+    /// Only show sessions up to WHEN; dates include the whole day
+    #[arg(long, value_name = "WHEN")]
+    until: Option<String>,
+
+    /// Only show sessions before WHEN; dates exclude that day
+    #[arg(long, value_name = "WHEN")]
+    before: Option<String>,
+
+    /// Show at most N sessions, the newest within the other filters
     #[arg(long)]
     last: Option<usize>,
 
+    /// Only show sessions whose project name or path contains TEXT
+    #[arg(long, value_name = "TEXT")]
+    project: Option<String>,
+
+    /// Only show sessions on a git branch containing TEXT
+    #[arg(long, value_name = "TEXT")]
+    branch: Option<String>,
+
+    /// Start with a saved view (filter, project, branch, time window, and sort)
+    #[arg(long, value_name = "NAME")]
+    view: Option<String>,
+
     /// Force light color theme
     #[arg(long = "light", conflicts_with = "dark")]
     light: bool,
@@ -33,30 +78,87 @@ struct Cli {
     /// Force dark color theme
     #[arg(long = "dark", conflicts_with = "light")]
     dark: bool,
+
+    /// Show a preview of the selected session next to the list
+    #[arg(long)]
+    preview: bool,
+
+    /// Enable mouse clicks and scrolling (disables terminal text selection)
+    #[arg(long)]
+    mouse: bool,
+
+    /// Collapse conversation messages longer than N lines
+    #[arg(long, value_name = "N")]
+    collapse: Option<usize>,
 }
 
-/// Parse a human-friendly duration string into a chrono::Duration.
-///
-/// Supported suffixes: `d` (days), `w` (weeks), `m` (30-day months).
-fn parse_duration(s: &str) -> Result<chrono::Duration, String> {
-    let s = s.trim();
-    if s.is_empty() {
-        return Err("empty duration string".to_string());
-    }
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Compress old session files in place (still browsable, decompressed on resume)
+    Compress {
+        /// Only compress sessions not modified within this duration (e.g. 90d)
+        #[arg(long, default_value = "90d")]
+        older_than: String,
 
-    let (num_str, suffix) = s.split_at(s.len() - 1);
-    let num: i64 = num_str
-        .parse()
-        .map_err(|_| format!("invalid number in duration: {num_str:?}"))?;
+        /// Compression format
+        #[arg(long, value_enum, default_value_t = compress::Format::Zstd)]
+        format: compress::Format,
 
-    match suffix {
-        "d" => Ok(chrono::Duration::days(num)),
-        "w" => Ok(chrono::Duration::weeks(num)),
-        "m" => Ok(chrono::Duration::days(num * 30)),
-        other => Err(format!(
-            "unknown duration suffix: {other:?} (expected d, w, or m)"
-        )),
-    }
+        /// Only list the files that would be compressed
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List sessions that modified a file, most recent first
+    Files {
+        /// File to look up (relative paths are resolved against the current directory)
+        path: PathBuf,
+
+        /// Also list sessions that only read the file
+        #[arg(long)]
+        reads: bool,
+    },
+    /// Show which session wrote each line of a file
+    Blame {
+        /// File to blame, optionally with a line number (e.g. src/main.rs:42)
+        target: String,
+    },
+    /// Audit session files for malformed lines and sessions missing from the list
+    Doctor,
+    /// Report dead, empty, short or old sessions, then archive or delete them
+    /// with their todos and subagent transcripts (defaults to --dead --empty)
+    Prune {
+        /// Sessions whose project directory no longer exists
+        #[arg(long)]
+        dead: bool,
+
+        /// Sessions without a real user prompt
+        #[arg(long)]
+        empty: bool,
+
+        /// Sessions with fewer than N prompts
+        #[arg(long, value_name = "N")]
+        min_turns: Option<usize>,
+
+        /// Sessions not used within this duration (e.g. 180d)
+        #[arg(long, value_name = "DURATION")]
+        older_than: Option<String>,
+
+        /// Move the sessions into the archive directory
+        #[arg(long, conflicts_with = "delete")]
+        archive: bool,
+
+        /// Delete the sessions
+        #[arg(long)]
+        delete: bool,
+
+        /// Where --archive moves sessions [default: <state dir>/archive]
+        #[arg(long, value_name = "DIR", requires = "archive")]
+        archive_dir: Option<PathBuf>,
+
+        /// Do not ask for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
 }
 
 fn main() {
@@ -73,17 +175,100 @@ fn main() {
         std::process::exit(2);
     }
 
-    let sessions = discover_sessions(&claude_home);
+    if let Some(command) = cli.command {
+        let code = match command {
+            Command::Compress {
+                older_than,
+                format,
+                dry_run,
+            } => {
+                let age = parse_duration(&older_than).unwrap_or_else(|e| {
+                    eprintln!("Invalid --older-than value: {e}");
+                    std::process::exit(1);
+                });
+                run_compress(&claude_home, age, &older_than, format, dry_run)
+            }
+            Command::Files { path, reads } => run_files(&claude_home, &path, reads),
+            Command::Blame { target } => run_blame(&claude_home, &target),
+            Command::Doctor => run_doctor(&claude_home),
+            Command::Prune {
+                dead,
+                empty,
+                min_turns,
+                older_than,
+                archive,
+                delete,
+                archive_dir,
+                yes,
+            } => {
+                let older_than = older_than.map(|d| {
+                    parse_duration(&d).unwrap_or_else(|e| {
+                        eprintln!("Invalid --older-than value: {e}");
+                        std::process::exit(1);
+                    })
+                });
+                let mut criteria = prune::PruneCriteria {
+                    dead,
+                    empty,
+                    min_turns,
+                    older_than: older_than.map(|d| chrono::Utc::now() - d),
+                };
+                if !dead && !empty && min_turns.is_none() && older_than.is_none() {
+                    criteria.dead = true;
+                    criteria.empty = true;
+                }
+                let action = if delete {
+                    PruneAction::Delete
+                } else if archive {
+                    PruneAction::Archive(archive_dir.unwrap_or_else(|| config::state_dir().join("archive")))
+                } else {
+                    PruneAction::Report
+                };
+                run_prune(&claude_home, &criteria, action, yes)
+            }
+        };
+        std::process::exit(code);
+    }
 
-    // Apply --since filter
-    let since_duration = cli.since.map(|s| {
-        parse_duration(&s).unwrap_or_else(|e| {
-            eprintln!("Invalid --since value: {e}");
-            std::process::exit(1);
-        })
-    });
+    let mut sessions = discover_sessions(&claude_home);
 
-    let sessions = apply_filters(sessions, since_duration, cli.last);
+    let now = chrono::Local::now();
+    for (flag, value) in [("since", &cli.since), ("until", &cli.until), ("before", &cli.before)] {
+        if let Some(value) = value {
+            if let Err(e) = parse_time(value, now) {
+                eprintln!("Invalid --{flag} value: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+    let views = views::load_views(&views::views_path()).unwrap_or_else(|e| {
+        eprintln!("Invalid views: {e}");
+        std::process::exit(1);
+    });
+    // Start from the named view; explicit flags override its fields
+    let mut view = match &cli.view {
+        Some(name) => views.get(name).cloned().unwrap_or_else(|| {
+            let names: Vec<&str> = views.keys().map(String::as_str).collect();
+            eprintln!("No view named {name:?} (saved views: {})", names.join(", "));
+            std::process::exit(1);
+        }),
+        None => views::View::default(),
+    };
+    if cli.since.is_some() {
+        view.scope.since = cli.since.clone();
+    }
+    if cli.until.is_some() {
+        view.scope.until = cli.until.clone();
+    }
+    if cli.before.is_some() {
+        view.scope.before = cli.before.clone();
+    }
+    if cli.project.is_some() {
+        view.scope.project = cli.project.clone();
+    }
+    if cli.branch.is_some() {
+        view.scope.branch = cli.branch.clone();
+    }
 
     // Determine color theme
     let theme = if cli.light {
@@ -94,9 +279,415 @@ fn main() {
         theme::Theme::detect()
     };
 
+    let config = config::Config::load().unwrap_or_else(|e| {
+        eprintln!("Invalid config: {e}");
+        std::process::exit(1);
+    });
+    let remapper = remap::PathRemapper::new(&config.remap).unwrap_or_else(|e| {
+        eprintln!("Invalid config: {e}");
+        std::process::exit(1);
+    });
+    for session in &mut sessions {
+        remapper.remap_session(session);
+    }
+    // --last counts the newest sessions within the scope, not overall
+    if cli.last.is_some() {
+        let scope = view.scope.resolve(now);
+        sessions.retain(|s| scope.contains(s));
+    }
+    let sessions = apply_filters(sessions, cli.last);
+
+    let (sort, sort_reverse) = view.sort_order((config.sort, config.sort_reverse));
+
     // Interactive TUI
-    if let Err(e) = tui::run(sessions, theme) {
+    let options = tui::TuiOptions {
+        preview: cli.preview,
+        mouse: cli.mouse,
+        collapse_after: cli.collapse.filter(|&n| n > 0),
+        history_size: config.history_size,
+        sort,
+        sort_reverse,
+        columns: config.columns,
+        density: config.density,
+        remap: config.remap,
+        last: cli.last,
+        view,
+        views,
+    };
+    if let Err(e) = tui::run(sessions, theme, options) {
         eprintln!("TUI error: {e}");
         std::process::exit(1);
     }
 }
+
+/// Compress session files older than `older_than` (given as `age_text` on
+/// the command line), printing one line per file.
+fn run_compress(
+    claude_home: &Path,
+    older_than: chrono::Duration,
+    age_text: &str,
+    format: compress::Format,
+    dry_run: bool,
+) -> i32 {
+    let candidates = compress::find_compress_candidates(claude_home, older_than);
+    if candidates.is_empty() {
+        println!("No uncompressed sessions older than {age_text}.");
+        return 0;
+    }
+
+    let total: u64 = candidates.iter().map(|c| c.size).sum();
+    if dry_run {
+        for c in &candidates {
+            println!("{}  {}", compress::format_size(c.size), c.path.display());
+        }
+        println!(
+            "Would compress {} sessions ({}).",
+            candidates.len(),
+            compress::format_size(total)
+        );
+        return 0;
+    }
+
+    let mut saved: u64 = 0;
+    let mut failed = 0;
+    for c in &candidates {
+        match compress::compress_file(&c.path, format) {
+            Ok(target) => {
+                let new_size = std::fs::metadata(&target).map(|m| m.len()).unwrap_or(0);
+                saved += c.size.saturating_sub(new_size);
+                println!(
+                    "{} -> {}  {}",
+                    compress::format_size(c.size),
+                    compress::format_size(new_size),
+                    target.display()
+                );
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!("skipped {}: {e}", c.path.display());
+            }
+        }
+    }
+
+    println!(
+        "Compressed {} of {} sessions, saved {}.",
+        candidates.len() - failed,
+        candidates.len(),
+        compress::format_size(saved)
+    );
+    if failed > 0 {
+        1
+    } else {
+        0
+    }
+}
+
+/// Report problems in session files; exits with 1 if any were found.
+fn run_doctor(claude_home: &Path) -> i32 {
+    let plural = |n: usize, word: &str| format!("{n} {word}{}", if n == 1 { "" } else { "s" });
+    let audits = doctor::audit_sessions(claude_home);
+    println!("Checked {}.", plural(audits.len(), "session file"));
+
+    let malformed: usize = audits.iter().map(|a| a.malformed.len()).sum();
+    if malformed > 0 {
+        println!("\nMalformed lines ({malformed}):");
+        for audit in &audits {
+            for m in &audit.malformed {
+                println!("  {}:{}: {}", audit.path.display(), m.line, m.error);
+            }
+        }
+    }
+
+    let mut unknown: std::collections::BTreeMap<&str, (usize, usize)> = std::collections::BTreeMap::new();
+    for audit in &audits {
+        for (entry_type, n) in &audit.unknown_types {
+            let counts = unknown.entry(entry_type).or_default();
+            counts.0 += n;
+            counts.1 += 1;
+        }
+    }
+    if !unknown.is_empty() {
+        println!("\nUnknown entry types:");
+        for (entry_type, (entries, files)) in &unknown {
+            println!("  {entry_type}: {} in {}", plural(*entries, "line"), plural(*files, "file"));
+        }
+    }
+
+    let no_user: Vec<&doctor::FileAudit> = audits.iter().filter(|a| a.has_no_user_entries()).collect();
+    if !no_user.is_empty() {
+        println!("\nFiles with no user entries ({}):", no_user.len());
+        for audit in &no_user {
+            println!("  {}", audit.path.display());
+        }
+    }
+
+    let dropped: Vec<&doctor::FileAudit> = audits.iter().filter(|a| a.dropped.is_some() && !a.has_no_user_entries()).collect();
+    if !dropped.is_empty() {
+        println!("\nSessions missing from the list ({}):", dropped.len());
+        for audit in &dropped {
+            println!("  {}: {}", audit.path.display(), audit.dropped.as_deref().unwrap_or_default());
+        }
+    }
+
+    println!("\nVersions:");
+    for (version, files) in doctor::version_counts(&audits) {
+        println!("  {version:<12} {}", plural(files, "file"));
+    }
+
+    if audits.iter().all(doctor::FileAudit::is_clean) {
+        println!("\nNo problems found.");
+        0
+    } else {
+        1
+    }
+}
+
+/// What `prune` does with the sessions it finds.
+enum PruneAction {
+    Report,
+    Archive(PathBuf),
+    Delete,
+}
+
+/// Report sessions matching `criteria` grouped by reason, then archive or
+/// delete them once confirmed.
+fn run_prune(claude_home: &Path, criteria: &prune::PruneCriteria, action: PruneAction, yes: bool) -> i32 {
+    let config = match config::Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid config: {e}");
+            return 1;
+        }
+    };
+    let remapper = match remap::PathRemapper::new(&config.remap) {
+        Ok(remapper) => remapper,
+        Err(e) => {
+            eprintln!("Invalid config: {e}");
+            return 1;
+        }
+    };
+
+    let candidates = prune::find_prune_candidates(claude_home, criteria, &remapper);
+    if candidates.is_empty() {
+        println!("No sessions to prune.");
+        return 0;
+    }
+
+    for group in candidates.chunk_by(|a, b| a.reason == b.reason) {
+        let size: u64 = group.iter().map(|c| c.size).sum();
+        println!(
+            "{} ({} sessions, {})",
+            group[0].reason.label(),
+            group.len(),
+            compress::format_size(size)
+        );
+        for c in group {
+            let project = if c.project.is_empty() { "-" } else { c.project.as_str() };
+            let prompt: String = c.prompt.chars().take(60).collect();
+            println!(
+                "  {:>9}  {}  {}  {}  {}",
+                compress::format_size(c.size),
+                c.last_activity.with_timezone(&chrono::Local).format("%Y-%m-%d"),
+                c.id.chars().take(8).collect::<String>(),
+                project,
+                prompt
+            );
+        }
+        println!();
+    }
+
+    let total: u64 = candidates.iter().map(|c| c.size).sum();
+    let files: usize = candidates.iter().map(|c| c.files.len()).sum();
+    let summary = format!(
+        "{} sessions, {} files and directories, {}",
+        candidates.len(),
+        files,
+        compress::format_size(total)
+    );
+    let verb = match &action {
+        PruneAction::Report => {
+            println!("Would prune {summary}. Use --archive or --delete to prune them.");
+            return 0;
+        }
+        PruneAction::Archive(dir) => format!("Archive {summary} to {}", dir.display()),
+        PruneAction::Delete => format!("Permanently delete {summary}"),
+    };
+    if !yes && !confirm(&format!("{verb}?")) {
+        println!("Nothing pruned.");
+        return 0;
+    }
+
+    let mut failed = 0;
+    for c in &candidates {
+        let result = match &action {
+            PruneAction::Archive(dir) => prune::archive_candidate(claude_home, c, dir),
+            PruneAction::Delete => prune::delete_candidate(c),
+            PruneAction::Report => Ok(()),
+        };
+        if let Err(e) = result {
+            failed += 1;
+            eprintln!("skipped {}: {e}", c.id);
+        }
+    }
+    let done = if matches!(action, PruneAction::Delete) { "Deleted" } else { "Archived" };
+    println!("{done} {} of {} sessions.", candidates.len() - failed, candidates.len());
+    if failed > 0 {
+        1
+    } else {
+        0
+    }
+}
+
+/// Ask a yes/no question on the terminal; anything but y or yes is no.
+fn confirm(question: &str) -> bool {
+    print!("{question} [y/N] ");
+    let _ = std::io::stdout().flush();
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Resolve a command-line path against the current directory.
+fn absolute_path(path: &Path) -> Result<PathBuf, String> {
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+    std::env::current_dir()
+        .map(|cwd| cwd.join(path))
+        .map_err(|e| format!("Cannot resolve {}: {e}", path.display()))
+}
+
+/// Print the sessions that touched `path`, one line per session.
+fn run_files(claude_home: &Path, path: &Path, include_reads: bool) -> i32 {
+    let target = match absolute_path(path) {
+        Ok(target) => target,
+        Err(e) => {
+            eprintln!("{e}");
+            return 1;
+        }
+    };
+
+    let sessions = discover_sessions(claude_home);
+    let cache = config::state_dir().join("file_index.json");
+    let index = activity::build_file_index(claude_home, &sessions, Some(&cache));
+    let matches = activity::sessions_touching_file(&sessions, &index, &target, include_reads);
+    if matches.is_empty() {
+        let verb = if include_reads { "touched" } else { "modified" };
+        println!("No sessions {verb} {}.", target.display());
+        return 0;
+    }
+
+    for m in &matches {
+        let mut counts = Vec::new();
+        let plural = |n: usize, word: &str| {
+            if n == 1 {
+                format!("1 {word}")
+            } else {
+                format!("{n} {word}s")
+            }
+        };
+        if m.edits > 0 {
+            counts.push(plural(m.edits, "edit"));
+        }
+        if m.reads > 0 {
+            counts.push(plural(m.reads, "read"));
+        }
+        let when = m.last_touched.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M");
+        let prompt: String = m.session.first_message.chars().take(60).collect();
+        println!(
+            "{when}  {}  {}  {}  {prompt}",
+            m.session.id,
+            m.session.project_name,
+            counts.join(", ")
+        );
+    }
+    0
+}
+
+/// Split a `file[:line]` argument. A trailing `:N` is taken as a 1-based
+/// line number.
+fn parse_blame_target(target: &str) -> (PathBuf, Option<usize>) {
+    if let Some((file, line)) = target.rsplit_once(':') {
+        if let Ok(line) = line.parse::<usize>() {
+            return (PathBuf::from(file), Some(line));
+        }
+    }
+    (PathBuf::from(target), None)
+}
+
+/// Print the blame of a file, or the details of a single line.
+fn run_blame(claude_home: &Path, target: &str) -> i32 {
+    let (path, line) = parse_blame_target(target);
+    let result = absolute_path(&path).and_then(|target| {
+        let sessions = discover_sessions(claude_home);
+        blame::blame_file(claude_home, &sessions, &target)
+    });
+    let blame = match result {
+        Ok(blame) => blame,
+        Err(e) => {
+            eprintln!("{e}");
+            return 1;
+        }
+    };
+    let format_time = |ts: Option<chrono::DateTime<chrono::Utc>>| {
+        ts.map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "unknown time".to_string())
+    };
+
+    if let Some(line) = line {
+        if line == 0 || line > blame.lines.len() {
+            eprintln!("{} has {} lines", path.display(), blame.lines.len());
+            return 1;
+        }
+        println!("{}:{line}: {}", path.display(), blame.lines[line - 1].trim());
+        match blame.line_sources[line - 1].map(|i| &blame.sources[i]) {
+            Some(source) => {
+                println!("  session  {} ({})", source.session.id, source.session.project_name);
+                println!("  message  {}", source.uuid.as_deref().unwrap_or("unknown"));
+                println!("  time     {}", format_time(source.timestamp));
+                println!("  tool     {}", source.tool);
+                if let Some(prompt) = &source.prompt {
+                    println!("  prompt   {prompt}");
+                }
+                println!("  resume   {}", source.session.resume_command());
+            }
+            None => println!("  unknown (no session edit matches this line)"),
+        }
+        return 0;
+    }
+
+    let width = blame.lines.len().to_string().len();
+    for hunk in blame.hunks() {
+        let range = if hunk.start == hunk.end {
+            hunk.start.to_string()
+        } else {
+            format!("{}-{}", hunk.start, hunk.end)
+        };
+        let range = format!("{range:>w$}", w = width * 2 + 1);
+        match hunk.source.map(|i| &blame.sources[i]) {
+            Some(source) => {
+                let message: String = source.uuid.as_deref().unwrap_or("-").chars().take(8).collect();
+                let prompt: String = source.prompt.as_deref().unwrap_or("").chars().take(60).collect();
+                let session: String = source.session.id.chars().take(8).collect();
+                println!(
+                    "{range}  {}  {session:<8}  {message:<8}  {prompt}",
+                    format_time(source.timestamp),
+                );
+            }
+            None => println!("{range}  unknown"),
+        }
+    }
+
+    if !blame.sources.is_empty() {
+        println!();
+        let mut seen = std::collections::HashSet::new();
+        for source in &blame.sources {
+            if seen.insert(&source.session.id) {
+                println!("{}  {}", source.session.id, source.session.resume_command());
+            }
+        }
+    }
+    0
+}
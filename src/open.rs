@@ -0,0 +1,75 @@
+// Opening URLs and file paths found in conversation text
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Characters trimmed from the ends of a link candidate.
+const LINK_PUNCTUATION: &[char] = &[
+    '(', ')', '[', ']', '<', '>', '{', '}', '"', '\'', '`', ',', '.', ';', ':', '!', '?',
+];
+
+/// Something clickable in conversation text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Link {
+    Url(String),
+    Path(String),
+}
+
+/// The URL or file path under the character `column` of `line`, if any.
+pub fn link_at(line: &str, column: usize) -> Option<Link> {
+    let chars: Vec<char> = line.chars().collect();
+    if column >= chars.len() || chars[column].is_whitespace() {
+        return None;
+    }
+    let start = chars[..column]
+        .iter()
+        .rposition(|c| c.is_whitespace())
+        .map_or(0, |i| i + 1);
+    let end = chars[column..]
+        .iter()
+        .position(|c| c.is_whitespace())
+        .map_or(chars.len(), |i| column + i);
+    let word: String = chars[start..end].iter().collect();
+
+    if let Some(offset) = word.find("http://").or_else(|| word.find("https://")) {
+        let url = word[offset..].trim_end_matches(LINK_PUNCTUATION);
+        return Some(Link::Url(url.to_string()));
+    }
+    let word = word.trim_matches(LINK_PUNCTUATION);
+    // Drop a trailing `:line` or `:line:col` location
+    let path = word
+        .split(':')
+        .next()
+        .filter(|p| p.contains('/') && !p.contains("//"))?;
+    Some(Link::Path(path.to_string()))
+}
+
+/// Resolve a path link against the session's working directory. Returns
+/// None if the file does not exist.
+pub fn resolve_path(path: &str, cwd: &str) -> Option<PathBuf> {
+    let resolved = if let Some(rest) = path.strip_prefix("~/") {
+        dirs::home_dir()?.join(rest)
+    } else if Path::new(path).is_absolute() {
+        PathBuf::from(path)
+    } else {
+        Path::new(cwd).join(path)
+    };
+    resolved.exists().then_some(resolved)
+}
+
+/// Open a URL or file with the system's default application.
+pub fn open_in_system(target: &str) -> Result<(), String> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    Command::new(opener)
+        .arg(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("failed to run {opener}: {e}"))
+}
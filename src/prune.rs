@@ -0,0 +1,240 @@
+// Finding and removing dead, empty, short, and old sessions together with
+// their todo files, subagent transcripts, and other per-session state
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use rayon::prelude::*;
+use serde_json::Value;
+
+use crate::activity::scan_session_activity;
+use crate::compress::{open_session_file, session_id_from_path};
+use crate::discovery::{collect_session_files, last_modified, parse_session_file};
+use crate::remap::PathRemapper;
+
+/// Why a session is pruned. Sessions matching several reasons are listed
+/// under the first, in this order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PruneReason {
+    DeadProject,
+    NoPrompt,
+    FewTurns,
+    Old,
+}
+
+impl PruneReason {
+    pub fn label(self) -> &'static str {
+        match self {
+            PruneReason::DeadProject => "Project directory no longer exists",
+            PruneReason::NoPrompt => "No real user prompt",
+            PruneReason::FewTurns => "Too few turns",
+            PruneReason::Old => "Not used since the cutoff",
+        }
+    }
+}
+
+/// Which sessions to prune; a session matching any criterion is pruned.
+#[derive(Debug, Clone, Default)]
+pub struct PruneCriteria {
+    /// Sessions whose project directory no longer exists (after remapping).
+    pub dead: bool,
+    /// Sessions without a real user prompt.
+    pub empty: bool,
+    /// Sessions with fewer prompts than this (but at least one).
+    pub min_turns: Option<usize>,
+    /// Sessions last written before this time.
+    pub older_than: Option<DateTime<Utc>>,
+}
+
+/// A session to prune with every file that belongs to it.
+#[derive(Debug, Clone)]
+pub struct PruneCandidate {
+    pub id: String,
+    /// Project directory, empty if the session never recorded one.
+    pub project: String,
+    pub reason: PruneReason,
+    pub last_activity: DateTime<Utc>,
+    /// Start of the first prompt, if any.
+    pub prompt: String,
+    /// The session file followed by its related files and directories.
+    pub files: Vec<PathBuf>,
+    /// Total size of `files`, including directory contents.
+    pub size: u64,
+}
+
+/// Find the sessions under `claude_home` that match `criteria`, sorted by
+/// reason and then by age, oldest first.
+///
+/// Subagent transcripts (`agent-*.jsonl`) are never candidates themselves;
+/// they are pruned with the session that started them.
+pub fn find_prune_candidates(
+    claude_home: &Path,
+    criteria: &PruneCriteria,
+    remapper: &PathRemapper,
+) -> Vec<PruneCandidate> {
+    let files = collect_session_files(&claude_home.join("projects"));
+    let (agents, sessions): (Vec<PathBuf>, Vec<PathBuf>) = files.into_iter().partition(|p| is_agent_file(p));
+
+    let mut agents_by_session: HashMap<String, Vec<PathBuf>> = HashMap::new();
+    for agent in agents {
+        if let Some(parent) = agent_parent_session(&agent) {
+            agents_by_session.entry(parent).or_default().push(agent);
+        }
+    }
+
+    let mut candidates: Vec<PruneCandidate> = sessions
+        .par_iter()
+        .filter_map(|path| {
+            let id = session_id_from_path(path)?;
+            let session = parse_session_file(path).map(|mut s| {
+                remapper.remap_session(&mut s);
+                s
+            });
+            let last_activity = session
+                .as_ref()
+                .map(|s| s.last_activity)
+                .or_else(|| last_modified(path))
+                .unwrap_or_else(Utc::now);
+            let turns = if criteria.empty || criteria.min_turns.is_some() {
+                scan_session_activity(path).user_turns
+            } else {
+                usize::MAX
+            };
+            let dead = session.as_ref().is_some_and(|s| !s.cwd.is_empty() && !s.project_exists);
+
+            let reason = if criteria.dead && dead {
+                PruneReason::DeadProject
+            } else if criteria.empty && turns == 0 {
+                PruneReason::NoPrompt
+            } else if criteria.min_turns.is_some_and(|n| turns > 0 && turns < n) {
+                PruneReason::FewTurns
+            } else if criteria.older_than.is_some_and(|cutoff| last_activity < cutoff) {
+                PruneReason::Old
+            } else {
+                return None;
+            };
+
+            let mut files = vec![path.clone()];
+            files.extend(related_files(claude_home, path, &id));
+            files.extend(agents_by_session.get(&id).into_iter().flatten().cloned());
+            let size = files.iter().map(|f| disk_size(f)).sum();
+            Some(PruneCandidate {
+                id,
+                project: session.as_ref().map(|s| s.cwd.clone()).unwrap_or_default(),
+                reason,
+                last_activity,
+                prompt: session.map(|s| s.first_message).unwrap_or_default(),
+                files,
+                size,
+            })
+        })
+        .collect();
+    candidates.sort_by(|a, b| a.reason.cmp(&b.reason).then(a.last_activity.cmp(&b.last_activity)));
+    candidates
+}
+
+/// Per-session files outside the session file that exist on disk: the
+/// session's directory of subagent transcripts next to it, its todo lists,
+/// file edit history, shell environment, and debug log.
+pub fn related_files(claude_home: &Path, session_file: &Path, id: &str) -> Vec<PathBuf> {
+    let mut related = Vec::new();
+    if let Some(dir) = session_file.parent() {
+        related.push(dir.join(id));
+    }
+    if let Ok(entries) = fs::read_dir(claude_home.join("todos")) {
+        let mut todos: Vec<PathBuf> = entries
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with(&format!("{id}-")))
+            .map(|e| e.path())
+            .collect();
+        todos.sort();
+        related.extend(todos);
+    }
+    related.push(claude_home.join("file-history").join(id));
+    related.push(claude_home.join("session-env").join(id));
+    related.push(claude_home.join("debug").join(format!("{id}.txt")));
+    related.retain(|p| p.exists());
+    related
+}
+
+/// Whether `path` is a subagent transcript kept next to session files.
+fn is_agent_file(path: &Path) -> bool {
+    session_id_from_path(path).is_some_and(|id| id.starts_with("agent-"))
+}
+
+/// Session that started the subagent transcript at `path`.
+fn agent_parent_session(path: &Path) -> Option<String> {
+    let reader = open_session_file(path).ok()?;
+    reader.lines().map_while(Result::ok).take(20).find_map(|line| {
+        let entry: Value = serde_json::from_str(&line).ok()?;
+        entry.get("sessionId")?.as_str().map(str::to_string)
+    })
+}
+
+/// Size of a file, or of everything inside a directory.
+pub fn disk_size(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| disk_size(&e.path())).sum())
+        .unwrap_or(0)
+}
+
+/// Delete every file of `candidate`.
+pub fn delete_candidate(candidate: &PruneCandidate) -> Result<(), String> {
+    for path in &candidate.files {
+        let result = if path.is_dir() {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_file(path)
+        };
+        result.map_err(|e| format!("failed to delete {}: {e}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Move every file of `candidate` into `archive`, keeping its path
+/// relative to `claude_home` so it can be moved back.
+pub fn archive_candidate(claude_home: &Path, candidate: &PruneCandidate, archive: &Path) -> Result<(), String> {
+    for path in &candidate.files {
+        let relative = path.strip_prefix(claude_home).unwrap_or(path);
+        move_path(path, &archive.join(relative))?;
+    }
+    Ok(())
+}
+
+/// Move a file or directory, copying when a rename crosses file systems.
+fn move_path(from: &Path, to: &Path) -> Result<(), String> {
+    if let Some(dir) = to.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_path(from, to).map_err(|e| format!("failed to archive {}: {e}", from.display()))?;
+    let removed = if from.is_dir() {
+        fs::remove_dir_all(from)
+    } else {
+        fs::remove_file(from)
+    };
+    removed.map_err(|e| format!("failed to remove {}: {e}", from.display()))
+}
+
+fn copy_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    if !from.is_dir() {
+        return fs::copy(from, to).map(|_| ());
+    }
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        copy_path(&entry.path(), &to.join(entry.file_name()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,241 @@
+// Path remapping for projects that moved or were recorded in a container,
+// and suggestions for where a missing project went
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::git::origin_url;
+use crate::session::Session;
+
+/// Directory levels searched below each root for relocated repositories.
+const SEARCH_DEPTH: usize = 3;
+
+/// A rewrite of recorded session paths, from the `[[remap]]` config tables.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathRule {
+    /// Path prefix, or a regex when `regex` is set.
+    pub from: String,
+    /// Replacement; regex rules can use `$1` or `${name}` for captures.
+    pub to: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub regex: bool,
+}
+
+#[derive(Debug)]
+enum Matcher {
+    Prefix(String),
+    Regex(Regex),
+}
+
+/// Compiled path rules, applied in order to recorded paths.
+#[derive(Debug, Default)]
+pub struct PathRemapper {
+    rules: Vec<(Matcher, String)>,
+}
+
+impl PathRemapper {
+    /// Compile `rules`, failing on an invalid regex or an empty prefix.
+    pub fn new(rules: &[PathRule]) -> Result<PathRemapper, String> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let matcher = if rule.regex {
+                    Regex::new(&rule.from)
+                        .map(Matcher::Regex)
+                        .map_err(|e| format!("invalid remap regex {:?}: {e}", rule.from))?
+                } else {
+                    let prefix = rule.from.trim_end_matches('/');
+                    if prefix.is_empty() {
+                        return Err("remap prefix must not be empty or /".to_string());
+                    }
+                    Matcher::Prefix(prefix.to_string())
+                };
+                Ok((matcher, rule.to.clone()))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(PathRemapper { rules })
+    }
+
+    /// `path` after every matching rule, in order, or None if no rule
+    /// matches. Prefixes match whole path components only.
+    pub fn apply(&self, path: &str) -> Option<String> {
+        let mut current = path.to_string();
+        let mut changed = false;
+        for (matcher, to) in &self.rules {
+            let next = match matcher {
+                Matcher::Prefix(prefix) => current
+                    .strip_prefix(prefix.as_str())
+                    .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+                    .map(|rest| format!("{}{rest}", to.trim_end_matches('/'))),
+                Matcher::Regex(re) if re.is_match(&current) => {
+                    Some(re.replace(&current, to.as_str()).into_owned())
+                }
+                Matcher::Regex(_) => None,
+            };
+            if let Some(next) = next {
+                current = next;
+                changed = true;
+            }
+        }
+        changed.then_some(current)
+    }
+
+    /// Point `session` at its remapped directory, updating the project name
+    /// and whether it exists.
+    pub fn remap_session(&self, session: &mut Session) {
+        let Some(cwd) = self.apply(&session.cwd) else {
+            return;
+        };
+        session.project_path = self.apply(&session.project_path).unwrap_or_else(|| cwd.clone());
+        session.project_name = Path::new(&session.project_path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        session.project_exists = Path::new(&cwd).exists();
+        session.cwd = cwd;
+    }
+}
+
+/// A place a missing project may have moved to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub path: PathBuf,
+    /// Origin remote of the repository there, if it has one.
+    pub remote: Option<String>,
+}
+
+/// Repository name of a git remote URL, e.g. `api` for
+/// `git@github.com:acme/api.git`.
+pub fn remote_repo_name(url: &str) -> Option<&str> {
+    let name = url.trim_end_matches('/').rsplit(['/', ':']).next()?;
+    let name = name.strip_suffix(".git").unwrap_or(name);
+    (!name.is_empty()).then_some(name)
+}
+
+/// Git repositories under `roots` that may be the moved `project`: those
+/// whose origin remote names a repository called like the project come
+/// first, then those whose directory has the project's name.
+pub fn suggest_locations(project: &Path, roots: &[PathBuf]) -> Vec<Suggestion> {
+    let Some(name) = project.file_name().and_then(|n| n.to_str()) else {
+        return Vec::new();
+    };
+    let mut repos = BTreeSet::new();
+    for root in roots {
+        find_repos(root, SEARCH_DEPTH, &mut repos);
+    }
+
+    let mut scored: Vec<(u8, Suggestion)> = repos
+        .into_iter()
+        .filter(|repo| repo != project)
+        .filter_map(|path| {
+            let remote = origin_url(&path);
+            let remote_match = remote
+                .as_deref()
+                .and_then(remote_repo_name)
+                .is_some_and(|r| r.eq_ignore_ascii_case(name));
+            let dir_match = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.eq_ignore_ascii_case(name));
+            let score = u8::from(remote_match) * 2 + u8::from(dir_match);
+            (score > 0).then_some((score, Suggestion { path, remote }))
+        })
+        .collect();
+    scored.sort_by(|(a, x), (b, y)| b.cmp(a).then_with(|| x.path.cmp(&y.path)));
+    scored.into_iter().map(|(_, s)| s).collect()
+}
+
+/// Directories holding a `.git` entry at most `depth` levels below `dir`,
+/// skipping hidden directories and not descending into repositories.
+fn find_repos(dir: &Path, depth: usize, repos: &mut BTreeSet<PathBuf>) {
+    if dir.join(".git").exists() {
+        repos.insert(dir.to_path_buf());
+        return;
+    }
+    if depth == 0 {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if !hidden && entry.file_type().is_ok_and(|t| t.is_dir()) {
+            find_repos(&entry.path(), depth - 1, repos);
+        }
+    }
+}
+
+/// Directories to search for moved projects: the parents of projects that
+/// still exist, and the home directory.
+pub fn search_roots(sessions: &[Session]) -> Vec<PathBuf> {
+    let mut roots: BTreeSet<PathBuf> = sessions
+        .iter()
+        .filter(|s| s.project_exists)
+        .filter_map(|s| Path::new(&s.project_path).parent().map(Path::to_path_buf))
+        .collect();
+    roots.extend(dirs::home_dir());
+    roots.into_iter().collect()
+}
+
+/// Searches for new locations of missing projects on a background thread,
+/// since walking the search roots can take seconds on a large home
+/// directory.
+pub struct LocationFinder {
+    requests: mpsc::Sender<(String, Vec<PathBuf>)>,
+    results: mpsc::Receiver<(String, Vec<Suggestion>)>,
+    pending: Option<String>,
+}
+
+impl Default for LocationFinder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocationFinder {
+    pub fn new() -> Self {
+        let (request_tx, request_rx) = mpsc::channel::<(String, Vec<PathBuf>)>();
+        let (result_tx, result_rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            while let Ok((project, roots)) = request_rx.recv() {
+                let suggestions = suggest_locations(Path::new(&project), &roots);
+                if result_tx.send((project, suggestions)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            requests: request_tx,
+            results: result_rx,
+            pending: None,
+        }
+    }
+
+    /// Whether a search for `project` is running.
+    pub fn is_pending(&self, project: &str) -> bool {
+        self.pending.as_deref() == Some(project)
+    }
+
+    /// Search `roots` for where `project` went.
+    pub fn request(&mut self, project: &str, roots: Vec<PathBuf>) {
+        if self.requests.send((project.to_string(), roots)).is_ok() {
+            self.pending = Some(project.to_string());
+        }
+    }
+
+    /// Result of a finished search, if one finished since the last poll.
+    pub fn poll(&mut self) -> Option<(String, Vec<Suggestion>)> {
+        let (project, suggestions) = self.results.try_recv().ok()?;
+        if self.is_pending(&project) {
+            self.pending = None;
+        }
+        Some((project, suggestions))
+    }
+}
@@ -1,6 +1,5 @@
 use std::collections::HashMap;
-use std::fs;
-use std::io::{BufRead, BufReader};
+use std::io::BufRead;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -9,6 +8,8 @@ use chrono::{DateTime, Utc};
 use rayon::prelude::*;
 use regex::Regex;
 
+use crate::compress::{open_session_file, session_id_from_path};
+use crate::discovery::{collect_session_files, last_modified, session_file_path};
 use crate::session::{clean_message, Session, SessionFileEntry, strip_system_blocks, strip_tags};
 
 /// Build a file-path-to-session index from discovered sessions.
@@ -16,20 +17,71 @@ use crate::session::{clean_message, Session, SessionFileEntry, strip_system_bloc
 /// Maps each session's JSONL file path to a clone of the Session.
 /// Used by `deep_search_indexed` to avoid re-parsing files for metadata.
 pub fn build_session_index(claude_home: &Path, sessions: &[Session]) -> HashMap<PathBuf, Session> {
-    let projects_dir = claude_home.join("projects");
     let mut index = HashMap::with_capacity(sessions.len());
 
     for session in sessions {
-        let encoded_dir = session.project_path.replace('/', "-");
-        let file_path = projects_dir
-            .join(&encoded_dir)
-            .join(format!("{}.jsonl", session.id));
+        let file_path = session_file_path(claude_home, session);
         index.insert(file_path, session.clone());
     }
 
     index
 }
 
+/// Compile a deep search pattern.
+///
+/// Patterns are case-insensitive unless they start with an explicit
+/// inline flag group such as `(?-i)`.
+pub fn build_search_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    build_pattern_regex(
+        pattern,
+        SearchOptions {
+            regex: true,
+            ..SearchOptions::default()
+        },
+    )
+}
+
+/// How a search pattern matches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// The pattern is a regex rather than literal text.
+    pub regex: bool,
+    /// Match case exactly.
+    pub case_sensitive: bool,
+    /// Only match whole words.
+    pub whole_word: bool,
+}
+
+/// Compile a search pattern with `options`.
+///
+/// Case is ignored unless `case_sensitive` is set or a regex pattern
+/// starts with an explicit inline flag group, as in deep search.
+pub fn build_pattern_regex(pattern: &str, options: SearchOptions) -> Result<Regex, regex::Error> {
+    let own_flags = options.regex && pattern.starts_with("(?");
+    let mut body = if options.regex {
+        pattern.to_string()
+    } else {
+        regex::escape(pattern)
+    };
+    if options.whole_word {
+        body = format!(r"\b(?:{body})\b");
+    }
+    if options.case_sensitive || own_flags {
+        Regex::new(&body)
+    } else {
+        Regex::new(&format!("(?i){body}"))
+    }
+}
+
+/// Check whether a single session file matches `pattern`, with the same
+/// semantics as `deep_search_indexed`. Invalid patterns never match.
+pub fn session_file_matches(path: &Path, pattern: &str) -> bool {
+    match build_search_regex(pattern) {
+        Ok(re) => file_matches(path, &re),
+        Err(_) => false,
+    }
+}
+
 /// Search through all session JSONL files for lines matching `pattern`,
 /// using a pre-built session index to avoid re-parsing metadata.
 ///
@@ -40,12 +92,7 @@ pub fn deep_search_indexed(
     session_index: &HashMap<PathBuf, Session>,
     cancel: &Arc<AtomicBool>,
 ) -> Vec<Session> {
-    let ci_pattern = if pattern.starts_with("(?") {
-        pattern.to_string()
-    } else {
-        format!("(?i){}", pattern)
-    };
-    let re = match Regex::new(&ci_pattern) {
+    let re = match build_search_regex(pattern) {
         Ok(r) => r,
         Err(e) => {
             eprintln!("Invalid search pattern: {e}");
@@ -58,26 +105,8 @@ pub fn deep_search_indexed(
         return Vec::new();
     }
 
-    // Collect all .jsonl file paths
-    let mut jsonl_files: Vec<PathBuf> = Vec::new();
-    if let Ok(entries) = fs::read_dir(&projects_dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_dir() {
-                if let Ok(files) = fs::read_dir(&path) {
-                    for file in files.flatten() {
-                        let fpath = file.path();
-                        if fpath.extension().and_then(|e| e.to_str()) == Some("jsonl") {
-                            jsonl_files.push(fpath);
-                        }
-                    }
-                }
-            }
-        }
-    }
-
     // Search files in parallel, look up session from index
-    let mut sessions: Vec<Session> = jsonl_files
+    let mut sessions: Vec<Session> = collect_session_files(&projects_dir)
         .par_iter()
         .filter_map(|path| {
             // Check cancellation flag
@@ -96,19 +125,14 @@ pub fn deep_search_indexed(
         })
         .collect();
 
-    sessions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.timestamp));
     sessions
 }
 
 /// Deep search without index. Used by tests and as a standalone entry point.
 #[allow(dead_code)]
 pub fn deep_search(claude_home: &Path, pattern: &str) -> Vec<Session> {
-    let ci_pattern = if pattern.starts_with("(?") {
-        pattern.to_string()
-    } else {
-        format!("(?i){}", pattern)
-    };
-    let re = match Regex::new(&ci_pattern) {
+    let re = match build_search_regex(pattern) {
         Ok(r) => r,
         Err(e) => {
             eprintln!("Invalid search pattern: {e}");
@@ -121,29 +145,12 @@ pub fn deep_search(claude_home: &Path, pattern: &str) -> Vec<Session> {
         return Vec::new();
     }
 
-    let mut jsonl_files: Vec<PathBuf> = Vec::new();
-    if let Ok(entries) = fs::read_dir(&projects_dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_dir() {
-                if let Ok(files) = fs::read_dir(&path) {
-                    for file in files.flatten() {
-                        let fpath = file.path();
-                        if fpath.extension().and_then(|e| e.to_str()) == Some("jsonl") {
-                            jsonl_files.push(fpath);
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    let mut sessions: Vec<Session> = jsonl_files
+    let mut sessions: Vec<Session> = collect_session_files(&projects_dir)
         .par_iter()
         .filter_map(|path| search_file_with_metadata(path, &re))
         .collect();
 
-    sessions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.timestamp));
     sessions
 }
 
@@ -153,12 +160,11 @@ pub fn deep_search(claude_home: &Path, pattern: &str) -> Vec<Session> {
 /// tags (system-reminder, local-command-caveat, etc.) before matching
 /// to avoid false positives from system-injected content.
 fn file_matches(path: &Path, re: &Regex) -> bool {
-    let file = match fs::File::open(path) {
-        Ok(f) => f,
+    let reader = match open_session_file(path) {
+        Ok(r) => r,
         Err(_) => return false,
     };
 
-    let reader = BufReader::new(file);
     for line in reader.lines() {
         let line = match line {
             Ok(l) => l,
@@ -219,10 +225,9 @@ fn extract_entry_type(line: &str) -> &str {
 /// Search a single JSONL file for the pattern and extract session metadata.
 /// Used as fallback when the session is not in the pre-built index.
 fn search_file_with_metadata(path: &Path, re: &Regex) -> Option<Session> {
-    let session_id = path.file_stem()?.to_str()?.to_string();
+    let session_id = session_id_from_path(path)?;
 
-    let file = fs::File::open(path).ok()?;
-    let reader = BufReader::new(file);
+    let reader = open_session_file(path).ok()?;
 
     let mut found_match = false;
     let mut first_user_entry: Option<SessionFileEntry> = None;
@@ -292,8 +297,11 @@ fn search_file_with_metadata(path: &Path, re: &Regex) -> Option<Session> {
         project_name,
         git_branch: entry.git_branch,
         timestamp,
+        last_activity: last_modified(path).map_or(timestamp, |t| t.max(timestamp)),
         first_message,
         cwd,
         project_exists,
+        version: entry.version,
+        file_path: path.to_path_buf(),
     })
 }
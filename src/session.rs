@@ -1,5 +1,7 @@
 // Session and related data types for Claude Code session parsing
 
+use std::path::PathBuf;
+
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
@@ -12,9 +14,15 @@ pub struct Session {
     pub project_name: String,
     pub git_branch: Option<String>,
     pub timestamp: DateTime<Utc>,
+    /// Last write to the session file, or `timestamp` if unknown.
+    pub last_activity: DateTime<Utc>,
     pub first_message: String,
     pub cwd: String,
     pub project_exists: bool,
+    /// Claude Code version that wrote the first prompt.
+    pub version: Option<String>,
+    /// Session file the session was discovered from (may be compressed).
+    pub file_path: PathBuf,
 }
 
 impl Session {
@@ -82,6 +90,12 @@ pub struct ContentBlock {
     pub block_type: String,
     #[serde(default)]
     pub text: String,
+    /// Tool name of a `tool_use` block.
+    #[serde(default)]
+    pub name: String,
+    /// Tool input of a `tool_use` block, left undecoded until needed.
+    #[serde(default)]
+    pub input: Option<Box<serde_json::value::RawValue>>,
 }
 
 impl<'de> Deserialize<'de> for StringOrArray {
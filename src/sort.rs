@@ -0,0 +1,110 @@
+// Sort orders for the session list
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::activity::SessionActivity;
+use crate::session::Session;
+
+/// What the session list is sorted by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortKey {
+    LastActivity,
+    #[default]
+    Created,
+    Project,
+    Branch,
+    Messages,
+    Tokens,
+    Duration,
+    Relevance,
+}
+
+impl SortKey {
+    /// All keys, in the order the sort menu lists them.
+    pub const ALL: [SortKey; 8] = [
+        SortKey::LastActivity,
+        SortKey::Created,
+        SortKey::Project,
+        SortKey::Branch,
+        SortKey::Messages,
+        SortKey::Tokens,
+        SortKey::Duration,
+        SortKey::Relevance,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortKey::LastActivity => "last activity",
+            SortKey::Created => "created",
+            SortKey::Project => "project",
+            SortKey::Branch => "branch",
+            SortKey::Messages => "messages",
+            SortKey::Tokens => "tokens",
+            SortKey::Duration => "duration",
+            SortKey::Relevance => "relevance",
+        }
+    }
+
+    /// Whether the key needs counts from a full scan of each session file.
+    pub fn needs_stats(self) -> bool {
+        matches!(self, SortKey::Messages | SortKey::Tokens | SortKey::Duration)
+    }
+}
+
+/// Counts from a full scan of a session file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionStats {
+    /// User prompts and assistant messages.
+    pub messages: usize,
+    /// User prompts.
+    pub turns: usize,
+    /// Input (including cached) and output tokens.
+    pub tokens: u64,
+    pub duration: chrono::Duration,
+    /// Model that answered first.
+    pub model: Option<String>,
+}
+
+impl From<&SessionActivity> for SessionStats {
+    fn from(activity: &SessionActivity) -> Self {
+        SessionStats {
+            messages: activity.user_turns + activity.assistant_turns,
+            turns: activity.user_turns,
+            tokens: activity.tokens.total_input() + activity.tokens.output,
+            duration: activity.duration().unwrap_or_default(),
+            model: activity.models.first().cloned(),
+        }
+    }
+}
+
+/// A session with what its sort keys compare.
+pub struct SortItem<'a> {
+    pub session: &'a Session,
+    pub stats: SessionStats,
+    /// How well the session matches the filter; higher is better.
+    pub relevance: u32,
+}
+
+/// Compare two items by `key`: newest, A to Z, largest, or most relevant
+/// first. Ties go to the newest session.
+pub fn compare(key: SortKey, a: &SortItem, b: &SortItem) -> Ordering {
+    let text = |s: Option<&str>| s.map(str::to_lowercase);
+    let ordering = match key {
+        SortKey::LastActivity => b.session.last_activity.cmp(&a.session.last_activity),
+        SortKey::Created => Ordering::Equal,
+        SortKey::Project => a.session.project_name.to_lowercase().cmp(&b.session.project_name.to_lowercase()),
+        // Sessions without a branch go last
+        SortKey::Branch => match (text(a.session.git_branch.as_deref()), text(b.session.git_branch.as_deref())) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            (x, y) => y.is_some().cmp(&x.is_some()),
+        },
+        SortKey::Messages => b.stats.messages.cmp(&a.stats.messages),
+        SortKey::Tokens => b.stats.tokens.cmp(&a.stats.tokens),
+        SortKey::Duration => b.stats.duration.cmp(&a.stats.duration),
+        SortKey::Relevance => b.relevance.cmp(&a.relevance),
+    };
+    ordering.then_with(|| b.session.timestamp.cmp(&a.session.timestamp))
+}
@@ -15,6 +15,11 @@ pub struct Theme {
     pub user_message_bg: Color,
     // Code blocks
     pub code_block_bg: Color,
+    // Diffs
+    pub diff_added_bg: Color,
+    pub diff_removed_bg: Color,
+    pub diff_added_fg: Color,
+    pub diff_removed_fg: Color,
     // Text
     pub text: Color,
     pub text_dim: Color,
@@ -34,6 +39,7 @@ pub struct Theme {
     // Session list
     pub selected_bg: Color,
     pub cursor_color: Color,
+    pub updated_marker: Color,
     // Syntect theme name
     pub syntect_theme: &'static str,
 }
@@ -49,6 +55,10 @@ impl Theme {
             assistant_header_fg: Color::Rgb(220, 190, 100),
             user_message_bg: Color::Rgb(25, 30, 40),
             code_block_bg: Color::Rgb(20, 25, 35),
+            diff_added_bg: Color::Rgb(20, 50, 30),
+            diff_removed_bg: Color::Rgb(60, 25, 30),
+            diff_added_fg: Color::Rgb(110, 210, 120),
+            diff_removed_fg: Color::Rgb(230, 110, 110),
             text: Color::Reset,
             text_dim: Color::DarkGray,
             heading: Color::Green,
@@ -63,6 +73,7 @@ impl Theme {
             status_label_fg: Color::Black,
             selected_bg: Color::Rgb(35, 45, 65),
             cursor_color: Color::Cyan,
+            updated_marker: Color::Rgb(120, 220, 120),
             syntect_theme: "base16-eighties.dark",
         }
     }
@@ -77,6 +88,10 @@ impl Theme {
             assistant_header_fg: Color::Rgb(130, 100, 0),
             user_message_bg: Color::Rgb(235, 240, 248),
             code_block_bg: Color::Rgb(245, 247, 250),
+            diff_added_bg: Color::Rgb(220, 245, 220),
+            diff_removed_bg: Color::Rgb(250, 222, 222),
+            diff_added_fg: Color::Rgb(20, 130, 40),
+            diff_removed_fg: Color::Rgb(180, 30, 30),
             text: Color::Black,
             text_dim: Color::DarkGray,
             heading: Color::Rgb(0, 120, 0),
@@ -91,6 +106,7 @@ impl Theme {
             status_label_fg: Color::White,
             selected_bg: Color::Rgb(210, 220, 240),
             cursor_color: Color::Rgb(0, 130, 150),
+            updated_marker: Color::Rgb(0, 150, 60),
             syntect_theme: "InspiredGitHub",
         }
     }
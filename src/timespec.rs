@@ -0,0 +1,132 @@
+// Durations and points in time for time filters, resolved in local time
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+/// Parse a human-friendly duration such as `12h`, `1w3d`, or `90min`.
+///
+/// Units: `min` (minutes), `h` (hours), `d` (days), `w` (weeks), and `m`
+/// (30-day months). Several number-unit pairs are added up.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err("empty duration string".to_string());
+    }
+
+    let mut total = Duration::zero();
+    let mut rest = s;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (num_str, tail) = rest.split_at(digits);
+        let unit_len = tail
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+        let num: i64 = num_str
+            .parse()
+            .map_err(|_| format!("invalid number in duration: {s:?}"))?;
+        total += match unit {
+            "min" => Duration::minutes(num),
+            "h" => Duration::hours(num),
+            "d" => Duration::days(num),
+            "w" => Duration::weeks(num),
+            "m" => Duration::days(num * 30),
+            "" => return Err(format!("missing unit in duration: {s:?}")),
+            other => {
+                return Err(format!(
+                    "unknown duration unit: {other:?} (expected min, h, d, w, or m)"
+                ))
+            }
+        };
+        rest = tail;
+    }
+    Ok(total)
+}
+
+/// A time filter bound: a duration back from now, a range of whole local
+/// days, or an exact instant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeSpec {
+    Ago(Duration),
+    /// Local days from the first date up to, not including, the second.
+    Days(NaiveDate, NaiveDate),
+    At(DateTime<Utc>),
+}
+
+impl TimeSpec {
+    /// Earliest instant the spec covers.
+    pub fn start(&self, now: DateTime<Local>) -> DateTime<Utc> {
+        match *self {
+            TimeSpec::Ago(duration) => now.with_timezone(&Utc) - duration,
+            TimeSpec::Days(first, _) => local_midnight(first),
+            TimeSpec::At(at) => at,
+        }
+    }
+
+    /// End of the spec: the end of the last day for day ranges, otherwise
+    /// the same as the start.
+    pub fn end(&self, now: DateTime<Local>) -> DateTime<Utc> {
+        match *self {
+            TimeSpec::Days(_, end) => local_midnight(end),
+            _ => self.start(now),
+        }
+    }
+}
+
+/// Parse a time filter bound, relative to `now`. Accepts durations
+/// (`12h`, `1w3d`), the words `today`, `yesterday`, `this-week`, and
+/// `this-month`, dates (`2025-03-01`), and times (`2025-03-01T14:30`, or
+/// RFC 3339 with an offset). Dates and times without an offset are local.
+pub fn parse_time(s: &str, now: DateTime<Local>) -> Result<TimeSpec, String> {
+    let s = s.trim();
+    let today = now.date_naive();
+    let word = s.to_lowercase().replace([' ', '_'], "-");
+    match word.as_str() {
+        "today" => return Ok(TimeSpec::Days(today, today + Duration::days(1))),
+        "yesterday" => return Ok(TimeSpec::Days(today - Duration::days(1), today)),
+        "this-week" => {
+            let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+            return Ok(TimeSpec::Days(monday, monday + Duration::weeks(1)));
+        }
+        "this-month" => {
+            let first = today.with_day(1).unwrap_or(today);
+            let next = first
+                .checked_add_months(chrono::Months::new(1))
+                .unwrap_or(first);
+            return Ok(TimeSpec::Days(first, next));
+        }
+        _ => {}
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(TimeSpec::Days(date, date + Duration::days(1)));
+    }
+    if let Ok(at) = DateTime::parse_from_rfc3339(s) {
+        return Ok(TimeSpec::At(at.with_timezone(&Utc)));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(s, format) {
+            return Local
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|at| TimeSpec::At(at.with_timezone(&Utc)))
+                .ok_or_else(|| format!("{s} does not exist in local time"));
+        }
+    }
+    parse_duration(s).map(TimeSpec::Ago).map_err(|e| {
+        format!("{e}; expected a duration, a date like 2025-03-01, today, yesterday, this-week, or this-month")
+    })
+}
+
+/// Start of `date` in local time. Days starting in a DST gap begin at the
+/// first valid local time.
+fn local_midnight(date: NaiveDate) -> DateTime<Utc> {
+    let mut at = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    for _ in 0..4 {
+        if let Some(local) = Local.from_local_datetime(&at).earliest() {
+            return local.with_timezone(&Utc);
+        }
+        at += Duration::minutes(30);
+    }
+    Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap_or_default())
+}
@@ -1,9 +1,13 @@
-use std::sync::atomic::Ordering;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Margin, Position, Rect};
 
-use super::{Action, App, ContentSearchState, Mode};
+use crate::session::MessageRole;
+use crate::sort::SortKey;
+
+use super::tree::ListLayout;
+use super::{Action, App, HistorySearch, Mode, SidePanel, ViewMenu};
 
 /// Handle a key event and return the resulting action.
 pub fn handle_input(app: &mut App, key: KeyEvent) -> Action {
@@ -12,14 +16,60 @@ pub fn handle_input(app: &mut App, key: KeyEvent) -> Action {
         return Action::Quit;
     }
 
+    if app.confirm.is_some() {
+        return handle_confirm(app, key);
+    }
+
     match app.mode {
         Mode::Browsing => handle_browse(app, key),
         Mode::Conversation => handle_conversation(app, key),
         Mode::ConversationSearch => handle_conversation_search(app, key),
+        Mode::Compare => handle_compare(app, key),
+    }
+}
+
+fn handle_confirm(app: &mut App, key: KeyEvent) -> Action {
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') => match app.confirm.take() {
+            Some(confirm) => app.accept_confirm(confirm),
+            None => Action::Continue,
+        },
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+            app.confirm = None;
+            Action::Continue
+        }
+        KeyCode::Char('t') => app.switch_to_live_pane(),
+        _ => Action::Continue,
+    }
+}
+
+/// Open the selected session, or fold a project or branch row in the tree.
+fn activate_selection(app: &mut App) -> Action {
+    if let Some(entry) = app.selected_entry() {
+        Action::EnterConversation(entry)
+    } else {
+        // Project or branch row in the tree
+        app.set_selected_project_expanded(None);
+        Action::Continue
     }
 }
 
 fn handle_browse(app: &mut App, key: KeyEvent) -> Action {
+    if app.history_search.is_some() {
+        return handle_history_search(app, key);
+    }
+    if app.view_menu.is_some() {
+        return handle_view_menu(app, key);
+    }
+    if app.relocate.is_some() {
+        return handle_relocate(app, key);
+    }
+    if app.sort_menu.is_some() {
+        return handle_sort_menu(app, key);
+    }
+    if !matches!(key.code, KeyCode::Up | KeyCode::Down) {
+        app.recall = None;
+    }
     match key.code {
         KeyCode::Esc => {
             if !app.filter_query.is_empty() || app.filter_active {
@@ -34,19 +84,72 @@ fn handle_browse(app: &mut App, key: KeyEvent) -> Action {
                 Action::Quit
             }
         }
-        KeyCode::Enter => {
-            if app.selected < app.display_entries.len() {
-                Action::EnterConversation(app.selected)
-            } else {
-                Action::Continue
-            }
+        KeyCode::Enter => activate_selection(app),
+        KeyCode::Tab => {
+            app.toggle_layout();
+            Action::Continue
+        }
+        KeyCode::Char('b') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.toggle_branch_grouping();
+            Action::Continue
+        }
+        KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.toggle_mark();
+            Action::Continue
+        }
+        KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.open_compare();
+            Action::Continue
+        }
+        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.toggle_preview();
+            Action::Continue
+        }
+        KeyCode::Right if app.layout == ListLayout::Tree => {
+            app.set_selected_project_expanded(Some(true));
+            Action::Continue
+        }
+        KeyCode::Left if app.layout == ListLayout::Tree => {
+            app.set_selected_project_expanded(Some(false));
+            Action::Continue
+        }
+        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.history_search = Some(HistorySearch::default());
+            Action::Continue
+        }
+        KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.view_menu = Some(ViewMenu::default());
+            Action::Continue
+        }
+        KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.cycle_time_window();
+            Action::Continue
+        }
+        KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.open_relocate();
+            Action::Continue
+        }
+        KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.open_sort_menu();
+            Action::Continue
+        }
+        KeyCode::Char('x') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.set_sort(app.sort, !app.sort_reverse);
+            Action::Continue
         }
         KeyCode::Down => {
-            app.move_down();
+            // Down steps back through recalled queries before moving the selection
+            if !app.recall_history(false) {
+                app.move_down();
+            }
             Action::Continue
         }
         KeyCode::Up => {
-            app.move_up();
+            // Up at the top of the list recalls older queries while typing one
+            let recall = app.filter_active && (app.recall.is_some() || app.selected == 0);
+            if !(recall && app.recall_history(true)) {
+                app.move_up();
+            }
             Action::Continue
         }
         KeyCode::PageDown => {
@@ -69,25 +172,16 @@ fn handle_browse(app: &mut App, key: KeyEvent) -> Action {
             Action::Continue
         }
         KeyCode::End => {
-            if !app.display_entries.is_empty() {
-                app.selected = app.display_entries.len() - 1;
+            if app.row_count() > 0 {
+                app.selected = app.row_count() - 1;
             }
             Action::Continue
         }
         KeyCode::Backspace => {
             if !app.filter_query.is_empty() {
-                app.filter_query.pop();
-                app.cancel_flag.store(true, Ordering::Relaxed);
-                app.search_receiver = None;
-                app.content_results.clear();
-                if app.filter_query.is_empty() {
-                    app.content_search_state = ContentSearchState::Idle;
-                    app.last_keystroke = None;
-                } else {
-                    app.content_search_state = ContentSearchState::Debouncing;
-                    app.last_keystroke = Some(Instant::now());
-                }
-                app.apply_filter();
+                let mut query = std::mem::take(&mut app.filter_query);
+                query.pop();
+                app.set_filter_query(query);
             }
             Action::Continue
         }
@@ -98,13 +192,8 @@ fn handle_browse(app: &mut App, key: KeyEvent) -> Action {
                 return Action::Continue;
             }
             app.filter_active = true;
-            app.filter_query.push(c);
-            app.cancel_flag.store(true, Ordering::Relaxed);
-            app.search_receiver = None;
-            app.content_results.clear();
-            app.content_search_state = ContentSearchState::Debouncing;
-            app.last_keystroke = Some(Instant::now());
-            app.apply_filter();
+            let query = format!("{}{c}", app.filter_query);
+            app.set_filter_query(query);
             Action::Continue
         }
         _ => Action::Continue,
@@ -112,6 +201,18 @@ fn handle_browse(app: &mut App, key: KeyEvent) -> Action {
 }
 
 fn handle_conversation(app: &mut App, key: KeyEvent) -> Action {
+    if app.conversation.as_ref().is_some_and(|c| c.combined_diff.is_some()) {
+        return handle_combined_diff(app, key);
+    }
+    if app.conversation.as_ref().is_some_and(|c| c.toc.as_ref().is_some_and(|t| t.focused)) {
+        return handle_toc(app, key);
+    }
+    if app.conversation.as_ref().is_some_and(|c| c.yank.is_some()) {
+        return handle_yank(app, key);
+    }
+    if app.conversation.as_ref().is_some_and(|c| c.visual.is_some()) {
+        return handle_visual(app, key);
+    }
     match key.code {
         KeyCode::Esc => {
             // First Esc: clear highlights if any are active
@@ -183,6 +284,65 @@ fn handle_conversation(app: &mut App, key: KeyEvent) -> Action {
             }
             Action::Continue
         }
+        KeyCode::Char(c @ (']' | '[' | '}' | '{')) => {
+            if let Some(conv) = &mut app.conversation {
+                let role = if matches!(c, ']' | '[') {
+                    MessageRole::User
+                } else {
+                    MessageRole::Assistant
+                };
+                conv.jump_to_message(role, matches!(c, ']' | '}'));
+            }
+            Action::Continue
+        }
+        KeyCode::Char('t') => {
+            app.toggle_toc();
+            Action::Continue
+        }
+        KeyCode::Char('y') => {
+            if let Some(conv) = &mut app.conversation {
+                conv.yank = Some(String::new());
+            }
+            Action::Continue
+        }
+        KeyCode::Char('v') => {
+            if let Some(conv) = &mut app.conversation {
+                conv.start_visual();
+            }
+            Action::Continue
+        }
+        KeyCode::Char('z') => {
+            if let Some(conv) = &mut app.conversation {
+                conv.toggle_fold();
+            }
+            Action::Continue
+        }
+        KeyCode::Char('Z') => {
+            if let Some(conv) = &mut app.conversation {
+                conv.toggle_fold_all();
+            }
+            Action::Continue
+        }
+        KeyCode::Char('f') => {
+            app.toggle_follow();
+            Action::Continue
+        }
+        KeyCode::Char('c') => {
+            app.toggle_side_panel(SidePanel::Commits);
+            Action::Continue
+        }
+        KeyCode::Char('F') => {
+            app.toggle_side_panel(SidePanel::Files);
+            Action::Continue
+        }
+        KeyCode::Tab => {
+            app.next_side_panel_tab();
+            Action::Continue
+        }
+        KeyCode::Char('D') => {
+            app.toggle_combined_diff();
+            Action::Continue
+        }
         KeyCode::Char('n') => {
             jump_to_next_match(app);
             Action::Continue
@@ -212,19 +372,305 @@ fn handle_conversation(app: &mut App, key: KeyEvent) -> Action {
             app.mode = Mode::ConversationSearch;
             Action::Continue
         }
-        KeyCode::Enter => {
-            if let Some(conv) = &app.conversation {
-                let cmd = conv.session.resume_command();
-                Action::CopyCommand(cmd)
+        KeyCode::Enter => app.resume_action(),
+        _ => Action::Continue,
+    }
+}
+
+/// Keys in the side-by-side comparison.
+fn handle_compare(app: &mut App, key: KeyEvent) -> Action {
+    if matches!(key.code, KeyCode::Esc | KeyCode::Char('q')) {
+        app.leave_compare();
+        return Action::Continue;
+    }
+    let Some(compare) = &mut app.compare else {
+        return Action::Continue;
+    };
+    let page = compare.panes[compare.focus].page_height as isize;
+    match key.code {
+        KeyCode::Tab | KeyCode::Left | KeyCode::Right => compare.focus = 1 - compare.focus,
+        KeyCode::Char('l') => compare.locked = !compare.locked,
+        KeyCode::Char(' ') | KeyCode::PageDown => compare.scroll_by(page),
+        KeyCode::Char('b') | KeyCode::PageUp => compare.scroll_by(-page),
+        KeyCode::Char('j') | KeyCode::Down => compare.scroll_by(1),
+        KeyCode::Char('k') | KeyCode::Up => compare.scroll_by(-1),
+        KeyCode::Char('g') => compare.scroll_by(isize::MIN),
+        KeyCode::Char('G') => compare.scroll_by(isize::MAX),
+        _ => {}
+    }
+    Action::Continue
+}
+
+/// Keys while the table of contents has focus: move the cursor, jump to
+/// the prompt, or hand focus back to the conversation.
+fn handle_toc(app: &mut App, key: KeyEvent) -> Action {
+    if key.code == KeyCode::Char('t') {
+        app.toggle_toc();
+        return Action::Continue;
+    }
+    let Some(conv) = &mut app.conversation else {
+        return Action::Continue;
+    };
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => conv.move_toc(1),
+        KeyCode::Char('k') | KeyCode::Up => conv.move_toc(-1),
+        KeyCode::PageDown => conv.move_toc(conv.page_height as isize),
+        KeyCode::PageUp => conv.move_toc(-(conv.page_height as isize)),
+        KeyCode::Char('g') | KeyCode::Home => conv.move_toc(isize::MIN),
+        KeyCode::Char('G') | KeyCode::End => conv.move_toc(isize::MAX),
+        KeyCode::Enter => conv.jump_to_toc_cursor(),
+        KeyCode::Esc => {
+            if let Some(toc) = &mut conv.toc {
+                toc.focused = false;
+            }
+        }
+        _ => {}
+    }
+    Action::Continue
+}
+
+/// Keys in yank mode: `y`/`m` copy the message at the top of the view, `e`
+/// the exchange as Markdown, and digits the numbered code block. The block
+/// is copied as soon as the number is unambiguous, or on Enter.
+fn handle_yank(app: &mut App, key: KeyEvent) -> Action {
+    let Some(conv) = &mut app.conversation else {
+        return Action::Continue;
+    };
+    let mut digits = conv.yank.take().unwrap_or_default();
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Char('m') => {
+            let text = conv.message_text();
+            app.yank_text("message", text);
+        }
+        KeyCode::Char('e') => {
+            let text = conv.exchange_markdown();
+            app.yank_text("exchange", text);
+        }
+        KeyCode::Char(c) if c.is_ascii_digit() => {
+            digits.push(c);
+            let n: usize = digits.parse().unwrap_or(0);
+            if n == 0 {
+                return Action::Continue;
+            }
+            if n.saturating_mul(10) > conv.code_block_count() {
+                let text = conv.code_block(n).map(|b| b.code);
+                app.yank_text(&format!("code block {n}"), text);
             } else {
-                Action::Continue
+                conv.yank = Some(digits);
             }
         }
-        _ => Action::Continue,
+        KeyCode::Enter if !digits.is_empty() => {
+            let n: usize = digits.parse().unwrap_or(0);
+            let text = conv.code_block(n).map(|b| b.code);
+            app.yank_text(&format!("code block {n}"), text);
+        }
+        _ => {}
     }
+    Action::Continue
+}
+
+/// Keys in visual mode: move the cursor to extend the selection, `y` to
+/// copy the source text of the selected lines, Esc to cancel.
+fn handle_visual(app: &mut App, key: KeyEvent) -> Action {
+    let Some(conv) = &mut app.conversation else {
+        return Action::Continue;
+    };
+    let page = conv.page_height as isize;
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => conv.move_visual(1),
+        KeyCode::Char('k') | KeyCode::Up => conv.move_visual(-1),
+        KeyCode::Char(' ') | KeyCode::PageDown => conv.move_visual(page),
+        KeyCode::Char('b') | KeyCode::PageUp => conv.move_visual(-page),
+        KeyCode::Char('g') | KeyCode::Home => conv.move_visual(isize::MIN),
+        KeyCode::Char('G') | KeyCode::End => conv.move_visual(isize::MAX),
+        KeyCode::Char('y') | KeyCode::Enter => {
+            if let Some(visual) = conv.visual.take() {
+                let (first, last) = visual.range();
+                let text = conv.selection_text(first, last);
+                app.yank_text("selection", text);
+            }
+        }
+        KeyCode::Esc | KeyCode::Char('v') | KeyCode::Char('q') => conv.visual = None,
+        _ => {}
+    }
+    Action::Continue
+}
+
+/// Keys in the views menu: move, Enter to show the selected view, `s` to
+/// save the current filter under a typed name, `d` to delete the selected
+/// view, `c` to clear the view, Esc to close.
+fn handle_view_menu(app: &mut App, key: KeyEvent) -> Action {
+    let Some(menu) = &mut app.view_menu else {
+        return Action::Continue;
+    };
+    if let Some(name) = &mut menu.naming {
+        match key.code {
+            KeyCode::Char(c) => name.push(c),
+            KeyCode::Backspace => {
+                name.pop();
+            }
+            KeyCode::Enter => {
+                let name = name.trim().to_string();
+                if !name.is_empty() {
+                    app.view_menu = None;
+                    app.save_view(&name);
+                }
+            }
+            KeyCode::Esc => menu.naming = None,
+            _ => {}
+        }
+        return Action::Continue;
+    }
+    let selected = app.views.keys().nth(menu.cursor).cloned();
+    let last = app.views.len().saturating_sub(1);
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => menu.cursor = (menu.cursor + 1).min(last),
+        KeyCode::Char('k') | KeyCode::Up => menu.cursor = menu.cursor.saturating_sub(1),
+        KeyCode::Char('s') => menu.naming = Some(String::new()),
+        KeyCode::Char('d') => {
+            if let Some(name) = selected {
+                menu.cursor = menu.cursor.min(last.saturating_sub(1));
+                app.delete_view(&name);
+            }
+        }
+        KeyCode::Char('c') => {
+            app.view_menu = None;
+            app.clear_view();
+        }
+        KeyCode::Enter => {
+            if let Some(name) = selected {
+                app.view_menu = None;
+                if let Err(e) = app.apply_view(&name) {
+                    app.set_status(e);
+                }
+            }
+        }
+        KeyCode::Esc | KeyCode::Char('q') => app.view_menu = None,
+        KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => app.view_menu = None,
+        _ => {}
+    }
+    Action::Continue
+}
+
+/// Keys in the sort menu: move, Enter to sort by the highlighted key, `r`
+/// to reverse the order, Esc to close.
+fn handle_sort_menu(app: &mut App, key: KeyEvent) -> Action {
+    let Some(menu) = &mut app.sort_menu else {
+        return Action::Continue;
+    };
+    let last = SortKey::ALL.len() - 1;
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => menu.cursor = (menu.cursor + 1).min(last),
+        KeyCode::Char('k') | KeyCode::Up => menu.cursor = menu.cursor.saturating_sub(1),
+        KeyCode::Char('r') => app.set_sort(app.sort, !app.sort_reverse),
+        KeyCode::Enter => {
+            if let Some(menu) = app.sort_menu.take() {
+                app.set_sort(SortKey::ALL[menu.cursor], app.sort_reverse);
+            }
+        }
+        KeyCode::Esc | KeyCode::Char('q') => app.sort_menu = None,
+        KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => app.sort_menu = None,
+        _ => {}
+    }
+    Action::Continue
+}
+
+/// Keys in the relocate menu: move, Enter to remap the project to the
+/// selected location, Esc to close.
+fn handle_relocate(app: &mut App, key: KeyEvent) -> Action {
+    let Some(menu) = &mut app.relocate else {
+        return Action::Continue;
+    };
+    let last = menu.suggestions.len().saturating_sub(1);
+    match key.code {
+        KeyCode::Char('j') | KeyCode::Down => menu.cursor = (menu.cursor + 1).min(last),
+        KeyCode::Char('k') | KeyCode::Up => menu.cursor = menu.cursor.saturating_sub(1),
+        KeyCode::Enter => {
+            if let Some(menu) = app.relocate.take() {
+                if let Some(target) = menu.suggestions.get(menu.cursor) {
+                    app.relocate_project(&menu.project, &target.path);
+                }
+            }
+        }
+        KeyCode::Esc | KeyCode::Char('q') => app.relocate = None,
+        KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => app.relocate = None,
+        _ => {}
+    }
+    Action::Continue
+}
+
+/// Keys during a Ctrl-R history search: typing narrows it, Ctrl-R finds
+/// the next older match, Enter puts the match into the query being edited,
+/// and Esc cancels.
+fn handle_history_search(app: &mut App, key: KeyEvent) -> Action {
+    let history = if app.mode == Mode::ConversationSearch {
+        &app.search_history
+    } else {
+        &app.filter_history
+    };
+    let newest = history.entries().len();
+    let Some(search) = &mut app.history_search else {
+        return Action::Continue;
+    };
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Char('r') if ctrl => {
+            let before = search.found.unwrap_or(newest);
+            if let Some(found) = history.find(&search.query, before) {
+                search.found = Some(found);
+            }
+        }
+        KeyCode::Char('g') if ctrl => app.history_search = None,
+        KeyCode::Esc => app.history_search = None,
+        KeyCode::Char(c) => {
+            search.query.push(c);
+            search.found = history.find(&search.query, newest);
+        }
+        KeyCode::Backspace => {
+            search.query.pop();
+            search.found = history.find(&search.query, newest);
+        }
+        KeyCode::Enter => {
+            let found = search.found.and_then(|i| history.entries().get(i).cloned());
+            app.history_search = None;
+            if let Some(query) = found {
+                app.set_edited_query(query);
+            }
+        }
+        _ => {}
+    }
+    Action::Continue
+}
+
+/// Keys while the combined diff is shown: scrolling, and D/Esc/q to close.
+fn handle_combined_diff(app: &mut App, key: KeyEvent) -> Action {
+    if matches!(key.code, KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('D')) {
+        app.toggle_combined_diff();
+        return Action::Continue;
+    }
+    let Some(diff) = app.conversation.as_mut().and_then(|c| c.combined_diff.as_mut()) else {
+        return Action::Continue;
+    };
+    let page = diff.page_height as isize;
+    match key.code {
+        KeyCode::Char(' ') | KeyCode::PageDown => diff.scroll_by(page),
+        KeyCode::Char('b') | KeyCode::PageUp => diff.scroll_by(-page),
+        KeyCode::Char('j') | KeyCode::Down => diff.scroll_by(1),
+        KeyCode::Char('k') | KeyCode::Up => diff.scroll_by(-1),
+        KeyCode::Char('g') => diff.scroll_offset = 0,
+        KeyCode::Char('G') => diff.scroll_by(isize::MAX),
+        _ => {}
+    }
+    Action::Continue
 }
 
 fn handle_conversation_search(app: &mut App, key: KeyEvent) -> Action {
+    if app.history_search.is_some() {
+        return handle_history_search(app, key);
+    }
+    if !matches!(key.code, KeyCode::Up | KeyCode::Down) {
+        app.recall = None;
+    }
     match key.code {
         KeyCode::Esc => {
             if let Some(conv) = &mut app.conversation {
@@ -238,6 +684,9 @@ fn handle_conversation_search(app: &mut App, key: KeyEvent) -> Action {
             Action::Continue
         }
         KeyCode::Enter => {
+            if let Some(query) = app.conversation.as_ref().map(|c| c.search_query.clone()) {
+                app.remember_search(&query);
+            }
             if let Some(conv) = &mut app.conversation {
                 conv.search_active = false;
                 conv.search_replacing = false;
@@ -289,6 +738,30 @@ fn handle_conversation_search(app: &mut App, key: KeyEvent) -> Action {
             }
             Action::Continue
         }
+        KeyCode::Up => {
+            app.recall_history(true);
+            Action::Continue
+        }
+        KeyCode::Down => {
+            app.recall_history(false);
+            Action::Continue
+        }
+        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.history_search = Some(HistorySearch::default());
+            Action::Continue
+        }
+        KeyCode::Char(c @ ('r' | 'c' | 'w')) if key.modifiers.contains(KeyModifiers::ALT) => {
+            if let Some(conv) = &mut app.conversation {
+                let options = &mut conv.search_options;
+                match c {
+                    'r' => options.regex = !options.regex,
+                    'c' => options.case_sensitive = !options.case_sensitive,
+                    _ => options.whole_word = !options.whole_word,
+                }
+                conv.rendered_width = 0;
+            }
+            Action::Continue
+        }
         KeyCode::Char(c) => {
             if let Some(conv) = &mut app.conversation {
                 if conv.search_replacing {
@@ -335,3 +808,161 @@ fn jump_to_prev_match(app: &mut App) {
             .min(max);
     }
 }
+
+/// Lines scrolled per mouse wheel step.
+const WHEEL_LINES: usize = 3;
+
+/// Longest interval between the clicks of a double click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Handle a mouse event and return the resulting action.
+pub fn handle_mouse(app: &mut App, mouse: MouseEvent) -> Action {
+    if app.confirm.is_some() {
+        return Action::Continue;
+    }
+    let pos = Position::new(mouse.column, mouse.row);
+    if let MouseEventKind::Up(MouseButton::Left) = mouse.kind {
+        app.dragging_scrollbar = false;
+        return Action::Continue;
+    }
+    match app.mode {
+        Mode::Browsing => handle_list_mouse(app, mouse.kind, pos),
+        Mode::Conversation | Mode::ConversationSearch => {
+            handle_conversation_mouse(app, mouse.kind, pos);
+            Action::Continue
+        }
+        Mode::Compare => {
+            handle_compare_mouse(app, mouse.kind, pos);
+            Action::Continue
+        }
+    }
+}
+
+/// Position of `y` along a vertical scrollbar track, from 0.0 to 1.0.
+fn track_fraction(track: Rect, y: u16) -> f64 {
+    let span = track.height.saturating_sub(1).max(1);
+    f64::from(y.saturating_sub(track.y).min(span)) / f64::from(span)
+}
+
+/// Whether `pos` starts or continues a drag of the scrollbar on `track`.
+fn on_scrollbar(app: &mut App, kind: MouseEventKind, pos: Position, track: Rect) -> bool {
+    match kind {
+        MouseEventKind::Down(MouseButton::Left) if track.contains(pos) => {
+            app.dragging_scrollbar = true;
+            true
+        }
+        MouseEventKind::Drag(MouseButton::Left) => app.dragging_scrollbar,
+        _ => false,
+    }
+}
+
+fn handle_list_mouse(app: &mut App, kind: MouseEventKind, pos: Position) -> Action {
+    let list = app.hit_areas.list;
+    let inner = list.inner(Margin::new(1, 1));
+    let track = Rect {
+        x: list.right().saturating_sub(1),
+        width: 1,
+        ..list.inner(Margin::new(0, 1))
+    };
+    let rows = app.row_count();
+    if on_scrollbar(app, kind, pos, track) {
+        app.selected = (track_fraction(track, pos.y) * rows.saturating_sub(1) as f64).round() as usize;
+        return Action::Continue;
+    }
+    match kind {
+        MouseEventKind::ScrollDown => {
+            for _ in 0..WHEEL_LINES {
+                app.move_down();
+            }
+        }
+        MouseEventKind::ScrollUp => {
+            for _ in 0..WHEEL_LINES {
+                app.move_up();
+            }
+        }
+        MouseEventKind::Down(MouseButton::Left) if inner.contains(pos) => {
+            let row = app.scroll_offset + usize::from(pos.y - inner.y) / app.density.row_height();
+            if row >= rows {
+                return Action::Continue;
+            }
+            app.selected = row;
+            let double = app
+                .last_click
+                .is_some_and(|(when, last)| last == row && when.elapsed() < DOUBLE_CLICK);
+            if double {
+                app.last_click = None;
+                return activate_selection(app);
+            }
+            app.last_click = Some((Instant::now(), row));
+        }
+        _ => {}
+    }
+    Action::Continue
+}
+
+fn handle_conversation_mouse(app: &mut App, kind: MouseEventKind, pos: Position) {
+    let track = app.hit_areas.conversation_scrollbar;
+    let area = app.hit_areas.conversation;
+    let toc_area = app.hit_areas.toc;
+    let scrolling = on_scrollbar(app, kind, pos, track);
+    let Some(conv) = &mut app.conversation else {
+        return;
+    };
+    if let Some(diff) = &mut conv.combined_diff {
+        match kind {
+            MouseEventKind::ScrollDown => diff.scroll_by(WHEEL_LINES as isize),
+            MouseEventKind::ScrollUp => diff.scroll_by(-(WHEEL_LINES as isize)),
+            _ if scrolling => {
+                let max = diff.lines.len().saturating_sub(diff.page_height);
+                diff.scroll_offset = (track_fraction(track, pos.y) * max as f64).round() as usize;
+            }
+            _ => {}
+        }
+        return;
+    }
+    match kind {
+        MouseEventKind::ScrollDown => conv.scroll_by(WHEEL_LINES as isize),
+        MouseEventKind::ScrollUp => conv.scroll_by(-(WHEEL_LINES as isize)),
+        _ if scrolling => {
+            let max = conv.lines.len().saturating_sub(conv.page_height);
+            conv.scroll_offset = (track_fraction(track, pos.y) * max as f64).round() as usize;
+        }
+        MouseEventKind::Down(MouseButton::Left) if toc_area.contains(pos) => {
+            let count = conv.activity.as_ref().map_or(0, |a| a.prompts.len());
+            if let Some(toc) = &mut conv.toc {
+                let row = toc.offset + usize::from(pos.y - toc_area.y);
+                if row >= count {
+                    return;
+                }
+                toc.cursor = row;
+            }
+            conv.jump_to_toc_cursor();
+        }
+        MouseEventKind::Down(MouseButton::Left) if area.contains(pos) => {
+            let line = conv.scroll_offset + usize::from(pos.y - area.y);
+            app.open_link_at(line, usize::from(pos.x - area.x));
+        }
+        _ => {}
+    }
+}
+
+fn handle_compare_mouse(app: &mut App, kind: MouseEventKind, pos: Position) {
+    let panes = app.hit_areas.compare;
+    let Some(compare) = &mut app.compare else {
+        return;
+    };
+    let Some(pane) = panes.iter().position(|p| p.contains(pos)) else {
+        return;
+    };
+    match kind {
+        MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
+            if !compare.locked {
+                compare.focus = pane;
+            }
+            let delta = WHEEL_LINES as isize;
+            compare.scroll_by(if kind == MouseEventKind::ScrollUp { -delta } else { delta });
+        }
+        MouseEventKind::Down(MouseButton::Left) => compare.focus = pane,
+        _ => {}
+    }
+}
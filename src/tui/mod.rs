@@ -1,31 +1,55 @@
 pub mod input;
+pub mod preview;
+pub mod stats;
 pub mod syntax;
 pub mod table;
+pub mod tree;
 pub mod view;
+pub mod yank;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::io::stdout;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{mpsc, Arc};
 use std::time::{Duration, Instant};
 
-use chrono::{DateTime, Utc};
-use crossterm::event::{self, Event};
+use chrono::{DateTime, Local, Utc};
+use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event};
 use crossterm::execute;
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
 };
 use ratatui::prelude::*;
+use regex::Regex;
 
+use crate::activity::{scan_session_activity, SessionActivity};
 use crate::clipboard;
-use crate::discovery::{get_claude_home, load_conversation};
-use crate::filter::filter_sessions;
-use crate::search;
-use crate::session::{ConversationMessage, Session};
+use crate::columns::{Column, Density, DEFAULT_COLUMNS};
+use crate::compress;
+use crate::config::{self, DEFAULT_HISTORY_SIZE};
+use crate::diff;
+use crate::discovery::{
+    apply_filters, get_claude_home, load_conversation, parse_session_file, session_file_path,
+    ConversationTail,
+};
+use crate::filter::{filter_sessions, relevance};
+use crate::git::{Commit, CommitsLoader};
+use crate::history::History;
+use crate::live::{self, LiveScanner, LiveSession};
+use crate::open;
+use crate::remap::{self, LocationFinder, PathRemapper, PathRule, Suggestion};
+use crate::search::{self, SearchOptions};
+use crate::session::{ConversationMessage, MessageRole, Session};
+use crate::sort::{self, SessionStats, SortItem, SortKey};
 use crate::theme::Theme;
+use crate::views::{self, Scope, View};
+use crate::watch::SessionWatcher;
 
-use input::handle_input;
+use input::{handle_input, handle_mouse};
+use preview::PreviewLoader;
+use stats::StatsLoader;
+use tree::{ListLayout, RowKey, TreeRow};
 
 /// TUI interaction mode.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -33,6 +57,7 @@ pub enum Mode {
     Browsing,
     Conversation,
     ConversationSearch,
+    Compare,
 }
 
 /// Phase of the background content search.
@@ -77,6 +102,42 @@ pub enum Action {
     BackToList,
 }
 
+/// A yes/no question shown in the status bar before a consequential action.
+#[derive(Debug, Clone)]
+pub enum Confirm {
+    /// The session file is compressed; decompress it, then copy `command`.
+    Decompress { path: PathBuf, command: String },
+    /// The session is open in another terminal; copy `command` anyway,
+    /// or switch to the tmux pane it runs in.
+    LiveSession {
+        command: String,
+        live: LiveSession,
+    },
+}
+
+impl Confirm {
+    /// The question to show in the status bar.
+    pub fn question(&self) -> String {
+        match self {
+            Confirm::Decompress { .. } => {
+                "Session is compressed. Decompress it before resuming? (y/n)".to_string()
+            }
+            Confirm::LiveSession { live, .. } => {
+                let pid = live
+                    .pid
+                    .map(|pid| format!(" (pid {pid})"))
+                    .unwrap_or_default();
+                let tmux = if live.tmux_pane.is_some() {
+                    ", t switch to its tmux pane"
+                } else {
+                    ""
+                };
+                format!("Session is running in another terminal{pid}. Resume anyway? (y/n{tmux})")
+            }
+        }
+    }
+}
+
 /// State for the conversation viewer.
 pub struct ConversationState {
     pub session: Session,
@@ -95,6 +156,473 @@ pub struct ConversationState {
     pub match_positions: Vec<usize>,
     pub current_match: usize,
     pub initial_search_terms: Vec<String>,
+    /// Regex, case, and whole-word modes of the search.
+    pub search_options: SearchOptions,
+    /// Line index at which each message's header starts in `lines`.
+    pub message_starts: Vec<usize>,
+    /// Incremental reader for plain session files (None if compressed).
+    pub tail: Option<ConversationTail>,
+    /// Follow mode: merge lines appended to the session file as they arrive.
+    pub follow: bool,
+    /// First message index whose rendering is stale after a follow update.
+    pub dirty_from: Option<usize>,
+    /// Side panel shown next to the conversation, if any.
+    pub side_panel: Option<SidePanel>,
+    /// Commits made during the session, looked up in the background when
+    /// first shown.
+    pub commits: Option<Result<Vec<Commit>, String>>,
+    /// Files touched by the session's tool calls, loaded when first shown.
+    pub activity: Option<SessionActivity>,
+    /// Combined diff shown in place of the conversation, if open.
+    pub combined_diff: Option<CombinedDiff>,
+    /// Folded and collapsed messages.
+    pub folds: Folds,
+    /// Message to scroll to once the conversation is re-rendered.
+    pub anchor_message: Option<usize>,
+    /// Table of contents of the user prompts, if open.
+    pub toc: Option<Toc>,
+    /// Digits typed in yank mode, or None when not yanking.
+    pub yank: Option<String>,
+    /// Source text of each rendered line, parallel to `lines`. None for
+    /// headers, labels, fold markers, and blank separators.
+    pub line_sources: Vec<Option<SourceLines>>,
+    /// Line selection of visual mode, if active.
+    pub visual: Option<Visual>,
+}
+
+/// Lines `start..end` of message `message`'s text that a rendered line
+/// was produced from. Wrapped lines share the range of their source line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceLines {
+    pub message: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Range of rendered lines selected in visual mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Visual {
+    /// Line where the selection started.
+    pub anchor: usize,
+    /// Line the selection extends to.
+    pub cursor: usize,
+}
+
+impl Visual {
+    /// First and last selected line.
+    pub fn range(&self) -> (usize, usize) {
+        (self.anchor.min(self.cursor), self.anchor.max(self.cursor))
+    }
+}
+
+/// Table-of-contents sidebar listing the session's user prompts.
+#[derive(Debug, Clone)]
+pub struct Toc {
+    /// Prompt under the cursor.
+    pub cursor: usize,
+    /// Keys move the cursor instead of scrolling the conversation.
+    pub focused: bool,
+    /// First prompt shown, kept by the renderer so the cursor stays visible.
+    pub offset: usize,
+}
+
+/// Folding state of the messages in a conversation.
+#[derive(Debug, Clone, Default)]
+pub struct Folds {
+    /// Messages folded (true) or unfolded (false) by hand.
+    pub overrides: HashMap<usize, bool>,
+    /// Fold every message without an override to its header.
+    pub all: bool,
+    /// Collapse message bodies longer than this many lines.
+    pub collapse_after: Option<usize>,
+    /// Messages shown folded or collapsed in the last rendering.
+    pub shown_folded: HashSet<usize>,
+}
+
+impl Folds {
+    /// Number of body lines to show of message `idx` with `body_len` lines,
+    /// or None to show all of them. Messages with search matches are not
+    /// collapsed automatically.
+    pub fn body_limit(&self, idx: usize, body_len: usize, has_match: bool) -> Option<usize> {
+        match self.overrides.get(&idx) {
+            Some(true) => return Some(0),
+            Some(false) => return None,
+            None => {}
+        }
+        if self.all {
+            return Some(0);
+        }
+        match self.collapse_after {
+            Some(limit) if body_len > limit && !has_match => Some(limit),
+            _ => None,
+        }
+    }
+
+    /// Fold message `idx` if it is shown in full, otherwise unfold it.
+    pub fn toggle(&mut self, idx: usize) {
+        let folded = self.shown_folded.contains(&idx);
+        self.overrides.insert(idx, !folded);
+    }
+
+    /// Fold all messages to their headers, or unfold them again.
+    pub fn toggle_all(&mut self) {
+        self.all = !self.all;
+        self.overrides.clear();
+    }
+}
+
+impl ConversationState {
+    /// Load a session's conversation. A non-empty `filter_query` is carried
+    /// over as initial search highlight.
+    pub fn load(session: Session, filter_query: &str) -> Self {
+        let claude_home = get_claude_home();
+        let path = session_file_path(&claude_home, &session);
+        let (messages, tail) = if compress::compression_of(&path).is_none() {
+            let mut tail = ConversationTail::new(path);
+            let mut messages = Vec::new();
+            tail.read_all(&mut messages);
+            (messages, Some(tail))
+        } else {
+            (load_conversation(&claude_home, &session), None)
+        };
+
+        let initial_search_terms: Vec<String> = {
+            let trimmed = filter_query.trim();
+            if !trimmed.is_empty() {
+                vec![trimmed.to_string()]
+            } else {
+                Vec::new()
+            }
+        };
+
+        ConversationState {
+            session,
+            messages,
+            lines: Vec::new(),
+            scroll_offset: 0,
+            page_height: 20,
+            rendered_width: 0,
+            search_query: String::new(),
+            search_active: false,
+            search_confirmed: false,
+            search_replacing: false,
+            search_cursor: 0,
+            match_positions: Vec::new(),
+            current_match: 0,
+            // Filter terms found the session as a deep search regex
+            search_options: SearchOptions {
+                regex: initial_search_terms
+                    .first()
+                    .is_some_and(|t| search::build_search_regex(t).is_ok()),
+                ..SearchOptions::default()
+            },
+            initial_search_terms,
+            message_starts: Vec::new(),
+            tail,
+            follow: false,
+            dirty_from: None,
+            side_panel: None,
+            commits: None,
+            activity: None,
+            combined_diff: None,
+            folds: Folds::default(),
+            anchor_message: None,
+            toc: None,
+            yank: None,
+            line_sources: Vec::new(),
+            visual: None,
+        }
+    }
+
+    /// Text of the message at the top of the view.
+    pub fn message_text(&self) -> Option<String> {
+        let idx = self.message_at(self.scroll_offset)?;
+        Some(self.messages[idx].text.clone())
+    }
+
+    /// The exchange at the top of the view (a prompt and its reply) as
+    /// Markdown with attribution.
+    pub fn exchange_markdown(&self) -> Option<String> {
+        let idx = self.message_at(self.scroll_offset)?;
+        let start = match self.messages[idx].role {
+            MessageRole::User => idx,
+            MessageRole::Assistant if idx > 0 => idx - 1,
+            MessageRole::Assistant => idx,
+        };
+        let exchange: Vec<&ConversationMessage> = self.messages[start..]
+            .iter()
+            .take(2)
+            .enumerate()
+            .take_while(|(i, m)| *i == 0 || m.role == MessageRole::Assistant)
+            .map(|(_, m)| m)
+            .collect();
+        Some(yank::exchange_markdown(&self.session, &exchange))
+    }
+
+    /// Code block number `n`, counting from 1 across the conversation.
+    pub fn code_block(&self, n: usize) -> Option<yank::CodeBlock> {
+        self.messages
+            .iter()
+            .flat_map(|m| yank::code_blocks(&m.text))
+            .nth(n.checked_sub(1)?)
+    }
+
+    /// Number of code blocks in the conversation.
+    pub fn code_block_count(&self) -> usize {
+        self.messages.iter().map(|m| yank::code_blocks(&m.text).len()).sum()
+    }
+
+    /// Pattern to highlight: the in-view query if any, otherwise the terms
+    /// carried over from the list filter. Errors if the regex is invalid.
+    pub fn search_regex(&self) -> Result<Option<Regex>, String> {
+        let pattern = if self.search_query.is_empty() {
+            self.initial_search_terms.join(" ")
+        } else {
+            self.search_query.clone()
+        };
+        if pattern.is_empty() {
+            return Ok(None);
+        }
+        search::build_pattern_regex(&pattern, self.search_options)
+            .map(Some)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Start visual mode with the top line of the view selected.
+    pub fn start_visual(&mut self) {
+        let line = self.scroll_offset.min(self.lines.len().saturating_sub(1));
+        self.visual = Some(Visual {
+            anchor: line,
+            cursor: line,
+        });
+    }
+
+    /// Move the visual cursor by `delta` lines, scrolling to keep it in view.
+    pub fn move_visual(&mut self, delta: isize) {
+        let last = self.lines.len().saturating_sub(1);
+        let Some(visual) = &mut self.visual else {
+            return;
+        };
+        visual.cursor = visual.cursor.saturating_add_signed(delta).min(last);
+        let cursor = visual.cursor;
+        if cursor < self.scroll_offset {
+            self.scroll_offset = cursor;
+        } else if cursor >= self.scroll_offset + self.page_height {
+            self.scroll_offset = cursor + 1 - self.page_height.max(1);
+        }
+    }
+
+    /// Source text of rendered lines `first..=last`: for each message the
+    /// selection touches, the lines its rendered lines came from, with a
+    /// blank line between messages.
+    pub fn selection_text(&self, first: usize, last: usize) -> Option<String> {
+        let mut ranges: Vec<SourceLines> = Vec::new();
+        for source in self.line_sources.iter().take(last + 1).skip(first).flatten() {
+            match ranges.last_mut() {
+                Some(range) if range.message == source.message => {
+                    range.start = range.start.min(source.start);
+                    range.end = range.end.max(source.end);
+                }
+                _ => ranges.push(*source),
+            }
+        }
+        let parts: Vec<String> = ranges
+            .iter()
+            .filter_map(|range| {
+                let text: Vec<&str> = self.messages[range.message]
+                    .text
+                    .lines()
+                    .skip(range.start)
+                    .take(range.end - range.start)
+                    .collect();
+                (!text.is_empty()).then(|| text.join("\n"))
+            })
+            .collect();
+        (!parts.is_empty()).then(|| parts.join("\n\n"))
+    }
+
+    /// Scan the session's tool calls and prompts unless already done.
+    pub fn ensure_activity(&mut self) {
+        if self.activity.is_none() {
+            let path = session_file_path(&get_claude_home(), &self.session);
+            self.activity = Some(scan_session_activity(&path));
+        }
+    }
+
+    /// Message index of each prompt in `activity`: the first user message
+    /// at or after the prompt's time, which the prompt was merged into.
+    pub fn prompt_messages(&self) -> Vec<Option<usize>> {
+        let Some(activity) = &self.activity else {
+            return Vec::new();
+        };
+        let mut next = 0;
+        activity
+            .prompts
+            .iter()
+            .map(|prompt| {
+                let ts = prompt.timestamp?;
+                while next < self.messages.len()
+                    && (self.messages[next].role != MessageRole::User
+                        || self.messages[next].timestamp < ts)
+                {
+                    next += 1;
+                }
+                (next < self.messages.len()).then_some(next)
+            })
+            .collect()
+    }
+
+    /// Prompt of the table of contents currently at the top of the view.
+    pub fn current_prompt(&self) -> Option<usize> {
+        let current = self.message_at(self.scroll_offset)?;
+        self.prompt_messages()
+            .iter()
+            .rposition(|m| m.is_some_and(|m| m <= current))
+    }
+
+    /// Move the table-of-contents cursor by `delta` prompts.
+    pub fn move_toc(&mut self, delta: isize) {
+        let count = self.activity.as_ref().map_or(0, |a| a.prompts.len());
+        if let Some(toc) = &mut self.toc {
+            toc.cursor = toc
+                .cursor
+                .saturating_add_signed(delta)
+                .min(count.saturating_sub(1));
+        }
+    }
+
+    /// Scroll to the prompt under the table-of-contents cursor and return
+    /// focus to the conversation.
+    pub fn jump_to_toc_cursor(&mut self) {
+        let Some(toc) = &mut self.toc else {
+            return;
+        };
+        toc.focused = false;
+        let cursor = toc.cursor;
+        if let Some(Some(idx)) = self.prompt_messages().get(cursor) {
+            if let Some(&start) = self.message_starts.get(*idx) {
+                let max = self.lines.len().saturating_sub(self.page_height);
+                self.scroll_offset = start.min(max);
+            }
+        }
+    }
+
+    /// Index of the message shown at rendered line `line`.
+    pub fn message_at(&self, line: usize) -> Option<usize> {
+        self.message_starts
+            .partition_point(|&start| start <= line)
+            .checked_sub(1)
+    }
+
+    /// Scroll to the next (or previous) message from `role`, relative to the
+    /// top of the view.
+    pub fn jump_to_message(&mut self, role: MessageRole, forward: bool) {
+        let offset = self.scroll_offset;
+        let mut candidates = self
+            .message_starts
+            .iter()
+            .zip(&self.messages)
+            .filter(|(_, msg)| msg.role == role)
+            .map(|(&start, _)| start);
+        let target = if forward {
+            candidates.find(|&start| start > offset)
+        } else {
+            candidates.rev().find(|&start| start < offset)
+        };
+        if let Some(start) = target {
+            let max = self.lines.len().saturating_sub(self.page_height);
+            self.scroll_offset = start.min(max);
+        }
+    }
+
+    /// Fold or unfold the message at the top of the view.
+    pub fn toggle_fold(&mut self) {
+        if let Some(idx) = self.message_at(self.scroll_offset) {
+            self.folds.toggle(idx);
+            self.anchor_message = Some(idx);
+            self.rendered_width = 0;
+        }
+    }
+
+    /// Fold all messages to their headers, or unfold them again.
+    pub fn toggle_fold_all(&mut self) {
+        self.anchor_message = self.message_at(self.scroll_offset);
+        self.folds.toggle_all();
+        self.rendered_width = 0;
+    }
+
+    /// Scroll by `delta` lines, clamped to the content.
+    pub fn scroll_by(&mut self, delta: isize) {
+        let max = self.lines.len().saturating_sub(self.page_height);
+        self.scroll_offset = self.scroll_offset.saturating_add_signed(delta).min(max);
+    }
+}
+
+/// Two sessions shown side by side.
+pub struct CompareState {
+    pub panes: [ConversationState; 2],
+    /// Turns, tokens, tools, and files of each session, for the summary.
+    pub activity: [SessionActivity; 2],
+    /// Pane that receives scroll keys when not locked.
+    pub focus: usize,
+    /// Scroll both panes together.
+    pub locked: bool,
+}
+
+impl CompareState {
+    /// Scroll the focused pane, or both panes when locked.
+    pub fn scroll_by(&mut self, delta: isize) {
+        if self.locked {
+            for pane in &mut self.panes {
+                pane.scroll_by(delta);
+            }
+        } else {
+            self.panes[self.focus].scroll_by(delta);
+        }
+    }
+}
+
+/// All edits the session made to one file, shown as one scrollable diff.
+pub struct CombinedDiff {
+    pub path: String,
+    /// Diff lines of each edit with the time of its message, in session order.
+    pub edits: Vec<(DateTime<Utc>, Vec<String>)>,
+    pub lines: Vec<Line<'static>>,
+    pub rendered_width: u16,
+    pub scroll_offset: usize,
+    pub page_height: usize,
+}
+
+impl CombinedDiff {
+    /// Scroll by `delta` lines, clamped to the content.
+    pub fn scroll_by(&mut self, delta: isize) {
+        let max = self.lines.len().saturating_sub(self.page_height);
+        self.scroll_offset = self.scroll_offset.saturating_add_signed(delta).min(max);
+    }
+}
+
+/// Side panel tabs of the conversation viewer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SidePanel {
+    Commits,
+    Files,
+}
+
+impl SidePanel {
+    pub const ALL: [SidePanel; 2] = [SidePanel::Commits, SidePanel::Files];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            SidePanel::Commits => "Commits",
+            SidePanel::Files => "Files",
+        }
+    }
+
+    /// The tab after this one, wrapping around.
+    pub fn next(self) -> SidePanel {
+        let idx = Self::ALL.iter().position(|p| *p == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
 }
 
 /// Application state for the TUI.
@@ -128,6 +656,148 @@ pub struct App {
     pub theme: Theme,
     /// Syntax highlighter for code blocks.
     pub syntax_highlighter: syntax::SyntaxHighlighter,
+    /// Pending yes/no confirmation, if any.
+    pub confirm: Option<Confirm>,
+    /// Flat list or project tree.
+    pub layout: ListLayout,
+    /// Rows of the project tree (empty in flat layout).
+    pub tree_rows: Vec<TreeRow>,
+    /// Project paths expanded in the tree.
+    pub expanded_projects: HashSet<String>,
+    /// Nest tree sessions under per-branch rows.
+    pub group_by_branch: bool,
+    /// Filesystem watcher feeding live session list updates.
+    pub watcher: Option<SessionWatcher>,
+    /// Session files changed since the last merge, waiting for the throttle.
+    pub pending_changes: BTreeSet<PathBuf>,
+    /// When watcher changes were last merged into the list.
+    pub last_merge: Instant,
+    /// Changed files to re-check once the in-flight content search completes.
+    pub content_recheck: Vec<PathBuf>,
+    /// Session IDs recently added or updated by the watcher, with the time of the change.
+    pub updated_at: HashMap<String, Instant>,
+    /// Sessions currently running in another terminal, keyed by session ID.
+    pub live: HashMap<String, LiveSession>,
+    /// When the last scan for running sessions was started.
+    pub last_live_scan: Option<Instant>,
+    /// Background detection of running sessions.
+    pub live_scanner: LiveScanner,
+    /// Background lookup of the commits shown in the conversation viewer.
+    pub commits_loader: CommitsLoader,
+    /// IDs of sessions marked for comparison, oldest first.
+    pub marked: Vec<String>,
+    /// Side-by-side comparison, if open.
+    pub compare: Option<CompareState>,
+    /// Whether the preview pane is shown next to the session list.
+    pub show_preview: bool,
+    /// Background loader and cache of session previews.
+    pub previews: PreviewLoader,
+    /// Screen areas of the last frame, for mouse hit testing.
+    pub hit_areas: HitAreas,
+    /// Time and list row of the last click, to detect double clicks.
+    pub last_click: Option<(Instant, usize)>,
+    /// A scrollbar is being dragged with the mouse.
+    pub dragging_scrollbar: bool,
+    /// Collapse conversation messages longer than this many lines.
+    pub collapse_after: Option<usize>,
+    /// Queries entered in the list filter.
+    pub filter_history: History,
+    /// Queries entered in the conversation search.
+    pub search_history: History,
+    /// History entry recalled with Up/Down into the query being edited.
+    pub recall: Option<Recall>,
+    /// Ctrl-R search over the history of the query being edited.
+    pub history_search: Option<HistorySearch>,
+    /// Project, branch, and time limits on the list, on top of the filter.
+    pub scope: Scope,
+    /// Saved views by name.
+    pub views: BTreeMap<String, View>,
+    /// File the views are saved to, or None to keep them in memory.
+    pub views_path: Option<PathBuf>,
+    /// Menu of saved views, if open.
+    pub view_menu: Option<ViewMenu>,
+    /// Menu of sort keys, if open.
+    pub sort_menu: Option<SortMenu>,
+    /// Order of the session list.
+    pub sort: SortKey,
+    pub sort_reverse: bool,
+    /// Message, token, and duration counts by session ID, scanned in the
+    /// background when a sort key or column first needs them.
+    pub stats: HashMap<String, SessionStats>,
+    pub stats_loader: StatsLoader,
+    /// Config file the sort order is saved to, or None to not save it.
+    pub config_path: Option<PathBuf>,
+    /// Columns shown after the prompt in the session list.
+    pub columns: Vec<Column>,
+    /// Lines per session row.
+    pub density: Density,
+    /// Rewrites of recorded project paths, and the rules they came from.
+    pub remapper: PathRemapper,
+    pub remap_rules: Vec<PathRule>,
+    /// Suggested new locations of a missing project, if open.
+    pub relocate: Option<RelocateMenu>,
+    /// Background search for the relocate menu's suggestions.
+    pub location_finder: LocationFinder,
+    /// Number of newest sessions the list is limited to (`--last`), kept as
+    /// the watcher adds sessions.
+    pub last: Option<usize>,
+}
+
+/// Menu of saved views, opened with Ctrl-V.
+#[derive(Debug, Clone, Default)]
+pub struct ViewMenu {
+    /// Selected view, by position in name order.
+    pub cursor: usize,
+    /// Name being typed to save the current filter as a view.
+    pub naming: Option<String>,
+}
+
+/// Menu of sort keys, opened with Ctrl-S.
+#[derive(Debug, Clone, Default)]
+pub struct SortMenu {
+    /// Highlighted key, by position in `SortKey::ALL`.
+    pub cursor: usize,
+}
+
+/// Likely new locations of a missing project, opened with Ctrl-L.
+#[derive(Debug, Clone)]
+pub struct RelocateMenu {
+    /// Recorded (or already remapped) project path.
+    pub project: String,
+    pub suggestions: Vec<Suggestion>,
+    pub cursor: usize,
+}
+
+/// Position of Up/Down recall in a search history.
+#[derive(Debug, Clone)]
+pub struct Recall {
+    /// Entry shown in the query; the history length stands for the draft.
+    pub index: usize,
+    /// Query typed before recalling started.
+    pub draft: String,
+}
+
+/// Incremental search over a search history, started with Ctrl-R.
+#[derive(Debug, Clone, Default)]
+pub struct HistorySearch {
+    pub query: String,
+    /// Index of the history entry matching the query.
+    pub found: Option<usize>,
+}
+
+/// Screen areas that react to the mouse, recorded while rendering.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HitAreas {
+    /// Session list, including its border.
+    pub list: Rect,
+    /// Conversation text, or the combined diff when open.
+    pub conversation: Rect,
+    /// Scrollbar track of the conversation viewer.
+    pub conversation_scrollbar: Rect,
+    /// Compare panes, including their borders.
+    pub compare: [Rect; 2],
+    /// Rows of the table of contents, if open.
+    pub toc: Rect,
 }
 
 impl App {
@@ -161,12 +831,372 @@ impl App {
             session_index: Arc::new(session_index),
             theme,
             syntax_highlighter: syntax::SyntaxHighlighter::new(),
+            confirm: None,
+            layout: ListLayout::Flat,
+            tree_rows: Vec::new(),
+            expanded_projects: HashSet::new(),
+            group_by_branch: false,
+            watcher: None,
+            pending_changes: BTreeSet::new(),
+            last_merge: Instant::now(),
+            content_recheck: Vec::new(),
+            updated_at: HashMap::new(),
+            live: HashMap::new(),
+            last_live_scan: None,
+            live_scanner: LiveScanner::new(get_claude_home()),
+            commits_loader: CommitsLoader::new(get_claude_home()),
+            marked: Vec::new(),
+            compare: None,
+            show_preview: false,
+            previews: PreviewLoader::new(get_claude_home()),
+            hit_areas: HitAreas::default(),
+            last_click: None,
+            dragging_scrollbar: false,
+            collapse_after: None,
+            filter_history: History::new(DEFAULT_HISTORY_SIZE),
+            search_history: History::new(DEFAULT_HISTORY_SIZE),
+            recall: None,
+            history_search: None,
+            scope: Scope::default(),
+            views: BTreeMap::new(),
+            views_path: None,
+            view_menu: None,
+            sort_menu: None,
+            sort: SortKey::default(),
+            sort_reverse: false,
+            stats: HashMap::new(),
+            stats_loader: StatsLoader::new(),
+            config_path: None,
+            columns: DEFAULT_COLUMNS.to_vec(),
+            density: Density::default(),
+            remapper: PathRemapper::default(),
+            remap_rules: Vec::new(),
+            relocate: None,
+            location_finder: LocationFinder::new(),
+            last: None,
+        }
+    }
+
+    /// Show the filter, scope, and sort of the saved view `name`.
+    pub fn apply_view(&mut self, name: &str) -> Result<(), String> {
+        let view = self
+            .views
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("No view named {name:?}"))?;
+        (self.sort, self.sort_reverse) = view.sort_order((self.sort, self.sort_reverse));
+        self.ensure_stats();
+        self.scope = view.scope;
+        self.filter_active = !view.query.is_empty();
+        self.set_filter_query(view.query);
+        Ok(())
+    }
+
+    /// Save the current filter, scope, and sort as view `name`, replacing
+    /// any view of that name.
+    pub fn save_view(&mut self, name: &str) {
+        let view = View {
+            query: self.filter_query.trim().to_string(),
+            scope: self.scope.clone(),
+            sort: Some(self.sort),
+            sort_reverse: Some(self.sort_reverse),
+        };
+        self.views.insert(name.to_string(), view);
+        match self.persist_views() {
+            Ok(()) => self.set_status(format!("Saved view {name:?}")),
+            Err(e) => self.set_status(e),
+        }
+    }
+
+    /// Delete the saved view `name`.
+    pub fn delete_view(&mut self, name: &str) {
+        if self.views.remove(name).is_none() {
+            return;
+        }
+        match self.persist_views() {
+            Ok(()) => self.set_status(format!("Deleted view {name:?}")),
+            Err(e) => self.set_status(e),
+        }
+    }
+
+    /// Drop the scope and filter to show all sessions again.
+    pub fn clear_view(&mut self) {
+        self.scope = Scope::default();
+        self.filter_active = false;
+        self.set_filter_query(String::new());
+    }
+
+    /// Step the time window through all, today, this week, and this month,
+    /// replacing any other time bounds in the scope.
+    pub fn cycle_time_window(&mut self) {
+        let next = match self.scope.since.as_deref() {
+            None => Some("today"),
+            Some("today") => Some("this-week"),
+            Some("this-week") => Some("this-month"),
+            Some(_) => None,
+        };
+        self.scope.since = next.map(str::to_string);
+        self.scope.until = None;
+        self.scope.before = None;
+        self.apply_filter();
+        let window = next.unwrap_or("all time").replace('-', " ");
+        self.set_status(format!("Showing sessions from {window}"));
+    }
+
+    /// Sort the list by `key`, reversed if `reverse`, and save the choice.
+    pub fn set_sort(&mut self, key: SortKey, reverse: bool) {
+        self.sort = key;
+        self.sort_reverse = reverse;
+        self.ensure_stats();
+        self.rebuild_display_entries();
+        self.selected = 0;
+        self.scroll_offset = 0;
+        let direction = if reverse { " (reversed)" } else { "" };
+        match self.persist_sort() {
+            Ok(()) => self.set_status(format!("Sorted by {}{direction}", key.label())),
+            Err(e) => self.set_status(e),
+        }
+    }
+
+    /// Open the sort menu with the current key highlighted.
+    pub fn open_sort_menu(&mut self) {
+        let cursor = SortKey::ALL.iter().position(|&k| k == self.sort).unwrap_or(0);
+        self.sort_menu = Some(SortMenu { cursor });
+    }
+
+    /// Start scanning the sessions for counts the sort key or columns need.
+    pub fn ensure_stats(&mut self) {
+        if !self.sort.needs_stats() && !self.columns.iter().any(|c| c.needs_stats()) {
+            return;
+        }
+        let missing: Vec<&Session> = self
+            .sessions
+            .iter()
+            .chain(&self.content_results)
+            .filter(|s| !self.stats.contains_key(&s.id) && !self.stats_loader.is_pending(&s.id))
+            .collect();
+        self.stats_loader.request(&missing);
+    }
+
+    /// Collect scanned stats, re-sorting the list if the sort key uses them.
+    pub fn poll_stats(&mut self) {
+        let scanned = self.stats_loader.poll();
+        if scanned.is_empty() {
+            return;
+        }
+        self.stats.extend(scanned);
+        if self.sort.needs_stats() {
+            let selection = self.selection_key();
+            self.rebuild_display_entries();
+            self.restore_selection(selection);
+        }
+    }
+
+    /// Start looking for new locations of the project of the selected row,
+    /// if it no longer exists. The menu opens when the search finishes.
+    pub fn open_relocate(&mut self) {
+        let project = match (self.selected_session(), self.tree_rows.get(self.selected)) {
+            (Some(session), _) => session.project_path.clone(),
+            (None, Some(TreeRow::Project { path, .. })) if self.layout == ListLayout::Tree => path.clone(),
+            _ => return,
+        };
+        if Path::new(&project).exists() {
+            self.set_status(format!("{project} still exists"));
+            return;
+        }
+        if !self.location_finder.is_pending(&project) {
+            self.location_finder.request(&project, remap::search_roots(&self.sessions));
+        }
+        self.set_status(format!("Looking for {project}..."));
+    }
+
+    /// Open the relocate menu once the search for a missing project's new
+    /// location finishes.
+    pub fn poll_relocate(&mut self) {
+        let Some((project, suggestions)) = self.location_finder.poll() else {
+            return;
+        };
+        // The menu belongs to the list; drop results that arrive elsewhere
+        if self.mode != Mode::Browsing {
+            return;
+        }
+        if suggestions.is_empty() {
+            self.set_status(format!("No likely new location for {project}; add a [[remap]] rule to config.toml"));
+            return;
+        }
+        self.status_message = None;
+        self.relocate = Some(RelocateMenu {
+            project,
+            suggestions,
+            cursor: 0,
+        });
+    }
+
+    /// Remap the sessions of project `from` to `to`, and save the rule.
+    pub fn relocate_project(&mut self, from: &str, to: &Path) {
+        let rule = PathRule {
+            from: from.to_string(),
+            to: to.to_string_lossy().into_owned(),
+            regex: false,
+        };
+        // Earlier rules have been applied already, so only the new one is needed
+        let remapper = match PathRemapper::new(std::slice::from_ref(&rule)) {
+            Ok(remapper) => remapper,
+            Err(e) => {
+                self.set_status(e);
+                return;
+            }
+        };
+        let selection = self.selection_key();
+        for session in self.sessions.iter_mut().chain(&mut self.content_results) {
+            remapper.remap_session(session);
+        }
+        for session in Arc::make_mut(&mut self.session_index).values_mut() {
+            remapper.remap_session(session);
+        }
+        self.remap_rules.push(rule);
+        if let Ok(all) = PathRemapper::new(&self.remap_rules) {
+            self.remapper = all;
+        }
+        self.apply_filter();
+        self.restore_selection(selection);
+        match self.persist_remap() {
+            Ok(()) => self.set_status(format!("Relocated {from} to {}", to.display())),
+            Err(e) => self.set_status(e),
+        }
+    }
+
+    fn persist_remap(&self) -> Result<(), String> {
+        let Some(path) = &self.config_path else {
+            return Ok(());
+        };
+        let config = config::Config {
+            remap: self.remap_rules.clone(),
+            ..config::Config::default()
+        };
+        config.save_keys(path, &["remap"])
+    }
+
+    fn persist_sort(&self) -> Result<(), String> {
+        let Some(path) = &self.config_path else {
+            return Ok(());
+        };
+        let config = config::Config {
+            sort: self.sort,
+            sort_reverse: self.sort_reverse,
+            ..config::Config::default()
+        };
+        config.save_keys(path, &["sort", "sort_reverse"])
+    }
+
+    fn persist_views(&self) -> Result<(), String> {
+        match &self.views_path {
+            Some(path) => views::save_views(path, &self.views),
+            None => Ok(()),
+        }
+    }
+
+    /// Replace the list filter, restarting the content search debounce.
+    pub fn set_filter_query(&mut self, query: String) {
+        self.filter_query = query;
+        self.filter_active |= !self.filter_query.is_empty();
+        self.cancel_flag.store(true, Ordering::Relaxed);
+        self.search_receiver = None;
+        self.content_results.clear();
+        if self.filter_query.is_empty() {
+            self.content_search_state = ContentSearchState::Idle;
+            self.last_keystroke = None;
+        } else {
+            self.content_search_state = ContentSearchState::Debouncing;
+            self.last_keystroke = Some(Instant::now());
+        }
+        self.apply_filter();
+    }
+
+    /// History of the query being edited: the conversation search while
+    /// typing one, otherwise the list filter.
+    pub fn active_history(&self) -> &History {
+        if self.mode == Mode::ConversationSearch {
+            &self.search_history
+        } else {
+            &self.filter_history
+        }
+    }
+
+    /// Text of the query being edited.
+    fn edited_query(&self) -> String {
+        match &self.conversation {
+            Some(conv) if self.mode == Mode::ConversationSearch => conv.search_query.clone(),
+            _ => self.filter_query.clone(),
+        }
+    }
+
+    /// Replace the text of the query being edited.
+    pub fn set_edited_query(&mut self, query: String) {
+        match &mut self.conversation {
+            Some(conv) if self.mode == Mode::ConversationSearch => {
+                conv.search_cursor = query.len();
+                conv.search_query = query;
+                conv.search_replacing = false;
+                conv.rendered_width = 0;
+            }
+            _ => self.set_filter_query(query),
+        }
+    }
+
+    /// Show the next older (or newer) history entry in the query being
+    /// edited. Going newer past the newest entry restores the typed query.
+    /// Returns false if there is nothing to recall.
+    pub fn recall_history(&mut self, older: bool) -> bool {
+        let len = self.active_history().entries().len();
+        if self.recall.is_none() {
+            if !older || len == 0 {
+                return false;
+            }
+            self.recall = Some(Recall {
+                index: len,
+                draft: self.edited_query(),
+            });
+        }
+        let Some(mut recall) = self.recall.take() else {
+            return false;
+        };
+        recall.index = if older {
+            recall.index.saturating_sub(1)
+        } else {
+            (recall.index + 1).min(len)
+        };
+        let query = match self.active_history().entries().get(recall.index) {
+            Some(entry) => entry.clone(),
+            None => std::mem::take(&mut recall.draft),
+        };
+        if recall.index < len {
+            self.recall = Some(recall);
+        }
+        self.set_edited_query(query);
+        true
+    }
+
+    /// Record the list filter in its history.
+    fn remember_filter(&mut self) {
+        if let Err(e) = self.filter_history.add(&self.filter_query) {
+            self.set_status(e);
+        }
+    }
+
+    /// Record a conversation search query in its history.
+    pub fn remember_search(&mut self, query: &str) {
+        if let Err(e) = self.search_history.add(query) {
+            self.set_status(e);
         }
     }
 
     /// Re-run the metadata filter and rebuild display entries.
     pub fn apply_filter(&mut self) {
-        self.filtered_indices = filter_sessions(&self.sessions, &self.filter_query);
+        let mut indices = filter_sessions(&self.sessions, &self.filter_query);
+        let scope = self.scope.resolve(Local::now());
+        indices.retain(|&idx| scope.contains(&self.sessions[idx]));
+        self.filtered_indices = indices;
         self.rebuild_display_entries();
         self.selected = 0;
         self.scroll_offset = 0;
@@ -186,6 +1216,7 @@ impl App {
             .collect();
 
         let mut entries = Vec::new();
+        let scope = self.scope.resolve(Local::now());
 
         for &idx in &self.filtered_indices {
             let session = &self.sessions[idx];
@@ -202,7 +1233,7 @@ impl App {
         }
 
         for (i, session) in self.content_results.iter().enumerate() {
-            if !metadata_ids.contains(session.id.as_str()) {
+            if !metadata_ids.contains(session.id.as_str()) && scope.contains(session) {
                 entries.push(DisplayEntry {
                     match_type: MatchType::Content,
                     source: DisplaySource::Content(i),
@@ -211,8 +1242,202 @@ impl App {
             }
         }
 
-        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-        self.display_entries = entries;
+        let query = self.filter_query.as_str();
+        let mut items: Vec<(SortItem, DisplayEntry)> = entries
+            .into_iter()
+            .map(|entry| {
+                let session = self.display_session(&entry);
+                // Content matches rank below metadata matches of any field
+                let content = u32::from(entry.match_type != MatchType::Metadata);
+                let item = SortItem {
+                    session,
+                    stats: self.stats.get(&session.id).cloned().unwrap_or_default(),
+                    relevance: relevance(session, query) * 2 + content,
+                };
+                (item, entry)
+            })
+            .collect();
+        items.sort_by(|(a, _), (b, _)| {
+            let ordering = sort::compare(self.sort, a, b);
+            if self.sort_reverse {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+        self.display_entries = items.into_iter().map(|(_, entry)| entry).collect();
+        self.rebuild_tree();
+    }
+
+    /// Rebuild the project tree rows from the current display entries.
+    ///
+    /// All projects are shown expanded while a filter is active, so matches
+    /// are never hidden inside collapsed projects.
+    pub fn rebuild_tree(&mut self) {
+        if self.layout != ListLayout::Tree {
+            self.tree_rows.clear();
+            return;
+        }
+        let sessions: Vec<&Session> = self
+            .display_entries
+            .iter()
+            .map(|e| self.display_session(e))
+            .collect();
+        let expand_all = !self.filter_query.trim().is_empty();
+        self.tree_rows = tree::build_tree(
+            &sessions,
+            &self.expanded_projects,
+            expand_all,
+            self.group_by_branch,
+        );
+    }
+
+    /// Number of selectable rows in the current layout.
+    pub fn row_count(&self) -> usize {
+        match self.layout {
+            ListLayout::Flat => self.display_entries.len(),
+            ListLayout::Tree => self.tree_rows.len(),
+        }
+    }
+
+    /// Display entry index under the cursor, if the cursor is on a session.
+    pub fn selected_entry(&self) -> Option<usize> {
+        match self.layout {
+            ListLayout::Flat => (self.selected < self.display_entries.len()).then_some(self.selected),
+            ListLayout::Tree => match self.tree_rows.get(self.selected) {
+                Some(TreeRow::Session { entry, .. }) => Some(*entry),
+                _ => None,
+            },
+        }
+    }
+
+    /// Session under the cursor, if the cursor is on a session.
+    pub fn selected_session(&self) -> Option<&Session> {
+        self.selected_entry()
+            .map(|idx| self.display_session(&self.display_entries[idx]))
+    }
+
+    /// Stable identity of the row under the cursor.
+    pub fn selection_key(&self) -> Option<RowKey> {
+        if let Some(session) = self.selected_session() {
+            return Some(RowKey::Session(session.id.clone()));
+        }
+        match self.tree_rows.get(self.selected) {
+            Some(TreeRow::Project { path, .. }) => Some(RowKey::Project(path.clone())),
+            Some(TreeRow::Branch { project, branch, .. }) => {
+                Some(RowKey::Branch(project.clone(), branch.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Move the cursor back to the row identified by `key` after a rebuild.
+    ///
+    /// Falls back to the session's project row in the tree if the session is
+    /// hidden in a collapsed project, and clamps the cursor if the row is gone.
+    pub fn restore_selection(&mut self, key: Option<RowKey>) {
+        let position = key.and_then(|key| match self.layout {
+            ListLayout::Flat => self.display_entries.iter().position(|e| {
+                let session = self.display_session(e);
+                match &key {
+                    RowKey::Session(id) => &session.id == id,
+                    RowKey::Project(path) | RowKey::Branch(path, _) => &session.project_path == path,
+                }
+            }),
+            ListLayout::Tree => {
+                let project_of_session = match &key {
+                    RowKey::Session(id) => self
+                        .display_entries
+                        .iter()
+                        .map(|e| self.display_session(e))
+                        .find(|s| &s.id == id)
+                        .map(|s| s.project_path.clone()),
+                    _ => None,
+                };
+                let exact = self.tree_rows.iter().position(|row| match (row, &key) {
+                    (TreeRow::Session { entry, .. }, RowKey::Session(id)) => {
+                        &self.display_session(&self.display_entries[*entry]).id == id
+                    }
+                    (TreeRow::Project { path, .. }, RowKey::Project(p)) => path == p,
+                    (TreeRow::Branch { project, branch, .. }, RowKey::Branch(p, b)) => {
+                        project == p && branch == b
+                    }
+                    _ => false,
+                });
+                exact.or_else(|| {
+                    let project = project_of_session?;
+                    self.tree_rows.iter().position(
+                        |row| matches!(row, TreeRow::Project { path, .. } if *path == project),
+                    )
+                })
+            }
+        });
+        self.selected = position
+            .unwrap_or(self.selected)
+            .min(self.row_count().saturating_sub(1));
+    }
+
+    /// Switch between the flat list and the project tree, keeping the selection.
+    pub fn toggle_layout(&mut self) {
+        let selection = self.selection_key();
+        self.layout = match self.layout {
+            ListLayout::Flat => ListLayout::Tree,
+            ListLayout::Tree => ListLayout::Flat,
+        };
+        if let Some(project) = self.project_of_selection(&selection) {
+            // Reveal the selected session when entering the tree
+            self.expanded_projects.insert(project);
+        }
+        self.rebuild_tree();
+        self.restore_selection(selection);
+    }
+
+    /// Project path of the session identified by `key`, if it is a session key.
+    fn project_of_selection(&self, key: &Option<RowKey>) -> Option<String> {
+        let Some(RowKey::Session(id)) = key else {
+            return None;
+        };
+        self.display_entries
+            .iter()
+            .map(|e| self.display_session(e))
+            .find(|s| &s.id == id)
+            .map(|s| s.project_path.clone())
+    }
+
+    /// Toggle nesting sessions under per-branch rows in the tree.
+    pub fn toggle_branch_grouping(&mut self) {
+        let selection = self.selection_key();
+        self.group_by_branch = !self.group_by_branch;
+        self.rebuild_tree();
+        self.restore_selection(selection);
+    }
+
+    /// Expand or collapse the project of the selected tree row.
+    ///
+    /// `expand` of None toggles. Collapsing from a session or branch row moves
+    /// the cursor to its project row.
+    pub fn set_selected_project_expanded(&mut self, expand: Option<bool>) {
+        if self.layout != ListLayout::Tree {
+            return;
+        }
+        let project = match self.tree_rows.get(self.selected) {
+            Some(TreeRow::Project { path, .. }) => path.clone(),
+            Some(TreeRow::Branch { project, .. }) => project.clone(),
+            Some(TreeRow::Session { entry, .. }) => self
+                .display_session(&self.display_entries[*entry])
+                .project_path
+                .clone(),
+            None => return,
+        };
+        let currently = self.expanded_projects.contains(&project);
+        let expand = expand.unwrap_or(!currently);
+        if expand {
+            self.expanded_projects.insert(project.clone());
+        } else {
+            self.expanded_projects.remove(&project);
+        }
+        self.rebuild_tree();
+        self.restore_selection(Some(RowKey::Project(project)));
     }
 
     /// Get the session referenced by a display entry.
@@ -234,8 +1459,9 @@ impl App {
 
     /// Move the selection cursor down, clamped to bounds.
     pub fn move_down(&mut self) {
-        if !self.display_entries.is_empty() {
-            self.selected = (self.selected + 1).min(self.display_entries.len() - 1);
+        let rows = self.row_count();
+        if rows > 0 {
+            self.selected = (self.selected + 1).min(rows - 1);
         }
     }
 
@@ -249,7 +1475,7 @@ impl App {
         if visible_items == 0 {
             return;
         }
-        if self.mode == Mode::Conversation || self.mode == Mode::ConversationSearch {
+        if self.mode != Mode::Browsing {
             return;
         }
         if self.selected < self.scroll_offset {
@@ -264,37 +1490,73 @@ impl App {
         if display_idx >= self.display_entries.len() {
             return;
         }
+        self.remember_filter();
         let entry = &self.display_entries[display_idx];
         let session = self.display_session(entry).clone();
-        let claude_home = get_claude_home();
-        let messages = load_conversation(&claude_home, &session);
+        let mut conv = ConversationState::load(session, &self.filter_query);
+        conv.folds.collapse_after = self.collapse_after;
+        self.conversation = Some(conv);
+        self.mode = Mode::Conversation;
+    }
 
-        let initial_search_terms: Vec<String> = {
-            let trimmed = self.filter_query.trim();
-            if !trimmed.is_empty() {
-                vec![trimmed.to_string()]
-            } else {
-                Vec::new()
-            }
+    /// Mark or unmark the selected session for comparison. At most two
+    /// sessions are marked; marking a third drops the oldest mark.
+    pub fn toggle_mark(&mut self) {
+        let Some(id) = self.selected_session().map(|s| s.id.clone()) else {
+            return;
         };
+        if let Some(pos) = self.marked.iter().position(|m| *m == id) {
+            self.marked.remove(pos);
+            return;
+        }
+        if self.marked.len() == 2 {
+            self.marked.remove(0);
+        }
+        self.marked.push(id);
+    }
 
-        self.conversation = Some(ConversationState {
-            session,
-            messages,
-            lines: Vec::new(),
-            scroll_offset: 0,
-            page_height: 20,
-            rendered_width: 0,
-            search_query: String::new(),
-            search_active: false,
-            search_confirmed: false,
-            search_replacing: false,
-            search_cursor: 0,
-            match_positions: Vec::new(),
-            current_match: 0,
-            initial_search_terms,
+    /// Whether the session is marked for comparison.
+    pub fn is_marked(&self, session_id: &str) -> bool {
+        self.marked.iter().any(|m| m == session_id)
+    }
+
+    /// Open the side-by-side comparison of the two marked sessions.
+    pub fn open_compare(&mut self) {
+        let sessions: Vec<Session> = self
+            .marked
+            .iter()
+            .filter_map(|id| {
+                self.sessions
+                    .iter()
+                    .chain(&self.content_results)
+                    .find(|s| &s.id == id)
+                    .cloned()
+            })
+            .collect();
+        let Ok([left, right]) = <[Session; 2]>::try_from(sessions) else {
+            self.set_status("Mark two sessions with Ctrl-T to compare them".to_string());
+            return;
+        };
+        self.remember_filter();
+        let claude_home = get_claude_home();
+        let activity = [&left, &right].map(|s| scan_session_activity(&session_file_path(&claude_home, s)));
+        self.compare = Some(CompareState {
+            panes: [left, right].map(|s| {
+                let mut pane = ConversationState::load(s, &self.filter_query);
+                pane.folds.collapse_after = self.collapse_after;
+                pane
+            }),
+            activity,
+            focus: 0,
+            locked: false,
         });
-        self.mode = Mode::Conversation;
+        self.mode = Mode::Compare;
+    }
+
+    /// Leave the comparison and return to the list.
+    pub fn leave_compare(&mut self) {
+        self.compare = None;
+        self.mode = Mode::Browsing;
     }
 
     /// Leave conversation viewer and return to the list.
@@ -303,8 +1565,296 @@ impl App {
         self.mode = Mode::Browsing;
     }
 
+    /// Show or hide a side panel in the conversation viewer, loading its
+    /// content on first use.
+    pub fn toggle_side_panel(&mut self, panel: SidePanel) {
+        let Some(conv) = &mut self.conversation else {
+            return;
+        };
+        if conv.side_panel == Some(panel) {
+            conv.side_panel = None;
+            return;
+        }
+        conv.side_panel = Some(panel);
+        match panel {
+            SidePanel::Commits => {
+                if conv.commits.is_none() {
+                    self.commits_loader.request(&conv.session);
+                }
+            }
+            SidePanel::Files => conv.ensure_activity(),
+        }
+    }
+
+    /// Open the table of contents focused, focus it if open, or close it
+    /// if focused.
+    pub fn toggle_toc(&mut self) {
+        let Some(conv) = &mut self.conversation else {
+            return;
+        };
+        match &mut conv.toc {
+            Some(toc) if toc.focused => conv.toc = None,
+            Some(toc) => toc.focused = true,
+            None => {
+                conv.ensure_activity();
+                let cursor = conv.current_prompt().unwrap_or(0);
+                conv.toc = Some(Toc {
+                    cursor,
+                    focused: true,
+                    offset: 0,
+                });
+            }
+        }
+    }
+
+    /// Open or close the combined diff of every edit to the file edited
+    /// nearest the current scroll position.
+    pub fn toggle_combined_diff(&mut self) {
+        let Some(conv) = &mut self.conversation else {
+            return;
+        };
+        if conv.combined_diff.take().is_some() {
+            return;
+        }
+
+        // Message at the top of the view, then search backwards, then forwards
+        let current = conv
+            .message_starts
+            .iter()
+            .rposition(|&start| start <= conv.scroll_offset)
+            .unwrap_or(0);
+        let path = conv.messages[..(current + 1).min(conv.messages.len())]
+            .iter()
+            .rev()
+            .find_map(|m| diff::diff_blocks(&m.text).pop().map(|(path, _)| path))
+            .or_else(|| {
+                conv.messages.iter().skip(current + 1).find_map(|m| {
+                    diff::diff_blocks(&m.text).into_iter().next().map(|(path, _)| path)
+                })
+            });
+        let Some(path) = path else {
+            self.set_status("No edits in this session".to_string());
+            return;
+        };
+
+        let edits = conv
+            .messages
+            .iter()
+            .flat_map(|m| {
+                diff::diff_blocks(&m.text)
+                    .into_iter()
+                    .filter(|(p, _)| *p == path)
+                    .map(|(_, lines)| (m.timestamp, lines))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        conv.combined_diff = Some(CombinedDiff {
+            path,
+            edits,
+            lines: Vec::new(),
+            rendered_width: 0,
+            scroll_offset: 0,
+            page_height: conv.page_height,
+        });
+    }
+
+    /// Switch the open side panel to its next tab.
+    pub fn next_side_panel_tab(&mut self) {
+        let Some(current) = self.conversation.as_ref().and_then(|c| c.side_panel) else {
+            return;
+        };
+        self.toggle_side_panel(current.next());
+    }
+
+    /// Toggle follow mode in the conversation viewer.
+    ///
+    /// Enabling it jumps to the bottom so new messages scroll into view.
+    pub fn toggle_follow(&mut self) {
+        let Some(conv) = &mut self.conversation else {
+            return;
+        };
+        if conv.tail.is_none() {
+            self.set_status("Follow mode is not available for compressed sessions".to_string());
+            return;
+        }
+        conv.follow = !conv.follow;
+        if conv.follow {
+            conv.scroll_offset = conv.lines.len().saturating_sub(conv.page_height);
+        }
+    }
+
+    /// In follow mode, merge lines appended to the session file and mark the
+    /// changed messages for re-rendering.
+    pub fn poll_follow(&mut self) {
+        let Some(conv) = &mut self.conversation else {
+            return;
+        };
+        if !conv.follow {
+            return;
+        }
+        let Some(tail) = &mut conv.tail else {
+            return;
+        };
+        if let Some(first) = tail.read_appended(&mut conv.messages) {
+            conv.dirty_from = Some(conv.dirty_from.map_or(first, |d| d.min(first)));
+        } else if conv.messages.is_empty() && !conv.message_starts.is_empty() {
+            // File was rewritten without displayable content
+            conv.rendered_width = 0;
+        }
+    }
+
+    /// Build the action for resuming the open conversation.
+    ///
+    /// Compressed sessions cannot be resumed by Claude Code directly, so
+    /// instead of copying the command this asks to decompress first.
+    pub fn resume_action(&mut self) -> Action {
+        let Some(conv) = &self.conversation else {
+            return Action::Continue;
+        };
+        let command = conv.session.resume_command();
+        if let Some(live) = self.live.get(&conv.session.id) {
+            self.confirm = Some(Confirm::LiveSession {
+                command,
+                live: live.clone(),
+            });
+            return Action::Continue;
+        }
+        self.resume_or_decompress(command)
+    }
+
+    /// Copy the resume command, asking to decompress compressed sessions first.
+    fn resume_or_decompress(&mut self, command: String) -> Action {
+        let Some(conv) = &self.conversation else {
+            return Action::Continue;
+        };
+        let path = session_file_path(&get_claude_home(), &conv.session);
+        if compress::compression_of(&path).is_some() {
+            self.confirm = Some(Confirm::Decompress { path, command });
+            return Action::Continue;
+        }
+        Action::CopyCommand(command)
+    }
+
+    /// Carry out a confirmed action.
+    pub fn accept_confirm(&mut self, confirm: Confirm) -> Action {
+        match confirm {
+            Confirm::Decompress { path, command } => match compress::decompress_file(&path) {
+                Ok(_) => Action::CopyCommand(command),
+                Err(e) => {
+                    self.set_status(format!("Decompression failed: {e}"));
+                    Action::Continue
+                }
+            },
+            Confirm::LiveSession { command, .. } => self.resume_or_decompress(command),
+        }
+    }
+
+    /// Switch to the tmux pane of a running session from a pending confirmation.
+    pub fn switch_to_live_pane(&mut self) -> Action {
+        let pane = match &self.confirm {
+            Some(Confirm::LiveSession { live, .. }) => live.tmux_pane.clone(),
+            _ => None,
+        };
+        let Some(pane) = pane else {
+            return Action::Continue;
+        };
+        self.confirm = None;
+        match live::switch_to_tmux_pane(&pane) {
+            Ok(()) => Action::Quit,
+            Err(e) => {
+                self.set_status(format!("Could not switch pane: {e}"));
+                Action::Continue
+            }
+        }
+    }
+
+    /// Whether a session is currently running in another terminal.
+    pub fn is_live(&self, id: &str) -> bool {
+        self.live.contains_key(id)
+    }
+
+    /// Collect finished scans for running sessions and start a new one at
+    /// most once per scan interval.
+    pub fn refresh_live(&mut self) {
+        if let Some(live) = self.live_scanner.poll() {
+            self.live = live;
+        }
+        if self
+            .last_live_scan
+            .is_some_and(|t| t.elapsed() < LIVE_SCAN_INTERVAL)
+        {
+            return;
+        }
+        self.live_scanner.request(&self.sessions);
+        self.last_live_scan = Some(Instant::now());
+    }
+
+    /// Show commits looked up in the background once they arrive.
+    pub fn poll_commits(&mut self) {
+        for (id, commits) in self.commits_loader.poll() {
+            if let Some(conv) = self.conversation.as_mut().filter(|c| c.session.id == id) {
+                conv.commits = Some(commits);
+            }
+        }
+    }
+
+    /// Show or hide the preview pane.
+    pub fn toggle_preview(&mut self) {
+        self.show_preview = !self.show_preview;
+    }
+
+    /// Collect loaded previews and request the selected session's preview.
+    pub fn poll_preview(&mut self) {
+        self.previews.poll();
+        if !self.show_preview || self.mode != Mode::Browsing {
+            return;
+        }
+        if let Some(session) = self.selected_session().cloned() {
+            self.previews.request(&session);
+        }
+    }
+
+    /// Open the URL or file path at `column` of rendered conversation line
+    /// `line`. Relative paths are resolved against the session directory.
+    pub fn open_link_at(&mut self, line: usize, column: usize) {
+        let Some(conv) = &self.conversation else {
+            return;
+        };
+        let Some(rendered) = conv.lines.get(line) else {
+            return;
+        };
+        let text: String = rendered.spans.iter().map(|s| s.content.as_ref()).collect();
+        let target = match open::link_at(&text, column) {
+            Some(open::Link::Url(url)) => url,
+            Some(open::Link::Path(path)) => match open::resolve_path(&path, &conv.session.cwd) {
+                Some(resolved) => resolved.to_string_lossy().into_owned(),
+                None => return,
+            },
+            None => return,
+        };
+        match open::open_in_system(&target) {
+            Ok(()) => self.set_status(format!("Opened {target}")),
+            Err(e) => self.set_status(e),
+        }
+    }
+
+    /// Copy `text` to the clipboard and report it as `what` in the status bar.
+    pub fn yank_text(&mut self, what: &str, text: Option<String>) {
+        let Some(text) = text else {
+            self.set_status(format!("No {what} to copy"));
+            return;
+        };
+        match clipboard::copy_to_clipboard(&text) {
+            Ok(()) => {
+                let lines = text.lines().count();
+                let noun = if lines == 1 { "line" } else { "lines" };
+                self.set_status(format!("Copied {what} ({lines} {noun})"));
+            }
+            Err(e) => self.set_status(e),
+        }
+    }
+
     /// Set a status message that disappears after a few seconds.
-    #[allow(dead_code)]
     pub fn set_status(&mut self, msg: String) {
         self.status_message = Some((msg, Instant::now()));
     }
@@ -324,24 +1874,24 @@ impl App {
             match rx.try_recv() {
                 Ok(results) => {
                     self.search_receiver = None;
-                    let selected_id = self
-                        .display_entries
-                        .get(self.selected)
-                        .map(|e| self.display_session(e).id.clone());
+                    let selection = self.selection_key();
 
-                    self.content_results = results;
+                    // Use the already remapped sessions where known
+                    self.content_results = results
+                        .into_iter()
+                        .map(|mut result| match self.sessions.iter().find(|s| s.id == result.id) {
+                            Some(known) => known.clone(),
+                            None => {
+                                self.remapper.remap_session(&mut result);
+                                result
+                            }
+                        })
+                        .collect();
                     self.content_search_state = ContentSearchState::Complete;
+                    let recheck = std::mem::take(&mut self.content_recheck);
+                    self.recheck_content_results(&recheck);
                     self.rebuild_display_entries();
-
-                    if let Some(id) = selected_id {
-                        if let Some(pos) = self
-                            .display_entries
-                            .iter()
-                            .position(|e| self.display_session(e).id == id)
-                        {
-                            self.selected = pos;
-                        }
-                    }
+                    self.restore_selection(selection);
                     true
                 }
                 Err(mpsc::TryRecvError::Empty) => {
@@ -387,18 +1937,177 @@ impl App {
         }
     }
 
-    /// Clear expired status messages.
+    /// Clear expired status messages and update markers.
     pub fn tick_status(&mut self) {
         if let Some((_, when)) = &self.status_message {
             if when.elapsed() > Duration::from_secs(3) {
                 self.status_message = None;
             }
         }
+        self.updated_at
+            .retain(|_, when| when.elapsed() < UPDATE_MARK_DURATION);
+    }
+
+    /// Whether a session was recently added or updated by the watcher.
+    pub fn is_recently_updated(&self, id: &str) -> bool {
+        self.updated_at.contains_key(id)
+    }
+
+    /// Collect watcher events and merge changed sessions into the list.
+    ///
+    /// Changes are throttled so that a session being actively written to
+    /// is re-parsed at most once per merge interval.
+    pub fn poll_watcher(&mut self) {
+        if let Some(watcher) = &self.watcher {
+            self.pending_changes.extend(watcher.changed_files());
+        }
+        if self.pending_changes.is_empty() || self.last_merge.elapsed() < MERGE_INTERVAL {
+            return;
+        }
+        let changed: Vec<PathBuf> = std::mem::take(&mut self.pending_changes).into_iter().collect();
+        self.merge_changed_files(&changed);
+        self.last_merge = Instant::now();
+    }
+
+    /// Re-parse the given session files and merge the results into `sessions`.
+    ///
+    /// Files that no longer exist in any form (plain or compressed) remove
+    /// their session. The selection, filter, and content search are preserved.
+    pub fn merge_changed_files(&mut self, paths: &[PathBuf]) {
+        let mut updated: Vec<(PathBuf, Session)> = Vec::new();
+        let mut removed: Vec<String> = Vec::new();
+        for path in paths {
+            if path.exists() {
+                if let Some(mut session) = parse_session_file(path) {
+                    self.remapper.remap_session(&mut session);
+                    updated.push((path.clone(), session));
+                }
+            } else if let Some(id) = compress::session_id_from_path(path) {
+                let dir = path.parent().unwrap_or(path);
+                let still_exists = compress::SESSION_SUFFIXES
+                    .iter()
+                    .any(|suffix| dir.join(format!("{id}{suffix}")).exists());
+                if !still_exists {
+                    removed.push(id);
+                }
+            }
+        }
+        if updated.is_empty() && removed.is_empty() {
+            return;
+        }
+
+        let selection = self.selection_key();
+
+        // Merge into the session list and the deep search index
+        let index = Arc::make_mut(&mut self.session_index);
+        for (path, session) in &updated {
+            index.retain(|_, s| s.id != session.id);
+            index.insert(path.clone(), session.clone());
+            match self.sessions.iter_mut().find(|s| s.id == session.id) {
+                Some(existing) => *existing = session.clone(),
+                None => self.sessions.push(session.clone()),
+            }
+            self.updated_at.insert(session.id.clone(), Instant::now());
+        }
+        for id in &removed {
+            index.retain(|_, s| &s.id != id);
+            self.sessions.retain(|s| &s.id != id);
+            self.content_results.retain(|s| &s.id != id);
+        }
+        self.sessions.sort_by_key(|s| std::cmp::Reverse(s.timestamp));
+        if let Some(last) = self.last {
+            // Like at startup, --last keeps the newest sessions within the scope
+            let scope = self.scope.resolve(Local::now());
+            self.sessions.retain(|s| scope.contains(s));
+            self.sessions = apply_filters(std::mem::take(&mut self.sessions), Some(last));
+            let kept: HashSet<&str> = self.sessions.iter().map(|s| s.id.as_str()).collect();
+            index.retain(|_, s| kept.contains(s.id.as_str()));
+            self.content_results.retain(|s| kept.contains(s.id.as_str()));
+        }
+        for (_, session) in &updated {
+            self.stats.remove(&session.id);
+            self.stats_loader.forget(&session.id);
+        }
+        self.ensure_stats();
+
+        // Keep content search results in sync with the changed files
+        let changed_paths: Vec<PathBuf> = updated.into_iter().map(|(path, _)| path).collect();
+        match self.content_search_state {
+            ContentSearchState::Complete => self.recheck_content_results(&changed_paths),
+            ContentSearchState::Searching => self.content_recheck.extend(changed_paths),
+            ContentSearchState::Idle | ContentSearchState::Debouncing => {}
+        }
+
+        let mut indices = filter_sessions(&self.sessions, &self.filter_query);
+        let scope = self.scope.resolve(Local::now());
+        indices.retain(|&idx| scope.contains(&self.sessions[idx]));
+        self.filtered_indices = indices;
+        self.rebuild_display_entries();
+        self.restore_selection(selection);
+    }
+
+    /// Re-run the content search query against individual changed files
+    /// and update `content_results` accordingly.
+    fn recheck_content_results(&mut self, paths: &[PathBuf]) {
+        let pattern = self.filter_query.trim();
+        if pattern.is_empty() {
+            return;
+        }
+        for path in paths {
+            let Some(session) = self.session_index.get(path).cloned() else {
+                continue;
+            };
+            self.content_results.retain(|s| s.id != session.id);
+            if search::session_file_matches(path, &self.filter_query) {
+                self.content_results.push(session);
+            }
+        }
     }
 }
 
+/// Minimum time between merges of watcher changes into the list.
+const MERGE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long a row stays marked after the watcher added or updated it.
+const UPDATE_MARK_DURATION: Duration = Duration::from_secs(5);
+
+/// Minimum time between scans for running sessions.
+const LIVE_SCAN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Startup options of the TUI.
+#[derive(Debug, Clone, Default)]
+pub struct TuiOptions {
+    /// Open with the preview pane shown.
+    pub preview: bool,
+    /// Capture the mouse for clicking and scrolling. Terminal text
+    /// selection does not work while it is captured.
+    pub mouse: bool,
+    /// Collapse conversation messages longer than this many lines.
+    pub collapse_after: Option<usize>,
+    /// Queries kept in each search history.
+    pub history_size: usize,
+    /// Initial filter and scope of the list.
+    pub view: View,
+    /// Saved views by name.
+    pub views: BTreeMap<String, View>,
+    /// Initial order of the list.
+    pub sort: SortKey,
+    pub sort_reverse: bool,
+    /// Columns and lines per row of the list.
+    pub columns: Vec<Column>,
+    pub density: Density,
+    /// Rewrites of recorded project paths, already applied to the sessions.
+    pub remap: Vec<PathRule>,
+    /// Number of newest sessions the list is limited to, if any.
+    pub last: Option<usize>,
+}
+
 /// Run the interactive TUI session picker.
-pub fn run(sessions: Vec<Session>, theme: Theme) -> Result<(), Box<dyn std::error::Error>> {
+pub fn run(
+    sessions: Vec<Session>,
+    theme: Theme,
+    options: TuiOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
     if sessions.is_empty() {
         eprintln!("No sessions found.");
         return Ok(());
@@ -407,19 +2116,43 @@ pub fn run(sessions: Vec<Session>, theme: Theme) -> Result<(), Box<dyn std::erro
     let original_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic_info| {
         let _ = disable_raw_mode();
-        let _ = execute!(stdout(), LeaveAlternateScreen);
+        let _ = execute!(stdout(), DisableMouseCapture, LeaveAlternateScreen);
         original_hook(panic_info);
     }));
 
     enable_raw_mode()?;
     let mut stdout = stdout();
     execute!(stdout, EnterAlternateScreen)?;
+    if options.mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     let claude_home = get_claude_home();
     let session_index = search::build_session_index(&claude_home, &sessions);
     let mut app = App::new(sessions, session_index, theme);
+    app.watcher = SessionWatcher::new(&claude_home).ok();
+    app.show_preview = options.preview;
+    app.collapse_after = options.collapse_after;
+    let state_dir = config::state_dir();
+    app.filter_history = History::load(&state_dir.join("filter_history"), options.history_size);
+    app.search_history = History::load(&state_dir.join("search_history"), options.history_size);
+    app.views = options.views;
+    app.views_path = Some(views::views_path());
+    app.config_path = Some(config::config_path());
+    app.sort = options.sort;
+    app.sort_reverse = options.sort_reverse;
+    app.columns = options.columns;
+    // The rules were checked when the sessions were remapped
+    app.remapper = PathRemapper::new(&options.remap).unwrap_or_default();
+    app.remap_rules = options.remap;
+    app.density = options.density;
+    app.last = options.last;
+    app.ensure_stats();
+    app.scope = options.view.scope;
+    app.filter_active = !options.view.query.is_empty();
+    app.set_filter_query(options.view.query);
     let mut deferred_command: Option<String> = None;
 
     loop {
@@ -433,36 +2166,50 @@ pub fn run(sessions: Vec<Session>, theme: Theme) -> Result<(), Box<dyn std::erro
             app.check_debounce();
         }
 
+        app.poll_watcher();
+        app.poll_follow();
+        app.refresh_live();
+        app.poll_preview();
+        app.poll_stats();
+        app.poll_commits();
+        app.poll_relocate();
+
         terminal.draw(|frame| {
             let height = frame.area().height.saturating_sub(2) as usize;
-            app.ensure_visible(height);
+            app.ensure_visible(height / app.density.row_height());
             view::render(frame, &mut app);
         })?;
 
         if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                match handle_input(&mut app, key) {
-                    Action::Quit => break,
-                    Action::EnterConversation(idx) => {
-                        app.enter_conversation(idx);
-                    }
-                    Action::CopyCommand(cmd) => match clipboard::copy_to_clipboard(&cmd) {
-                        Ok(()) => break,
-                        Err(_) => {
-                            deferred_command = Some(cmd);
-                            break;
-                        }
-                    },
-                    Action::BackToList => {
-                        app.leave_conversation();
+            let action = match event::read()? {
+                Event::Key(key) => handle_input(&mut app, key),
+                Event::Mouse(mouse) => handle_mouse(&mut app, mouse),
+                _ => Action::Continue,
+            };
+            match action {
+                Action::Quit => break,
+                Action::EnterConversation(idx) => {
+                    app.enter_conversation(idx);
+                }
+                Action::CopyCommand(cmd) => match clipboard::copy_to_clipboard(&cmd) {
+                    Ok(()) => break,
+                    Err(_) => {
+                        deferred_command = Some(cmd);
+                        break;
                     }
-                    Action::Continue => {}
+                },
+                Action::BackToList => {
+                    app.leave_conversation();
                 }
+                Action::Continue => {}
             }
         }
     }
 
     disable_raw_mode()?;
+    if options.mouse {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
     terminal.show_cursor()?;
 
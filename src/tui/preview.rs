@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use chrono::{DateTime, Utc};
+
+use crate::discovery::load_conversation;
+use crate::session::{ConversationMessage, MessageRole, Session};
+
+/// Number of user prompts shown in a preview.
+pub const PREVIEW_PROMPTS: usize = 3;
+
+/// Cached previews kept before the cache is cleared.
+const CACHE_LIMIT: usize = 256;
+
+/// Summary of a session shown next to the session list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preview {
+    pub user_messages: usize,
+    pub assistant_messages: usize,
+    /// First user prompts, oldest first.
+    pub prompts: Vec<String>,
+    pub last_reply: Option<String>,
+}
+
+/// Build a preview from a loaded conversation.
+pub fn build_preview(messages: &[ConversationMessage]) -> Preview {
+    let is_user = |m: &&ConversationMessage| m.role == MessageRole::User;
+    Preview {
+        user_messages: messages.iter().filter(is_user).count(),
+        assistant_messages: messages.iter().filter(|m| !is_user(m)).count(),
+        prompts: messages
+            .iter()
+            .filter(is_user)
+            .take(PREVIEW_PROMPTS)
+            .map(|m| m.text.trim().to_string())
+            .collect(),
+        last_reply: messages
+            .iter()
+            .rev()
+            .find(|m| m.role == MessageRole::Assistant)
+            .map(|m| m.text.trim().to_string()),
+    }
+}
+
+/// Cache key of a session: its file and last activity, which follows the
+/// file's modification time, so sessions updated by the watcher are
+/// reloaded.
+type PreviewKey = (PathBuf, DateTime<Utc>);
+
+fn preview_key(session: &Session) -> PreviewKey {
+    (session.file_path.clone(), session.last_activity)
+}
+
+/// Loads previews on a background thread and caches them.
+///
+/// Only the latest request matters: the worker skips requests that were
+/// superseded while it was busy, so moving the cursor quickly never queues
+/// up work.
+pub struct PreviewLoader {
+    requests: mpsc::Sender<(PreviewKey, Session)>,
+    results: mpsc::Receiver<(PreviewKey, Preview)>,
+    cache: HashMap<PreviewKey, Preview>,
+    pending: Option<PreviewKey>,
+}
+
+impl PreviewLoader {
+    pub fn new(claude_home: PathBuf) -> Self {
+        let (request_tx, request_rx) = mpsc::channel::<(PreviewKey, Session)>();
+        let (result_tx, result_rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            while let Ok(mut request) = request_rx.recv() {
+                while let Ok(newer) = request_rx.try_recv() {
+                    request = newer;
+                }
+                let (key, session) = request;
+                let preview = load_preview(&claude_home, &session);
+                if result_tx.send((key, preview)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            requests: request_tx,
+            results: result_rx,
+            cache: HashMap::new(),
+            pending: None,
+        }
+    }
+
+    /// Collect finished previews. Returns true if any arrived.
+    pub fn poll(&mut self) -> bool {
+        let mut received = false;
+        while let Ok((key, preview)) = self.results.try_recv() {
+            if self.pending.as_ref() == Some(&key) {
+                self.pending = None;
+            }
+            if self.cache.len() >= CACHE_LIMIT {
+                self.cache.clear();
+            }
+            self.cache.insert(key, preview);
+            received = true;
+        }
+        received
+    }
+
+    /// Request a preview of `session` unless it is cached or loading.
+    pub fn request(&mut self, session: &Session) {
+        let key = preview_key(session);
+        if self.cache.contains_key(&key) || self.pending.as_ref() == Some(&key) {
+            return;
+        }
+        if self.requests.send((key.clone(), session.clone())).is_ok() {
+            self.pending = Some(key);
+        }
+    }
+
+    /// Cached preview of `session`, if loaded.
+    pub fn get(&self, session: &Session) -> Option<&Preview> {
+        self.cache.get(&preview_key(session))
+    }
+}
+
+fn load_preview(claude_home: &Path, session: &Session) -> Preview {
+    build_preview(&load_conversation(claude_home, session))
+}
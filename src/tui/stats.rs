@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::mpsc;
+
+use rayon::prelude::*;
+
+use crate::activity::scan_session_activity;
+use crate::session::Session;
+use crate::sort::SessionStats;
+
+/// Sessions scanned before their stats are sent back, so large histories
+/// fill in gradually.
+const BATCH_SIZE: usize = 64;
+
+/// A session file to scan, tagged with the request it belongs to.
+type StatsRequest = (String, u64, PathBuf);
+
+/// Scans session files for their stats on a background thread.
+///
+/// Each request carries a generation; results of requests that were
+/// forgotten or repeated since are dropped, so stats of a changed file are
+/// never overwritten by an older scan.
+pub struct StatsLoader {
+    requests: mpsc::Sender<Vec<StatsRequest>>,
+    results: mpsc::Receiver<Vec<(String, u64, SessionStats)>>,
+    pending: HashMap<String, u64>,
+    generation: u64,
+}
+
+impl Default for StatsLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatsLoader {
+    pub fn new() -> Self {
+        let (request_tx, request_rx) = mpsc::channel::<Vec<StatsRequest>>();
+        let (result_tx, result_rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            while let Ok(batch) = request_rx.recv() {
+                for chunk in batch.chunks(BATCH_SIZE) {
+                    let stats: Vec<(String, u64, SessionStats)> = chunk
+                        .par_iter()
+                        .map(|(id, generation, path)| {
+                            (id.clone(), *generation, SessionStats::from(&scan_session_activity(path)))
+                        })
+                        .collect();
+                    if result_tx.send(stats).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        Self {
+            requests: request_tx,
+            results: result_rx,
+            pending: HashMap::new(),
+            generation: 0,
+        }
+    }
+
+    /// Whether a scan of `id` is in flight.
+    pub fn is_pending(&self, id: &str) -> bool {
+        self.pending.contains_key(id)
+    }
+
+    /// Whether any scan is in flight.
+    pub fn is_loading(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Scan `sessions`, replacing earlier requests for them.
+    pub fn request(&mut self, sessions: &[&Session]) {
+        if sessions.is_empty() {
+            return;
+        }
+        self.generation += 1;
+        let batch: Vec<StatsRequest> = sessions
+            .iter()
+            .map(|s| (s.id.clone(), self.generation, s.file_path.clone()))
+            .collect();
+        if self.requests.send(batch).is_ok() {
+            for s in sessions {
+                self.pending.insert(s.id.clone(), self.generation);
+            }
+        }
+    }
+
+    /// Drop the result of any scan of `id` in flight.
+    pub fn forget(&mut self, id: &str) {
+        self.pending.remove(id);
+    }
+
+    /// Stats scanned since the last poll.
+    pub fn poll(&mut self) -> Vec<(String, SessionStats)> {
+        let mut received = Vec::new();
+        while let Ok(batch) = self.results.try_recv() {
+            for (id, generation, stats) in batch {
+                if self.pending.get(&id) == Some(&generation) {
+                    self.pending.remove(&id);
+                    received.push((id, stats));
+                }
+            }
+        }
+        received
+    }
+}
@@ -74,17 +74,27 @@ impl SyntaxHighlighter {
         theme_name: &str,
         bg_color: RatColor,
         line_width: usize,
+    ) -> Option<Vec<Line<'static>>> {
+        let lines: Vec<(&str, RatColor)> = code_lines.iter().map(|l| (*l, bg_color)).collect();
+        self.highlight_lines(&lines, language, theme_name, line_width)
+    }
+
+    /// Highlight code lines that each carry their own background color
+    /// (e.g. added and removed lines of a diff).
+    /// Returns styled Lines padded to `line_width`, or None if the language is not recognized.
+    pub fn highlight_lines(
+        &self,
+        code_lines: &[(&str, RatColor)],
+        language: &str,
+        theme_name: &str,
+        line_width: usize,
     ) -> Option<Vec<Line<'static>>> {
         let syntax = self.find_syntax(language)?;
         let theme = self.theme_set.themes.get(theme_name)?;
         let mut highlighter = HighlightLines::new(syntax, theme);
 
-        let fallback_style = Style::default()
-            .fg(RatColor::Rgb(130, 170, 200))
-            .bg(bg_color);
-
         let mut lines = Vec::new();
-        for code_line in code_lines {
+        for &(code_line, bg_color) in code_lines {
             let mut spans: Vec<Span<'static>> = Vec::new();
             let mut line_len = 0usize;
 
@@ -107,6 +117,9 @@ impl SyntaxHighlighter {
                 }
                 Err(_) => {
                     // Per-line fallback: use single color for this line
+                    let fallback_style = Style::default()
+                        .fg(RatColor::Rgb(130, 170, 200))
+                        .bg(bg_color);
                     line_len = code_line.chars().count();
                     spans.push(Span::styled(code_line.to_string(), fallback_style));
                 }
@@ -127,6 +140,16 @@ impl SyntaxHighlighter {
     }
 }
 
+/// Language tag for a file path: its extension, or the file name for
+/// extensionless files such as `Makefile`.
+pub fn language_for_path(path: &str) -> Option<String> {
+    let path = std::path::Path::new(path);
+    path.extension()
+        .or_else(|| path.file_name())
+        .and_then(|s| s.to_str())
+        .map(str::to_lowercase)
+}
+
 /// Extract the language tag from a code fence opening line.
 /// Handles formats like: ```rust, ```rust,ignore, ```python
 pub fn extract_language(fence_line: &str) -> Option<String> {
@@ -0,0 +1,127 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+
+use crate::session::Session;
+
+/// Layout of the session list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListLayout {
+    Flat,
+    Tree,
+}
+
+/// A single row of the project tree.
+#[derive(Debug, Clone)]
+pub enum TreeRow {
+    Project {
+        path: String,
+        name: String,
+        count: usize,
+        last_activity: DateTime<Utc>,
+        /// Distinct branches, most recent first.
+        branches: Vec<String>,
+        expanded: bool,
+    },
+    Branch {
+        project: String,
+        branch: String,
+        count: usize,
+    },
+    /// A session, referencing an index into `App::display_entries`.
+    Session { entry: usize, depth: usize },
+}
+
+/// Stable identity of a tree row, used to keep the selection across rebuilds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RowKey {
+    Project(String),
+    Branch(String, String),
+    Session(String),
+}
+
+/// Build the project tree from the (already filtered and sorted) display entries.
+///
+/// `sessions` holds the session of each display entry in order. Projects are
+/// ordered by last activity. Collapsed projects show only their header row,
+/// unless `expand_all` is set (e.g. while a filter is active). With
+/// `group_by_branch`, sessions are nested under one row per branch.
+pub fn build_tree(
+    sessions: &[&Session],
+    expanded: &HashSet<String>,
+    expand_all: bool,
+    group_by_branch: bool,
+) -> Vec<TreeRow> {
+    let mut order: Vec<String> = Vec::new();
+    let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+    for (entry, session) in sessions.iter().enumerate() {
+        let key = session.project_path.clone();
+        groups
+            .entry(key.clone())
+            .or_insert_with(|| {
+                order.push(key);
+                Vec::new()
+            })
+            .push(entry);
+    }
+
+    let last_activity = |entries: &[usize]| {
+        entries
+            .iter()
+            .map(|&e| sessions[e].timestamp)
+            .max()
+            .unwrap_or_default()
+    };
+    order.sort_by_key(|key| std::cmp::Reverse(last_activity(&groups[key])));
+
+    let mut rows = Vec::new();
+    for key in order {
+        let entries = &groups[&key];
+        let mut branches: Vec<String> = Vec::new();
+        for &e in entries {
+            let branch = branch_label(sessions[e]);
+            if !branches.contains(&branch) {
+                branches.push(branch);
+            }
+        }
+        let is_expanded = expand_all || expanded.contains(&key);
+        rows.push(TreeRow::Project {
+            path: key.clone(),
+            name: sessions[entries[0]].project_name.clone(),
+            count: entries.len(),
+            last_activity: last_activity(entries),
+            branches: branches.clone(),
+            expanded: is_expanded,
+        });
+        if !is_expanded {
+            continue;
+        }
+
+        if group_by_branch {
+            for branch in branches {
+                let in_branch: Vec<usize> = entries
+                    .iter()
+                    .copied()
+                    .filter(|&e| branch_label(sessions[e]) == branch)
+                    .collect();
+                rows.push(TreeRow::Branch {
+                    project: key.clone(),
+                    branch,
+                    count: in_branch.len(),
+                });
+                rows.extend(in_branch.into_iter().map(|entry| TreeRow::Session { entry, depth: 2 }));
+            }
+        } else {
+            rows.extend(entries.iter().map(|&entry| TreeRow::Session { entry, depth: 1 }));
+        }
+    }
+    rows
+}
+
+/// Branch name used for grouping; sessions without a branch share one group.
+pub fn branch_label(session: &Session) -> String {
+    match &session.git_branch {
+        Some(branch) if !branch.is_empty() => branch.clone(),
+        _ => "(no branch)".to_string(),
+    }
+}
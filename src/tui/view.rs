@@ -1,13 +1,20 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use chrono_humanize::{Accuracy, HumanTime, Tense};
 use ratatui::prelude::*;
 use ratatui::layout::Margin;
-use ratatui::widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState};
+use ratatui::widgets::{
+    Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState,
+};
+use regex::Regex;
 
-use crate::session::{ConversationMessage, MessageRole};
+use crate::columns::{fit_columns, Column, Density, COLUMN_GAP};
+use crate::diff::{classify_diff_line, diff_fence_path, is_fence, DiffLineKind, DIFF_LANGUAGE, HUNK_SEPARATOR};
+use crate::session::{ConversationMessage, MessageRole, Session};
+use crate::sort::SortKey;
 
 use super::table;
-use super::{App, ContentSearchState, Mode};
+use super::tree::{ListLayout, TreeRow};
+use super::{App, ContentSearchState, ConversationState, Mode, SidePanel, SourceLines};
 
 /// Render the full TUI frame.
 pub fn render(frame: &mut Frame, app: &mut App) {
@@ -17,92 +24,482 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         render_conversation(frame, app, area);
         return;
     }
+    if app.mode == Mode::Compare {
+        render_compare(frame, app, area);
+        return;
+    }
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Min(1), Constraint::Length(1)])
         .split(area);
 
-    render_session_list(frame, app, chunks[0]);
+    if app.show_preview && chunks[0].width >= MIN_PREVIEW_WIDTH {
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[0]);
+        app.hit_areas.list = panes[0];
+        render_session_list(frame, app, panes[0]);
+        render_preview(frame, app, panes[1]);
+    } else {
+        app.hit_areas.list = chunks[0];
+        render_session_list(frame, app, chunks[0]);
+    }
+    if app.view_menu.is_some() {
+        render_view_menu(frame, app, chunks[0]);
+    }
+    if app.relocate.is_some() {
+        render_relocate_menu(frame, app, chunks[0]);
+    }
+    if app.sort_menu.is_some() {
+        render_sort_menu(frame, app, chunks[0]);
+    }
     render_status_bar(frame, app, chunks[1]);
 }
 
-/// Render the session list with single-line entries.
-fn render_session_list(frame: &mut Frame, app: &App, area: Rect) {
-    let width = area.width.saturating_sub(2) as usize; // account for left/right borders
-    let height = area.height as usize;
-    let visible_items = height.saturating_sub(2); // account for top/bottom borders
-    let mut lines: Vec<Line> = Vec::new();
+/// Render the menu of saved views over the middle of `area`.
+fn render_view_menu(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(menu) = &app.view_menu else {
+        return;
+    };
+    let dim = Style::default().fg(app.theme.text_dim);
+    let mut lines: Vec<Line> = app
+        .views
+        .iter()
+        .enumerate()
+        .map(|(i, (name, view))| {
+            let mut details = Vec::new();
+            if !view.query.is_empty() {
+                details.push(format!("\"{}\"", view.query));
+            }
+            if !view.scope.is_empty() {
+                details.push(view.scope.describe());
+            }
+            details.extend(view.describe_sort());
+            let line = Line::from(vec![
+                Span::styled(format!(" {name} "), Style::default().fg(app.theme.text).bold()),
+                Span::styled(details.join(" \u{00b7} "), dim),
+            ]);
+            if i == menu.cursor && menu.naming.is_none() {
+                line.patch_style(Style::default().bg(app.theme.selected_bg))
+            } else {
+                line
+            }
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(Line::styled(" No saved views yet. Press s to save the current filter.", dim));
+    }
+    render_popup(frame, app, area, " Views ", lines, menu.cursor);
+}
 
-    let terms = search_terms(app);
-    let term_refs: Vec<&str> = terms.iter().map(|s| s.as_str()).collect();
+/// Render the suggested new locations of a missing project over `area`.
+fn render_relocate_menu(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(menu) = &app.relocate else {
+        return;
+    };
+    let dim = Style::default().fg(app.theme.text_dim);
+    let lines: Vec<Line> = menu
+        .suggestions
+        .iter()
+        .enumerate()
+        .map(|(i, suggestion)| {
+            let line = Line::from(vec![
+                Span::styled(
+                    format!(" {} ", suggestion.path.display()),
+                    Style::default().fg(app.theme.text).bold(),
+                ),
+                Span::styled(suggestion.remote.clone().unwrap_or_default(), dim),
+            ]);
+            if i == menu.cursor {
+                line.patch_style(Style::default().bg(app.theme.selected_bg))
+            } else {
+                line
+            }
+        })
+        .collect();
+    let title = format!(" Relocate {} ", menu.project);
+    render_popup(frame, app, area, &title, lines, menu.cursor);
+}
 
-    let start = app.scroll_offset;
-    let end = (start + visible_items).min(app.display_entries.len());
+/// Render the sort keys over `area`, marking the current one, with the
+/// direction below them.
+fn render_sort_menu(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(menu) = &app.sort_menu else {
+        return;
+    };
+    let dim = Style::default().fg(app.theme.text_dim);
+    let mut lines: Vec<Line> = SortKey::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, &key)| {
+            let current = if key == app.sort { "\u{25cf}" } else { " " };
+            let line = Line::from(vec![
+                Span::styled(format!(" {current} "), Style::default().fg(app.theme.cursor_color)),
+                Span::styled(key.label(), Style::default().fg(app.theme.text).bold()),
+            ]);
+            if i == menu.cursor {
+                line.patch_style(Style::default().bg(app.theme.selected_bg))
+            } else {
+                line
+            }
+        })
+        .collect();
+    let reverse = if app.sort_reverse { "[x]" } else { "[ ]" };
+    lines.push(Line::from(""));
+    lines.push(Line::styled(format!(" {reverse} reversed"), dim));
+    render_popup(frame, app, area, " Sort by ", lines, menu.cursor);
+}
 
-    for i in start..end {
-        let entry = &app.display_entries[i];
-        let session = app.display_session(entry);
-        let is_selected = i == app.selected;
+/// Render a bordered popup of `lines` over the middle of `area`, scrolled
+/// to keep line `cursor` visible.
+fn render_popup(frame: &mut Frame, app: &App, area: Rect, title: &str, mut lines: Vec<Line>, cursor: usize) {
+    let width = area.width.saturating_sub(4).min(72);
+    let height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+    let popup = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + (area.height.saturating_sub(height)) / 3,
+        width,
+        height,
+    };
+    let visible = height.saturating_sub(2) as usize;
+    let offset = (cursor + 1).saturating_sub(visible);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.cursor_color))
+        .title(title.to_string())
+        .title_style(Style::default().fg(app.theme.cursor_color).bold());
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(Text::from(lines.split_off(offset.min(lines.len())))).block(block),
+        popup,
+    );
+}
 
-        let delta = Utc::now().signed_duration_since(session.timestamp);
-        let time_ago = HumanTime::from(-delta).to_text_en(Accuracy::Rough, Tense::Past);
-        let right = format!("{}  {}", session.project_name, time_ago);
-        let right_len = right.len();
+/// Narrowest terminal that shows the preview pane next to the list.
+const MIN_PREVIEW_WIDTH: u16 = 80;
 
-        let (cursor, cursor_len) = if is_selected {
-            ("\u{27A4} ", 2)
-        } else {
-            ("  ", 2)
-        };
+/// Wrapped lines shown per prompt in the preview pane.
+const PREVIEW_PROMPT_LINES: usize = 3;
+
+/// Render the preview of the selected session.
+fn render_preview(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.text_dim))
+        .title(" Preview ")
+        .title_style(Style::default().fg(app.theme.cursor_color).bold());
+    let inner = block.inner(area);
+    let lines = preview_lines(app, inner.width as usize, inner.height as usize);
+    frame.render_widget(Paragraph::new(Text::from(lines)).block(block), area);
+}
 
-        let max_msg_len = width.saturating_sub(cursor_len + right_len + 2);
-        let msg = truncate_str(&session.first_message, max_msg_len);
-        let msg_len = msg.chars().count();
-        let pad = width.saturating_sub(cursor_len + msg_len + right_len);
-        let padding = " ".repeat(pad);
+/// Lines of the preview pane: metadata, the first prompts, and as much of
+/// the last reply as fits in `height`.
+fn preview_lines(app: &App, width: usize, height: usize) -> Vec<Line<'static>> {
+    let dim = Style::default().fg(app.theme.text_dim);
+    let Some(session) = app.selected_session() else {
+        return vec![Line::styled(" no session selected", dim)];
+    };
+    let text_width = width.saturating_sub(2);
+    let label = |name: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!(" {name:<9} "), dim),
+            Span::styled(
+                truncate_str(&value, text_width.saturating_sub(10)),
+                Style::default().fg(app.theme.text),
+            ),
+        ])
+    };
+
+    let mut lines = vec![label("Project", session.project_path.clone())];
+    if let Some(branch) = session.git_branch.as_ref().filter(|b| !b.is_empty()) {
+        lines.push(label("Branch", branch.clone()));
+    }
+    let local = session.timestamp.with_timezone(&chrono::Local);
+    lines.push(label(
+        "Active",
+        format!("{} ({})", local.format("%Y-%m-%d %H:%M"), time_ago(session.timestamp)),
+    ));
+    lines.push(label("Session", session.id.clone()));
+
+    let Some(preview) = app.previews.get(session) else {
+        lines.push(Line::from(""));
+        lines.push(Line::styled(" loading...", dim));
+        return lines;
+    };
+    lines.push(label(
+        "Messages",
+        format!(
+            "{} prompts \u{00B7} {} replies",
+            preview.user_messages, preview.assistant_messages
+        ),
+    ));
+
+    let heading = |text: &str| Line::styled(format!(" {text}"), dim.bold());
+    let user_style = Style::default().fg(app.theme.cursor_color);
+    lines.push(Line::from(""));
+    lines.push(heading("Prompts"));
+    for prompt in &preview.prompts {
+        let wrapped: Vec<String> = prompt
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .flat_map(|l| wrap_line(l, text_width.saturating_sub(2)))
+            .collect();
+        for (i, line) in wrapped.iter().take(PREVIEW_PROMPT_LINES).enumerate() {
+            let marker = if i == 0 { " \u{25B6} " } else { "   " };
+            let line = if i + 1 == PREVIEW_PROMPT_LINES && wrapped.len() > PREVIEW_PROMPT_LINES {
+                truncate_str(&format!("{line}..."), text_width.saturating_sub(2))
+            } else {
+                line.clone()
+            };
+            lines.push(Line::from(vec![
+                Span::styled(marker, user_style),
+                Span::styled(line, Style::default().fg(app.theme.text)),
+            ]));
+        }
+    }
 
-        let msg_style = if is_selected {
-            Style::default().fg(Color::White)
+    if let Some(reply) = &preview.last_reply {
+        lines.push(Line::from(""));
+        lines.push(heading("Last reply"));
+        let room = height.saturating_sub(lines.len());
+        let reply_style = Style::default().fg(app.theme.text);
+        lines.extend(
+            reply
+                .lines()
+                .flat_map(|l| wrap_line(l, text_width))
+                .take(room)
+                .map(|l| Line::styled(format!(" {l}"), reply_style)),
+        );
+    }
+    lines
+}
+
+/// Render two marked sessions side by side, each with a summary header.
+fn render_compare(frame: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(area);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[0]);
+    app.hit_areas.compare = [columns[0], columns[1]];
+
+    let Some(compare) = &mut app.compare else {
+        return;
+    };
+    let dim = Style::default().fg(app.theme.text_dim);
+    for (i, pane) in compare.panes.iter_mut().enumerate() {
+        let focused = compare.locked || i == compare.focus;
+        let border = if focused {
+            Style::default().fg(app.theme.cursor_color)
         } else {
-            Style::default().fg(app.theme.text)
+            dim
         };
+        let title_width = (columns[i].width as usize).saturating_sub(4);
+        let title = truncate_str(
+            &format!("{} \u{00B7} {}", pane.session.project_name, pane.session.first_message),
+            title_width,
+        );
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border)
+            .title(format!(" {title} "))
+            .title_style(border.bold());
+        let inner = block.inner(columns[i]);
+        frame.render_widget(block, columns[i]);
+
+        let summary = compare_summary_lines(&compare.activity[i], &app.theme, inner.width as usize);
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(summary.len() as u16), Constraint::Min(1)])
+            .split(inner);
+        frame.render_widget(Paragraph::new(Text::from(summary)), parts[0]);
+
+        let body = parts[1];
+        pane.page_height = body.height as usize;
+        if pane.rendered_width != body.width || pane.lines.is_empty() {
+            let search = pane.search_regex().ok().flatten();
+            let rendered = pre_render_conversation(
+                &pane.messages,
+                &mut pane.folds,
+                body.width as usize,
+                search.as_ref(),
+                &app.theme,
+                &app.syntax_highlighter,
+            );
+            pane.lines = rendered.lines;
+            pane.message_starts = rendered.message_starts;
+            pane.line_sources = rendered.line_sources;
+            pane.rendered_width = body.width;
+            pane.scroll_by(0);
+        }
+        let start = pane.scroll_offset.min(pane.lines.len().saturating_sub(1));
+        let end = (start + pane.page_height).min(pane.lines.len());
+        frame.render_widget(Paragraph::new(Text::from(pane.lines[start..end].to_vec())), body);
+    }
 
-        let dim = Style::default().fg(app.theme.text_dim);
+    let label_style = Style::default()
+        .fg(app.theme.status_label_fg)
+        .bg(app.theme.status_label_bg)
+        .bold();
+    let lock = if compare.locked { "on" } else { "off" };
+    let status = Line::from(vec![
+        Span::styled(" COMPARE ", label_style),
+        Span::raw(" "),
+        Span::styled(
+            format!("Tab focus  l lock ({lock})  Space/b scroll  g/G top/bottom  Esc back"),
+            dim,
+        ),
+    ]);
+    frame.render_widget(
+        Paragraph::new(status).style(Style::default().bg(app.theme.status_bar_bg)),
+        chunks[1],
+    );
+}
+
+/// Summary header of one compared session: model, turns, tokens, duration,
+/// tools, and files.
+fn compare_summary_lines(
+    activity: &crate::activity::SessionActivity,
+    theme: &crate::theme::Theme,
+    width: usize,
+) -> Vec<Line<'static>> {
+    let tokens = &activity.tokens;
+    let duration = activity
+        .duration()
+        .map(format_duration)
+        .unwrap_or_else(|| "-".to_string());
+    let mut tools: Vec<(&String, &usize)> = activity.tool_counts.iter().collect();
+    tools.sort_by_key(|(_, count)| std::cmp::Reverse(**count));
+    let mut tool_list: Vec<String> = tools.iter().take(4).map(|(t, c)| format!("{t} {c}")).collect();
+    if tools.len() > 4 {
+        tool_list.push(format!("+{}", tools.len() - 4));
+    }
+    let files = activity.file_summary();
+    let edited = files.iter().filter(|f| f.edits > 0).count();
+
+    let rows = [
+        ("Model", activity.models.join(", ")),
+        (
+            "Turns",
+            format!("{} prompts \u{00B7} {} replies", activity.user_turns, activity.assistant_turns),
+        ),
+        (
+            "Tokens",
+            format!(
+                "{} in \u{00B7} {} out",
+                format_tokens(tokens.total_input()),
+                format_tokens(tokens.output)
+            ),
+        ),
+        ("Duration", duration),
+        ("Tools", tool_list.join(" \u{00B7} ")),
+        ("Files", format!("{} touched \u{00B7} {edited} edited", files.len())),
+    ];
+    let mut lines: Vec<Line<'static>> = rows
+        .into_iter()
+        .map(|(label, value)| {
+            let value = truncate_str(&value, width.saturating_sub(11));
+            Line::from(vec![
+                Span::styled(format!(" {label:<9} "), Style::default().fg(theme.text_dim)),
+                Span::styled(value, Style::default().fg(theme.text)),
+            ])
+        })
+        .collect();
+    lines.push(Line::styled("\u{2500}".repeat(width), Style::default().fg(theme.separator)));
+    lines
+}
+
+/// Compact token count, e.g. `950`, `12.3k`, `1.2M`.
+fn format_tokens(n: u64) -> String {
+    match n {
+        0..=999 => n.to_string(),
+        1_000..=999_999 => format!("{:.1}k", n as f64 / 1_000.0),
+        _ => format!("{:.1}M", n as f64 / 1_000_000.0),
+    }
+}
 
-        let cursor_style = Style::default().fg(app.theme.cursor_color);
+/// Compact duration, e.g. `45s`, `12m`, `1h 05m`.
+fn format_duration(d: chrono::Duration) -> String {
+    let secs = d.num_seconds().max(0);
+    if secs < 60 {
+        format!("{secs}s")
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+    }
+}
+
+/// Narrowest the prompt gets before columns are dropped in one-line rows.
+const MIN_PROMPT_WIDTH: usize = 20;
+
+/// Render the session list, one or two lines per entry.
+fn render_session_list(frame: &mut Frame, app: &App, area: Rect) {
+    let width = area.width.saturating_sub(2) as usize; // account for left/right borders
+    let height = area.height as usize;
+    let row_height = app.density.row_height();
+    let visible_items = height.saturating_sub(2) / row_height; // account for top/bottom borders
+    let mut lines: Vec<Line> = Vec::new();
 
-        let mut spans = vec![Span::styled(cursor, cursor_style)];
-        spans.extend(highlight_terms(&msg, &term_refs, msg_style, &app.theme));
-        spans.push(Span::raw(padding));
-        spans.push(Span::styled(right, dim));
+    let terms = search_terms(app);
+    let term_refs: Vec<&str> = terms.iter().map(|s| s.as_str()).collect();
 
-        let line = Line::from(spans);
+    let start = app.scroll_offset;
+    let end = (start + visible_items).min(app.row_count());
+    let columns = fit_list_columns(app, start..end, width);
 
-        if is_selected {
-            lines.push(line.patch_style(Style::default().bg(app.theme.selected_bg)));
-        } else {
-            lines.push(line);
+    for i in start..end {
+        let is_selected = i == app.selected;
+        let mut row = match app.layout {
+            ListLayout::Flat => session_row(app, i, 0, is_selected, width, &term_refs, &columns),
+            ListLayout::Tree => match &app.tree_rows[i] {
+                row @ TreeRow::Project { .. } => vec![project_row(app, row, is_selected, width)],
+                TreeRow::Branch { branch, count, .. } => {
+                    vec![branch_row(app, branch, *count, is_selected)]
+                }
+                TreeRow::Session { entry, depth } => {
+                    session_row(app, *entry, *depth, is_selected, width, &term_refs, &columns)
+                }
+            },
+        };
+        row.resize(row_height, Line::default());
+
+        for line in row {
+            if is_selected {
+                lines.push(line.patch_style(Style::default().bg(app.theme.selected_bg)));
+            } else {
+                lines.push(line);
+            }
         }
     }
-
     let text = Text::from(lines);
     let border_style = Style::default().fg(app.theme.text_dim);
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(border_style)
-        .title(format!(
-            " cc-session ({}/{}) ",
-            app.display_entries.len(),
-            app.sessions.len()
-        ))
+        .title(if app.scope.is_empty() {
+            format!(" cc-session ({}/{}) ", app.display_entries.len(), app.sessions.len())
+        } else {
+            format!(
+                " cc-session ({}/{}) [{}] ",
+                app.display_entries.len(),
+                app.sessions.len(),
+                app.scope.describe()
+            )
+        })
         .title_style(Style::default().fg(app.theme.cursor_color).bold());
 
     let paragraph = Paragraph::new(text).block(block);
     frame.render_widget(paragraph, area);
 
     // Render vertical scrollbar
-    let total = app.display_entries.len();
+    let total = app.row_count();
     let visible = area.height.saturating_sub(2) as usize;
     if total > visible {
         let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
@@ -122,6 +519,218 @@ fn render_session_list(frame: &mut Frame, app: &App, area: Rect) {
     }
 }
 
+/// Session shown in list row `row`, with its tree depth.
+fn list_row_session(app: &App, row: usize) -> Option<(&Session, usize)> {
+    let (entry, depth) = match app.layout {
+        ListLayout::Flat => (row, 0),
+        ListLayout::Tree => match app.tree_rows.get(row)? {
+            TreeRow::Session { entry, depth } => (*entry, *depth),
+            _ => return None,
+        },
+    };
+    Some((app.display_session(app.display_entries.get(entry)?), depth))
+}
+
+/// Widths of the configured columns for the visible rows: as wide as their
+/// widest text, truncated or dropped by priority when the row is too narrow.
+fn fit_list_columns(app: &App, rows: std::ops::Range<usize>, width: usize) -> Vec<(Column, usize)> {
+    let mut widths = vec![0; app.columns.len()];
+    for (session, depth) in rows.filter_map(|row| list_row_session(app, row)) {
+        for (width, &column) in widths.iter_mut().zip(&app.columns) {
+            *width = (*width).max(column_text(app, session, column, depth).chars().count());
+        }
+    }
+    // Leave room for the cursor and up to two levels of tree indent
+    let available = match app.density {
+        Density::OneLine => width.saturating_sub(6 + MIN_PROMPT_WIDTH),
+        Density::TwoLine => width.saturating_sub(6),
+    };
+    let fitted = fit_columns(&app.columns, &widths, available);
+    app.columns.iter().copied().zip(fitted).collect()
+}
+
+/// Text of one column of a session row. Counts from the session scan are
+/// empty until it has run.
+fn column_text(app: &App, session: &Session, column: Column, depth: usize) -> String {
+    let stats = app.stats.get(&session.id);
+    match column {
+        Column::Running if app.is_live(&session.id) => "\u{25B6} running".to_string(),
+        Column::Running => String::new(),
+        // Tree rows are nested under their project
+        Column::Project if depth == 0 => session.project_name.clone(),
+        Column::Project => String::new(),
+        Column::Branch => session.git_branch.clone().unwrap_or_default(),
+        Column::Model => stats
+            .and_then(|s| s.model.as_deref())
+            .map(short_model)
+            .unwrap_or_default(),
+        Column::Turns => stats.map_or_else(String::new, |s| match s.turns {
+            1 => "1 turn".to_string(),
+            n => format!("{n} turns"),
+        }),
+        Column::Tokens => stats.map_or_else(String::new, |s| format!("{} tok", format_tokens(s.tokens))),
+        Column::Duration => stats.map_or_else(String::new, |s| format_duration(s.duration)),
+        Column::Id => session.id.chars().take(8).collect(),
+        Column::Version => session.version.as_deref().map(|v| format!("v{v}")).unwrap_or_default(),
+        Column::Time => time_ago(session.timestamp),
+    }
+}
+
+/// Model name without the `claude-` prefix and date suffix, e.g.
+/// `sonnet-4-5` for `claude-sonnet-4-5-20250929`.
+fn short_model(model: &str) -> String {
+    let model = model.strip_prefix("claude-").unwrap_or(model);
+    match model.rsplit_once('-') {
+        Some((name, date)) if date.len() == 8 && date.bytes().all(|b| b.is_ascii_digit()) => name.to_string(),
+        _ => model.to_string(),
+    }
+}
+
+/// Build a session row: one line with the prompt followed by the columns,
+/// or the columns on a second line. `depth` > 0 indents the row under its
+/// tree parent, where the project name is implied and therefore omitted.
+fn session_row(
+    app: &App,
+    entry: usize,
+    depth: usize,
+    is_selected: bool,
+    width: usize,
+    term_refs: &[&str],
+    columns: &[(Column, usize)],
+) -> Vec<Line<'static>> {
+    let session = app.display_session(&app.display_entries[entry]);
+
+    let dim = Style::default().fg(app.theme.text_dim);
+    let mut cells: Vec<Span<'static>> = Vec::new();
+    for &(column, column_width) in columns.iter().filter(|(_, w)| *w > 0) {
+        let text = truncate_str(&column_text(app, session, column, depth), column_width);
+        let text = if column.right_aligned() {
+            format!("{text:>column_width$}")
+        } else {
+            format!("{text:<column_width$}")
+        };
+        let style = if column == Column::Running {
+            Style::default().fg(app.theme.updated_marker).bold()
+        } else {
+            dim
+        };
+        cells.push(Span::raw(" ".repeat(COLUMN_GAP)));
+        cells.push(Span::styled(text, style));
+    }
+    let columns_len: usize = cells.iter().map(|s| s.content.chars().count()).sum();
+
+    let is_updated = app.is_recently_updated(&session.id);
+    let is_marked = app.is_marked(&session.id);
+    let cursor = if is_selected {
+        "\u{27A4} "
+    } else if is_marked {
+        "\u{25C6} "
+    } else if is_updated {
+        "\u{25CF} "
+    } else {
+        "  "
+    };
+    let indent = "  ".repeat(depth);
+    let cursor_len = 2 + indent.len();
+
+    let two_line = app.density == Density::TwoLine;
+    let max_msg_len = if two_line {
+        width.saturating_sub(cursor_len)
+    } else {
+        width.saturating_sub(cursor_len + columns_len)
+    };
+    let msg = truncate_str(&session.first_message, max_msg_len);
+    let msg_len = msg.chars().count();
+
+    let msg_style = if is_selected {
+        Style::default().fg(Color::White)
+    } else {
+        Style::default().fg(app.theme.text)
+    };
+
+    let cursor_style = if is_updated && !is_marked {
+        Style::default().fg(app.theme.updated_marker)
+    } else {
+        Style::default().fg(app.theme.cursor_color)
+    };
+
+    let mut spans = vec![Span::styled(cursor, cursor_style), Span::raw(indent.clone())];
+    spans.extend(highlight_terms(&msg, term_refs, msg_style, &app.theme));
+    if two_line {
+        // Columns start under the prompt
+        let mut second = vec![Span::raw(" ".repeat(cursor_len))];
+        second.extend(cells.into_iter().skip(1));
+        return vec![Line::from(spans), Line::from(second)];
+    }
+    let pad = width.saturating_sub(cursor_len + msg_len + columns_len);
+    spans.push(Span::raw(" ".repeat(pad)));
+    spans.extend(cells);
+    vec![Line::from(spans)]
+}
+
+/// Build a project header row of the tree.
+fn project_row(app: &App, row: &TreeRow, is_selected: bool, width: usize) -> Line<'static> {
+    let TreeRow::Project {
+        name,
+        count,
+        last_activity,
+        branches,
+        expanded,
+        ..
+    } = row
+    else {
+        return Line::default();
+    };
+    let cursor = if is_selected { "\u{27A4} " } else { "  " };
+    let arrow = if *expanded { "\u{25BE} " } else { "\u{25B8} " };
+    let sessions = if *count == 1 {
+        "1 session".to_string()
+    } else {
+        format!("{count} sessions")
+    };
+    let branch_list = match branches.len() {
+        0 => String::new(),
+        1..=3 => format!(" \u{00B7} {}", branches.join(", ")),
+        n => format!(" \u{00B7} {} +{}", branches[..3].join(", "), n - 3),
+    };
+    let info = format!("  {sessions}{branch_list}");
+    let right = time_ago(*last_activity);
+
+    let used = 4 + name.chars().count() + right.chars().count();
+    let info = truncate_str(&info, width.saturating_sub(used + 2));
+    let pad = width.saturating_sub(used + info.chars().count());
+
+    let dim = Style::default().fg(app.theme.text_dim);
+    Line::from(vec![
+        Span::styled(cursor, Style::default().fg(app.theme.cursor_color)),
+        Span::styled(arrow, Style::default().fg(app.theme.cursor_color)),
+        Span::styled(name.clone(), Style::default().fg(app.theme.text).bold()),
+        Span::styled(info, dim),
+        Span::raw(" ".repeat(pad)),
+        Span::styled(right, dim),
+    ])
+}
+
+/// Build a branch row of the tree.
+fn branch_row(app: &App, branch: &str, count: usize, is_selected: bool) -> Line<'static> {
+    let cursor = if is_selected { "\u{27A4} " } else { "  " };
+    Line::from(vec![
+        Span::styled(cursor, Style::default().fg(app.theme.cursor_color)),
+        Span::raw("  "),
+        Span::styled(
+            format!("\u{2387} {branch}"),
+            Style::default().fg(app.theme.cursor_color),
+        ),
+        Span::styled(format!("  ({count})"), Style::default().fg(app.theme.text_dim)),
+    ])
+}
+
+/// Human-readable age of a timestamp, e.g. "3 hours ago".
+fn time_ago(timestamp: DateTime<Utc>) -> String {
+    let delta = Utc::now().signed_duration_since(timestamp);
+    HumanTime::from(-delta).to_text_en(Accuracy::Rough, Tense::Past)
+}
+
 const MAX_CONTENT_WIDTH: u16 = 120;
 
 /// Render the conversation viewer.
@@ -158,9 +767,30 @@ fn render_conversation(frame: &mut Frame, app: &mut App, area: Rect) {
         .border_style(border_style)
         .title(format!(" cc-session{title_extra}"))
         .title_style(title_style);
-    let inner_area = block.inner(full_content_area);
+    let mut inner_area = block.inner(full_content_area);
     frame.render_widget(block, full_content_area);
 
+    if let Some(panel) = app.conversation.as_ref().and_then(|c| c.side_panel) {
+        let panel_width = (inner_area.width * 2 / 5).clamp(24, 60).min(inner_area.width / 2);
+        let split = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(1), Constraint::Length(panel_width)])
+            .split(inner_area);
+        inner_area = split[0];
+        render_side_panel(frame, app, panel, split[1]);
+    }
+
+    app.hit_areas.toc = Rect::default();
+    if app.conversation.as_ref().is_some_and(|c| c.toc.is_some()) {
+        let toc_width = (inner_area.width / 3).clamp(24, 48).min(inner_area.width / 2);
+        let split = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(toc_width), Constraint::Min(1)])
+            .split(inner_area);
+        inner_area = split[1];
+        render_toc(frame, app, split[0]);
+    }
+
     let terminal_width = inner_area.width;
     let content_width = terminal_width.min(MAX_CONTENT_WIDTH);
     let left_margin = (terminal_width.saturating_sub(content_width)) / 2;
@@ -173,27 +803,55 @@ fn render_conversation(frame: &mut Frame, app: &mut App, area: Rect) {
     };
 
     let height = content_area.height as usize;
+    let track = full_content_area.inner(Margin {
+        vertical: 1,
+        horizontal: 0,
+    });
+    app.hit_areas.conversation = content_area;
+    app.hit_areas.conversation_scrollbar = Rect {
+        x: track.right().saturating_sub(1),
+        width: 1,
+        ..track
+    };
+
+    if let Some(diff) = app.conversation.as_mut().and_then(|c| c.combined_diff.as_mut()) {
+        diff.page_height = height;
+        if diff.rendered_width != content_width {
+            diff.lines = render_combined_diff(
+                diff,
+                content_width as usize,
+                &app.theme,
+                &app.syntax_highlighter,
+            );
+            diff.rendered_width = content_width;
+        }
+        let start = diff.scroll_offset.min(diff.lines.len().saturating_sub(1));
+        let end = (start + height).min(diff.lines.len());
+        frame.render_widget(Paragraph::new(Text::from(diff.lines[start..end].to_vec())), content_area);
+        render_conversation_status(frame, app, status_area);
+        return;
+    }
 
     if let Some(conv) = &mut app.conversation {
         conv.page_height = height;
 
+        let search = conv.search_regex().ok().flatten();
+
         if conv.rendered_width != content_width || conv.lines.is_empty() {
-            let search_terms: Vec<String> = if !conv.search_query.is_empty() {
-                vec![conv.search_query.clone()]
-            } else {
-                conv.initial_search_terms.clone()
-            };
-            let term_refs: Vec<&str> = search_terms.iter().map(|s| s.as_str()).collect();
-            conv.lines = pre_render_conversation(
+            let rendered = pre_render_conversation(
                 &conv.messages,
+                &mut conv.folds,
                 content_width as usize,
-                &term_refs,
+                search.as_ref(),
                 &app.theme,
                 &app.syntax_highlighter,
             );
+            conv.lines = rendered.lines;
+            conv.message_starts = rendered.message_starts;
+            conv.line_sources = rendered.line_sources;
+            conv.match_positions = rendered.match_positions;
             conv.rendered_width = content_width;
-
-            conv.match_positions = find_match_positions(&conv.lines, &term_refs);
+            conv.dirty_from = None;
 
             if !conv.match_positions.is_empty()
                 && conv.scroll_offset == 0
@@ -203,6 +861,27 @@ fn render_conversation(frame: &mut Frame, app: &mut App, area: Rect) {
                 let max = conv.lines.len().saturating_sub(height);
                 conv.scroll_offset = match_line.saturating_sub(height / 2).min(max);
             }
+            if let Some(idx) = conv.anchor_message.take() {
+                let max = conv.lines.len().saturating_sub(height);
+                if let Some(&start) = conv.message_starts.get(idx) {
+                    conv.scroll_offset = start.min(max);
+                }
+            }
+        } else if let Some(from) = conv.dirty_from.take() {
+            render_appended_messages(
+                conv,
+                from,
+                content_width as usize,
+                search.as_ref(),
+                &app.theme,
+                &app.syntax_highlighter,
+            );
+        }
+        // Keep the selection within re-rendered content
+        let last = conv.lines.len().saturating_sub(1);
+        if let Some(visual) = &mut conv.visual {
+            visual.anchor = visual.anchor.min(last);
+            visual.cursor = visual.cursor.min(last);
         }
     }
 
@@ -246,6 +925,16 @@ fn render_conversation(frame: &mut Frame, app: &mut App, area: Rect) {
             }
         }
 
+        if let Some(visual) = &conv.visual {
+            let (first, last) = visual.range();
+            let selected = Style::default().bg(app.theme.selected_bg);
+            for (i, line) in visible_lines.iter_mut().enumerate() {
+                if (first..=last).contains(&(start + i)) {
+                    *line = line.clone().patch_style(selected);
+                }
+            }
+        }
+
         let text = Text::from(visible_lines);
 
         let paragraph = Paragraph::new(text);
@@ -268,7 +957,273 @@ fn render_conversation(frame: &mut Frame, app: &mut App, area: Rect) {
         );
     }
 
-    render_conversation_status(frame, app, status_area);
+    render_conversation_status(frame, app, status_area);
+}
+
+/// Render the table of contents: one line per user prompt with its time and
+/// the number of replies it received. The prompt in view is marked, and the
+/// cursor is highlighted while the sidebar has focus.
+fn render_toc(frame: &mut Frame, app: &mut App, area: Rect) {
+    let theme = &app.theme;
+    let Some(conv) = &mut app.conversation else {
+        return;
+    };
+    let current = conv.current_prompt();
+    let (Some(toc), Some(activity)) = (&mut conv.toc, &conv.activity) else {
+        return;
+    };
+    let dim = Style::default().fg(theme.text_dim);
+    let block = Block::default()
+        .borders(Borders::RIGHT)
+        .border_style(dim)
+        .title(format!(" Prompts ({}) ", activity.prompts.len()))
+        .title_style(Style::default().fg(theme.cursor_color).bold());
+    let inner = block.inner(area);
+    app.hit_areas.toc = inner;
+
+    let height = (inner.height as usize).max(1);
+    let focus_row = if toc.focused {
+        toc.cursor
+    } else {
+        current.unwrap_or(0)
+    };
+    if focus_row < toc.offset {
+        toc.offset = focus_row;
+    } else if focus_row >= toc.offset + height {
+        toc.offset = focus_row + 1 - height;
+    }
+
+    let width = inner.width as usize;
+    let mut lines = Vec::new();
+    for (i, prompt) in activity.prompts.iter().enumerate().skip(toc.offset).take(height) {
+        let is_current = current == Some(i);
+        let marker = if is_current { "\u{25B6} " } else { "  " };
+        let time = prompt
+            .timestamp
+            .map(|t| t.with_timezone(&chrono::Local).format("%H:%M ").to_string())
+            .unwrap_or_default();
+        let turns = format!(" {}", prompt.assistant_turns);
+        let used = 2 + time.chars().count() + turns.chars().count();
+        let text = truncate_str(prompt.text.lines().next().unwrap_or(""), width.saturating_sub(used));
+        let pad = width.saturating_sub(used + text.chars().count());
+        let text_style = if is_current {
+            Style::default().fg(theme.text).bold()
+        } else {
+            Style::default().fg(theme.text)
+        };
+        let line = Line::from(vec![
+            Span::styled(marker, Style::default().fg(theme.cursor_color)),
+            Span::styled(time, dim),
+            Span::styled(text, text_style),
+            Span::raw(" ".repeat(pad)),
+            Span::styled(turns, dim),
+        ]);
+        if toc.focused && i == toc.cursor {
+            lines.push(line.patch_style(Style::default().bg(theme.selected_bg)));
+        } else {
+            lines.push(line);
+        }
+    }
+    if activity.prompts.is_empty() {
+        lines.push(Line::styled(" no prompts", dim));
+    }
+    frame.render_widget(Paragraph::new(Text::from(lines)).block(block), area);
+}
+
+/// Render a side panel of the conversation viewer.
+fn render_side_panel(frame: &mut Frame, app: &App, panel: SidePanel, area: Rect) {
+    let Some(conv) = &app.conversation else {
+        return;
+    };
+    let lines = match panel {
+        SidePanel::Commits => commit_panel_lines(app, conv, area.width),
+        SidePanel::Files => file_panel_lines(app, conv, area.width),
+    };
+    let mut tabs = vec![Span::raw(" ")];
+    for tab in SidePanel::ALL {
+        let style = if tab == panel {
+            Style::default().fg(app.theme.cursor_color).bold()
+        } else {
+            Style::default().fg(app.theme.text_dim)
+        };
+        tabs.push(Span::styled(tab.title(), style));
+        tabs.push(Span::raw(" "));
+    }
+    let block = Block::default()
+        .borders(Borders::LEFT)
+        .border_style(Style::default().fg(app.theme.text_dim))
+        .title(Line::from(tabs));
+    frame.render_widget(Paragraph::new(Text::from(lines)).block(block), area);
+}
+
+/// Lines of the commits panel. Commits touching files the session edited
+/// are marked and emphasized.
+fn commit_panel_lines(app: &App, conv: &ConversationState, width: u16) -> Vec<Line<'static>> {
+    let dim = Style::default().fg(app.theme.text_dim);
+    let width = width.saturating_sub(2) as usize;
+    let commits = match &conv.commits {
+        None => return vec![Line::styled(" loading...", dim)],
+        Some(Err(e)) => return vec![Line::styled(format!(" {e}"), dim)],
+        Some(Ok(commits)) => commits,
+    };
+    if commits.is_empty() {
+        return vec![Line::styled(" no commits during this session", dim)];
+    }
+
+    let touching = commits.iter().filter(|c| c.touches_session_edits).count();
+    let mut lines = vec![
+        Line::styled(
+            format!(" {} commits, {touching} touch edited files", commits.len()),
+            dim,
+        ),
+        Line::from(""),
+    ];
+    for commit in commits {
+        let (marker, subject_style) = if commit.touches_session_edits {
+            (
+                Span::styled("\u{25CF} ", Style::default().fg(app.theme.updated_marker)),
+                Style::default().fg(app.theme.text).bold(),
+            )
+        } else {
+            (Span::raw("  "), Style::default().fg(app.theme.text_dim))
+        };
+        let subject = truncate_str(
+            &commit.subject,
+            width.saturating_sub(commit.short_hash.len() + 3),
+        );
+        lines.push(Line::from(vec![
+            Span::raw(" "),
+            marker,
+            Span::styled(commit.short_hash.clone(), Style::default().fg(app.theme.cursor_color)),
+            Span::raw(" "),
+            Span::styled(subject, subject_style),
+        ]));
+        let local = commit.timestamp.with_timezone(&chrono::Local);
+        let detail = format!(
+            "{}, {} \u{00B7} {} files",
+            commit.author,
+            local.format("%Y-%m-%d %H:%M"),
+            commit.files.len()
+        );
+        lines.push(Line::styled(
+            format!("   {}", truncate_str(&detail, width.saturating_sub(3))),
+            dim,
+        ));
+    }
+    lines
+}
+
+/// Lines of the files panel: files the session edited, then files it only
+/// read, shown relative to the project directory where possible.
+fn file_panel_lines(app: &App, conv: &ConversationState, width: u16) -> Vec<Line<'static>> {
+    let dim = Style::default().fg(app.theme.text_dim);
+    let width = width.saturating_sub(2) as usize;
+    let Some(activity) = &conv.activity else {
+        return vec![Line::styled(" loading...", dim)];
+    };
+    let files = activity.file_summary();
+    if files.is_empty() {
+        return vec![Line::styled(" no files touched", dim)];
+    }
+
+    let project = format!("{}/", conv.session.project_path.trim_end_matches('/'));
+    let edited = files.iter().filter(|f| f.edits > 0).count();
+    let mut lines = Vec::new();
+    for (i, file) in files.iter().enumerate() {
+        if i == 0 && edited > 0 {
+            lines.push(Line::styled(format!(" Edited ({edited})"), dim.bold()));
+        }
+        if i == edited {
+            if edited > 0 {
+                lines.push(Line::from(""));
+            }
+            lines.push(Line::styled(format!(" Read only ({})", files.len() - edited), dim.bold()));
+        }
+        let path = file.path.strip_prefix(&project).unwrap_or(&file.path);
+        let count = if file.edits > 0 {
+            format!("{}\u{00D7}", file.edits)
+        } else {
+            String::new()
+        };
+        let path_style = if file.edits > 0 {
+            Style::default().fg(app.theme.text)
+        } else {
+            dim
+        };
+        let path = truncate_str(path, width.saturating_sub(count.chars().count() + 3));
+        let pad = width.saturating_sub(path.chars().count() + count.chars().count() + 2);
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled(path, path_style),
+            Span::raw(" ".repeat(pad)),
+            Span::styled(count, dim),
+        ]));
+    }
+    lines
+}
+
+/// Re-render messages from index `from` onward after follow mode merged
+/// appended lines, keeping the rendered lines of all earlier messages.
+///
+/// Auto-scrolls to the new bottom if the view was pinned to the bottom.
+fn render_appended_messages(
+    conv: &mut ConversationState,
+    from: usize,
+    width: usize,
+    search: Option<&Regex>,
+    theme: &crate::theme::Theme,
+    syntax_highlighter: &super::syntax::SyntaxHighlighter,
+) {
+    let pinned = conv.scroll_offset >= conv.lines.len().saturating_sub(conv.page_height);
+
+    // With no messages yet, the lines hold only the "no messages" placeholder
+    let keep = if conv.message_starts.is_empty() {
+        0
+    } else {
+        conv.message_starts
+            .get(from)
+            .copied()
+            .unwrap_or(conv.lines.len())
+    };
+    conv.lines.truncate(keep);
+    conv.message_starts.truncate(from);
+
+    conv.folds.shown_folded.retain(|&idx| idx < from);
+    conv.line_sources.truncate(keep);
+    let mut track = RenderTrack {
+        message: from,
+        code_index: conv.messages[..from.min(conv.messages.len())]
+            .iter()
+            .map(|m| super::yank::code_blocks(&m.text).len())
+            .sum(),
+        sources: std::mem::take(&mut conv.line_sources),
+    };
+    for (idx, msg) in conv.messages.iter().enumerate().skip(from) {
+        let start = conv.lines.len();
+        conv.message_starts.push(start);
+        track.message = idx;
+        render_message(msg, width, theme, syntax_highlighter, &mut track, &mut conv.lines);
+        let folded = fold_message(idx, msg, start, &mut conv.folds, search, theme, &mut conv.lines);
+        if let Some(cut) = folded {
+            track.sources.truncate(cut);
+        }
+        track.decoration(conv.lines.len());
+    }
+    conv.line_sources = track.sources;
+
+    conv.match_positions.retain(|&pos| pos < keep);
+    if let Some(re) = search {
+        let appended =
+            highlight_matches(&mut conv.lines[keep..], &conv.line_sources[keep..], re, width, theme);
+        conv.match_positions.extend(appended.into_iter().map(|pos| pos + keep));
+    }
+    if conv.current_match >= conv.match_positions.len() {
+        conv.current_match = 0;
+    }
+
+    if conv.follow && pinned {
+        conv.scroll_offset = conv.lines.len().saturating_sub(conv.page_height);
+    }
 }
 
 /// Render the conversation viewer status bar.
@@ -280,7 +1235,58 @@ fn render_conversation_status(frame: &mut Frame, app: &App, area: Rect) {
             .bg(app.theme.status_label_bg)
             .bold();
 
-        if conv.search_active {
+        if let (Some(diff), None) = (&conv.combined_diff, &app.confirm) {
+            Line::from(vec![
+                Span::styled(" DIFF ", label_style),
+                Span::styled(
+                    format!(" {}  ({} edits) ", diff.path, diff.edits.len()),
+                    Style::default().fg(Color::Green).bold(),
+                ),
+                Span::raw(" "),
+                Span::styled("Space/b scroll  g/G top/bottom  D/Esc back", dim),
+            ])
+        } else if let Some(confirm) = &app.confirm {
+            Line::from(vec![
+                Span::styled(" ? ", label_style),
+                Span::styled(
+                    format!(" {}", confirm.question()),
+                    Style::default().fg(Color::Yellow).bold(),
+                ),
+            ])
+        } else if let Some((msg, _)) = &app.status_message {
+            Line::from(vec![Span::styled(
+                format!(" {msg}"),
+                Style::default().fg(Color::Green).bold(),
+            )])
+        } else if let Some(digits) = &conv.yank {
+            Line::from(vec![
+                Span::styled(" YANK ", label_style),
+                Span::styled(format!(" {digits} "), Style::default().fg(app.theme.text)),
+                Span::raw(" "),
+                Span::styled(
+                    "y message  e exchange as Markdown  1-9 code block  Esc cancel",
+                    dim,
+                ),
+            ])
+        } else if let Some(visual) = &conv.visual {
+            let (first, last) = visual.range();
+            let count = last - first + 1;
+            let noun = if count == 1 { "line" } else { "lines" };
+            Line::from(vec![
+                Span::styled(" VISUAL ", label_style),
+                Span::styled(format!(" {count} {noun} "), Style::default().fg(app.theme.text)),
+                Span::raw(" "),
+                Span::styled("j/k extend  y copy  Esc cancel", dim),
+            ])
+        } else if conv.toc.as_ref().is_some_and(|t| t.focused) {
+            Line::from(vec![
+                Span::styled(" PROMPTS ", label_style),
+                Span::raw(" "),
+                Span::styled("j/k move  Enter jump  Esc conversation  t close", dim),
+            ])
+        } else if let Some(line) = history_search_line(app).filter(|_| conv.search_active) {
+            line
+        } else if conv.search_active {
             // Render search query with cursor position
             let mut spans = vec![
                 Span::styled(" / ", label_style),
@@ -320,7 +1326,27 @@ fn render_conversation_status(frame: &mut Frame, app: &App, area: Rect) {
             }
 
             spans.push(Span::raw("  "));
-            spans.push(Span::styled("Enter confirm  Esc cancel", dim));
+            let options = conv.search_options;
+            for (label, on) in [
+                (".*", options.regex),
+                ("Aa", options.case_sensitive),
+                ("\\b", options.whole_word),
+            ] {
+                let style = if on {
+                    Style::default().fg(Color::Black).bg(app.theme.status_label_bg)
+                } else {
+                    dim
+                };
+                spans.push(Span::styled(format!(" {label} "), style));
+            }
+            spans.push(Span::raw("  "));
+            if conv.search_regex().is_err() {
+                spans.push(Span::styled("invalid regex  ", Style::default().fg(Color::Red)));
+            }
+            spans.push(Span::styled(
+                "Alt-r regex  Alt-c case  Alt-w word  \u{2191}\u{2193}/^R history  Enter confirm  Esc cancel",
+                dim,
+            ));
 
             Line::from(spans)
         } else if conv.search_confirmed && !conv.match_positions.is_empty() {
@@ -351,7 +1377,7 @@ fn render_conversation_status(frame: &mut Frame, app: &App, area: Rect) {
                 ),
                 Span::raw(" "),
                 Span::styled(
-                    "Space/b scroll  g/G top/bottom  / search  Enter copy & exit  Esc back",
+                    "Space/b scroll  [/] prompts  {/} replies  z/Z fold  t prompts  y yank  v select  / search  f follow  c/F commits/files  D diff  Enter copy & exit  Esc back",
                     dim,
                 ),
             ])
@@ -360,6 +1386,17 @@ fn render_conversation_status(frame: &mut Frame, app: &App, area: Rect) {
         Line::from("")
     };
 
+    let mut content = content;
+    if let Some(conv) = &app.conversation {
+        if conv.follow && !conv.search_active && app.confirm.is_none() {
+            let follow_style = Style::default()
+                .fg(app.theme.status_label_fg)
+                .bg(app.theme.updated_marker)
+                .bold();
+            content.spans.insert(0, Span::styled(" FOLLOW ", follow_style));
+        }
+    }
+
     let bar =
         Paragraph::new(content).style(Style::default().bg(app.theme.status_bar_bg));
     frame.render_widget(bar, area);
@@ -367,279 +1404,637 @@ fn render_conversation_status(frame: &mut Frame, app: &App, area: Rect) {
 
 /// Pre-render conversation messages into terminal lines with word wrapping,
 /// syntax highlighting, table rendering, and role header bars.
+///
+/// Also returns the line index at which each message's header starts.
 fn pre_render_conversation(
     messages: &[ConversationMessage],
+    folds: &mut super::Folds,
     width: usize,
-    search_terms: &[&str],
+    search: Option<&Regex>,
     theme: &crate::theme::Theme,
     syntax_highlighter: &super::syntax::SyntaxHighlighter,
-) -> Vec<Line<'static>> {
+) -> RenderedConversation {
     let mut lines: Vec<Line<'static>> = Vec::new();
+    let mut message_starts: Vec<usize> = Vec::with_capacity(messages.len());
     let dim = Style::default().fg(theme.text_dim);
-    let fallback_code_style = Style::default()
-        .fg(Color::Rgb(130, 170, 200))
-        .bg(theme.code_block_bg);
 
     if messages.is_empty() {
         lines.push(Line::from(Span::styled(
             " No messages found in this session.",
             dim,
         )));
-        return lines;
+        return RenderedConversation {
+            lines,
+            message_starts,
+            line_sources: vec![None],
+            match_positions: Vec::new(),
+        };
     }
 
-    for msg in messages {
-        // Full-width role header bar with integrated timestamp
-        let delta = Utc::now().signed_duration_since(msg.timestamp);
-        let time_ago = HumanTime::from(-delta).to_text_en(Accuracy::Rough, Tense::Past);
+    folds.shown_folded.clear();
+    let mut track = RenderTrack::default();
+    for (idx, msg) in messages.iter().enumerate() {
+        let start = lines.len();
+        message_starts.push(start);
+        track.message = idx;
+        render_message(msg, width, theme, syntax_highlighter, &mut track, &mut lines);
+        if let Some(cut) = fold_message(idx, msg, start, folds, search, theme, &mut lines) {
+            track.sources.truncate(cut);
+        }
+        track.decoration(lines.len());
+    }
+    let match_positions = match search {
+        Some(re) => highlight_matches(&mut lines, &track.sources, re, width, theme),
+        None => Vec::new(),
+    };
 
-        let (label, header_bg, header_fg): (&str, Color, Color) = match msg.role {
-            MessageRole::User => (
-                " \u{25B6} You ",
-                theme.user_header_bg,
-                theme.user_header_fg,
-            ),
-            MessageRole::Assistant => (
-                " \u{25C0} Claude ",
-                theme.assistant_header_bg,
-                theme.assistant_header_fg,
-            ),
-        };
+    RenderedConversation {
+        lines,
+        message_starts,
+        line_sources: track.sources,
+        match_positions,
+    }
+}
 
-        let time_str = format!(" {} ", time_ago);
-        let label_len = label.chars().count();
-        let time_len = time_str.chars().count();
-        let pad_len = width.saturating_sub(label_len + time_len);
-        let padding = " ".repeat(pad_len);
+/// Rendered lines of a conversation with the message boundaries, the
+/// source of each line, and the lines where search matches start.
+struct RenderedConversation {
+    lines: Vec<Line<'static>>,
+    message_starts: Vec<usize>,
+    line_sources: Vec<Option<SourceLines>>,
+    match_positions: Vec<usize>,
+}
 
-        let header_style = Style::default().fg(header_fg).bg(header_bg).bold();
-        let header_dim = Style::default().fg(header_fg).bg(header_bg);
-        lines.push(Line::from(vec![
-            Span::styled(label, header_style),
-            Span::styled(padding, Style::default().bg(header_bg)),
-            Span::styled(time_str, header_dim),
-        ]));
+/// Cut the body of message `idx`, rendered onto `lines` from `start`, to
+/// what `folds` allows, ending it with a marker counting the hidden lines.
+/// Returns the number of lines kept before the marker if the body was cut.
+fn fold_message(
+    idx: usize,
+    msg: &ConversationMessage,
+    start: usize,
+    folds: &mut super::Folds,
+    search: Option<&Regex>,
+    theme: &crate::theme::Theme,
+    lines: &mut Vec<Line<'static>>,
+) -> Option<usize> {
+    // Header and trailing blank line are not part of the body
+    let body_start = start + 1;
+    let body_len = lines.len().saturating_sub(body_start + 1);
+    let has_match = search.is_some_and(|re| re.is_match(&msg.text));
+    let limit = folds.body_limit(idx, body_len, has_match)?;
+    folds.shown_folded.insert(idx);
+    let hidden = body_len - limit.min(body_len);
+    let cut = body_start + limit.min(body_len);
+    lines.truncate(cut);
+    let noun = if hidden == 1 { "line" } else { "lines" };
+    lines.push(Line::styled(
+        format!(" \u{2026} {hidden} more {noun}"),
+        Style::default().fg(theme.text_dim).italic(),
+    ));
+    if limit > 0 {
+        lines.push(Line::from(""));
+    }
+    Some(cut)
+}
 
-        // Determine message body background
-        let msg_bg = match msg.role {
-            MessageRole::User => Some(theme.user_message_bg),
-            MessageRole::Assistant => None,
-        };
+/// Running state while rendering messages: code block numbering and the
+/// source of each rendered line.
+#[derive(Default)]
+struct RenderTrack {
+    /// Index of the message being rendered.
+    message: usize,
+    /// Code blocks numbered so far.
+    code_index: usize,
+    /// Source of each line rendered so far.
+    sources: Vec<Option<SourceLines>>,
+}
 
-        let base_style = if let Some(bg) = msg_bg {
-            Style::default().fg(theme.text).bg(bg)
-        } else {
-            Style::default().fg(theme.text)
+impl RenderTrack {
+    /// Attribute the lines rendered since the last call, up to `len`, to
+    /// lines `start..end` of the current message's text.
+    fn map_to(&mut self, len: usize, start: usize, end: usize) {
+        let source = SourceLines {
+            message: self.message,
+            start,
+            end,
         };
-        let heading_style = Style::default().fg(theme.heading).bold();
-
-        // Collect message lines for table detection
-        let text_lines: Vec<&str> = msg.text.lines().collect();
-        let mut i = 0;
-        let mut in_code_fence = false;
-        let mut code_lang: Option<String> = None;
-        let mut code_buffer: Vec<String> = Vec::new();
-
-        while i < text_lines.len() {
-            let text_line = text_lines[i];
-            let trimmed = text_line.trim();
-
-            // Code fence handling
-            if trimmed.starts_with("```") {
-                if in_code_fence {
-                    // Closing fence: render buffered code
-                    let code_refs: Vec<&str> =
-                        code_buffer.iter().map(|s| s.as_str()).collect();
-                    let highlighted = code_lang.as_ref().and_then(|lang| {
-                        syntax_highlighter.highlight_code(
-                            &code_refs,
-                            lang,
-                            theme.syntect_theme,
-                            theme.code_block_bg,
-                            width,
-                        )
-                    });
-
-                    if let Some(hl_lines) = highlighted {
-                        lines.extend(hl_lines);
-                    } else {
-                        // Fallback: single-color code, padded to full width
-                        for cl in &code_buffer {
-                            let wrapped = wrap_line(cl, width);
-                            for wl in wrapped {
-                                let char_len = wl.chars().count();
-                                let pad = " ".repeat(width.saturating_sub(char_len));
-                                lines.push(Line::from(vec![Span::styled(
-                                    format!("{}{}", wl, pad),
-                                    fallback_code_style,
-                                )]));
-                            }
-                        }
-                    }
+        if len > self.sources.len() {
+            self.sources.resize(len, Some(source));
+        }
+    }
+
+    /// Mark the lines rendered since the last call, up to `len`, as
+    /// decoration without source text.
+    fn decoration(&mut self, len: usize) {
+        if len > self.sources.len() {
+            self.sources.resize(len, None);
+        }
+    }
+}
+
+/// Label line above code block `n`, showing the number `y<n>` copies it by.
+fn code_block_label(
+    n: usize,
+    language: Option<&str>,
+    width: usize,
+    theme: &crate::theme::Theme,
+) -> Line<'static> {
+    let label = match language {
+        Some(lang) => format!(" [{n}] {lang}"),
+        None => format!(" [{n}]"),
+    };
+    let pad = width.saturating_sub(label.chars().count());
+    Line::styled(
+        format!("{label}{}", " ".repeat(pad)),
+        Style::default().fg(theme.text_dim).bg(theme.code_block_bg),
+    )
+}
+
+/// Render a single message (role header, body, trailing blank line) onto `lines`.
+fn render_message(
+    msg: &ConversationMessage,
+    width: usize,
+    theme: &crate::theme::Theme,
+    syntax_highlighter: &super::syntax::SyntaxHighlighter,
+    track: &mut RenderTrack,
+    lines: &mut Vec<Line<'static>>,
+) {
+    let fallback_code_style = Style::default()
+        .fg(Color::Rgb(130, 170, 200))
+        .bg(theme.code_block_bg);
+
+    // Full-width role header bar with integrated timestamp
+    let delta = Utc::now().signed_duration_since(msg.timestamp);
+    let time_ago = HumanTime::from(-delta).to_text_en(Accuracy::Rough, Tense::Past);
+
+    let (label, header_bg, header_fg): (&str, Color, Color) = match msg.role {
+        MessageRole::User => (
+            " \u{25B6} You ",
+            theme.user_header_bg,
+            theme.user_header_fg,
+        ),
+        MessageRole::Assistant => (
+            " \u{25C0} Claude ",
+            theme.assistant_header_bg,
+            theme.assistant_header_fg,
+        ),
+    };
+
+    let time_str = format!(" {} ", time_ago);
+    let label_len = label.chars().count();
+    let time_len = time_str.chars().count();
+    let pad_len = width.saturating_sub(label_len + time_len);
+    let padding = " ".repeat(pad_len);
+
+    let header_style = Style::default().fg(header_fg).bg(header_bg).bold();
+    let header_dim = Style::default().fg(header_fg).bg(header_bg);
+    lines.push(Line::from(vec![
+        Span::styled(label, header_style),
+        Span::styled(padding, Style::default().bg(header_bg)),
+        Span::styled(time_str, header_dim),
+    ]));
+
+    // Determine message body background
+    let msg_bg = match msg.role {
+        MessageRole::User => Some(theme.user_message_bg),
+        MessageRole::Assistant => None,
+    };
+
+    let base_style = if let Some(bg) = msg_bg {
+        Style::default().fg(theme.text).bg(bg)
+    } else {
+        Style::default().fg(theme.text)
+    };
+    let heading_style = Style::default().fg(theme.heading).bold();
+
+    track.decoration(lines.len());
 
+    // Collect message lines for table detection
+    let text_lines: Vec<&str> = msg.text.lines().collect();
+    let mut i = 0;
+    // Source line at which the current iteration started
+    let mut from = 0;
+    let mut in_code_fence = false;
+    let mut code_lang: Option<String> = None;
+    let mut code_path: Option<String> = None;
+    let mut code_buffer: Vec<String> = Vec::new();
+
+    while i < text_lines.len() {
+        track.map_to(lines.len(), from, i);
+        from = i;
+        let text_line = text_lines[i];
+        let trimmed = text_line.trim();
+
+        // Code fence handling
+        let in_diff = in_code_fence && code_lang.as_deref() == Some(DIFF_LANGUAGE);
+        if is_fence(text_line, in_diff) {
+            if in_code_fence {
+                // Closing fence: render buffered code
+                let code_refs: Vec<&str> =
+                    code_buffer.iter().map(|s| s.as_str()).collect();
+                let code_start = i - code_buffer.len();
+                if code_lang.as_deref() == Some(DIFF_LANGUAGE) {
+                    if code_path.is_some() {
+                        // Path header
+                        track.decoration(lines.len() + 1);
+                    }
+                    render_diff_block(
+                        &code_refs,
+                        code_path.as_deref(),
+                        width,
+                        theme,
+                        syntax_highlighter,
+                        lines,
+                    );
+                    // One rendered line per diff line
+                    for k in 0..code_buffer.len() {
+                        let src = code_start + k;
+                        track.map_to(track.sources.len() + 1, src, src + 1);
+                    }
                     code_buffer.clear();
                     code_lang = None;
+                    code_path = None;
                     in_code_fence = false;
+                    i += 1;
+                    continue;
+                }
+                track.code_index += 1;
+                lines.push(code_block_label(track.code_index, code_lang.as_deref(), width, theme));
+                track.decoration(lines.len());
+                let highlighted = code_lang.as_ref().and_then(|lang| {
+                    syntax_highlighter.highlight_code(
+                        &code_refs,
+                        lang,
+                        theme.syntect_theme,
+                        theme.code_block_bg,
+                        width,
+                    )
+                });
+
+                if let Some(hl_lines) = highlighted {
+                    // One rendered line per code line
+                    for (k, line) in hl_lines.into_iter().enumerate() {
+                        lines.push(line);
+                        track.map_to(lines.len(), code_start + k, code_start + k + 1);
+                    }
                 } else {
-                    // Opening fence: extract language
-                    in_code_fence = true;
-                    code_lang =
-                        super::syntax::extract_language(trimmed);
+                    // Fallback: single-color code, padded to full width
+                    for (k, cl) in code_buffer.iter().enumerate() {
+                        let wrapped = wrap_line(cl, width);
+                        for wl in wrapped {
+                            let char_len = wl.chars().count();
+                            let pad = " ".repeat(width.saturating_sub(char_len));
+                            lines.push(Line::from(vec![Span::styled(
+                                format!("{}{}", wl, pad),
+                                fallback_code_style,
+                            )]));
+                        }
+                        track.map_to(lines.len(), code_start + k, code_start + k + 1);
+                    }
                 }
-                i += 1;
-                continue;
-            }
 
-            if in_code_fence {
-                code_buffer.push(text_line.to_string());
-                i += 1;
-                continue;
+                code_buffer.clear();
+                code_lang = None;
+                in_code_fence = false;
+            } else {
+                // Opening fence: extract language
+                in_code_fence = true;
+                code_lang =
+                    super::syntax::extract_language(trimmed);
+                code_path = diff_fence_path(trimmed).map(str::to_string);
             }
+            i += 1;
+            continue;
+        }
 
-            // Table detection: collect consecutive pipe lines
-            if table::is_table_line(trimmed) {
-                let mut table_lines: Vec<&str> = vec![text_line];
-                let mut j = i + 1;
-                while j < text_lines.len() && table::is_table_line(text_lines[j].trim()) {
-                    table_lines.push(text_lines[j]);
-                    j += 1;
-                }
+        if in_code_fence {
+            code_buffer.push(text_line.to_string());
+            i += 1;
+            continue;
+        }
 
-                if table_lines.len() >= 2 {
-                    let table_refs: Vec<&str> = table_lines.to_vec();
-                    if let Some(rendered) =
-                        table::render_table_lines(&table_refs, width, theme)
-                    {
-                        // Apply message background to table lines if user message
-                        for tl in rendered {
-                            if let Some(bg) = msg_bg {
-                                lines.push(tl.patch_style(Style::default().bg(bg)));
-                            } else {
-                                lines.push(tl);
-                            }
+        // Table detection: collect consecutive pipe lines
+        if table::is_table_line(trimmed) {
+            let mut table_lines: Vec<&str> = vec![text_line];
+            let mut j = i + 1;
+            while j < text_lines.len() && table::is_table_line(text_lines[j].trim()) {
+                table_lines.push(text_lines[j]);
+                j += 1;
+            }
+
+            if table_lines.len() >= 2 {
+                let table_refs: Vec<&str> = table_lines.to_vec();
+                if let Some(rendered) =
+                    table::render_table_lines(&table_refs, width, theme)
+                {
+                    // Apply message background to table lines if user message
+                    for tl in rendered {
+                        if let Some(bg) = msg_bg {
+                            lines.push(tl.patch_style(Style::default().bg(bg)));
+                        } else {
+                            lines.push(tl);
                         }
-                        i = j;
-                        continue;
                     }
+                    i = j;
+                    continue;
                 }
-                // Fall through to normal rendering if table parsing failed
-            }
-
-            // Markdown heading
-            if trimmed.starts_with('#') {
-                let level = trimmed.chars().take_while(|&c| c == '#').count();
-                let heading_text = trimmed[level..].trim_start();
-                let prefix = "\u{2500}".repeat(level.min(3));
-                let wrapped =
-                    wrap_line(heading_text, width.saturating_sub(prefix.len() + 1));
-                let heading_with_bg = heading_style.bg(theme.heading_bg);
-                for (idx, wl) in wrapped.into_iter().enumerate() {
-                    let mut spans = Vec::new();
-                    if idx == 0 {
-                        spans.push(Span::styled(
-                            format!("{} ", prefix),
-                            Style::default().fg(theme.text_dim).bg(theme.heading_bg),
-                        ));
-                    }
-                    spans.extend(render_markdown_inline(
-                        &wl,
-                        heading_with_bg,
-                        search_terms,
-                        theme,
+            }
+            // Fall through to normal rendering if table parsing failed
+        }
+
+        // Markdown heading
+        if trimmed.starts_with('#') {
+            let level = trimmed.chars().take_while(|&c| c == '#').count();
+            let heading_text = trimmed[level..].trim_start();
+            let prefix = "\u{2500}".repeat(level.min(3));
+            let wrapped =
+                wrap_line(heading_text, width.saturating_sub(prefix.len() + 1));
+            let heading_with_bg = heading_style.bg(theme.heading_bg);
+            for (idx, wl) in wrapped.into_iter().enumerate() {
+                let mut spans = Vec::new();
+                if idx == 0 {
+                    spans.push(Span::styled(
+                        format!("{} ", prefix),
+                        Style::default().fg(theme.text_dim).bg(theme.heading_bg),
                     ));
-                    // Pad to full width for consistent background
-                    let char_count: usize = spans.iter().map(|s| s.content.chars().count()).sum();
-                    if char_count < width {
-                        spans.push(Span::styled(
-                            " ".repeat(width - char_count),
-                            Style::default().bg(theme.heading_bg),
-                        ));
-                    }
-                    lines.push(Line::from(spans));
                 }
-                i += 1;
-                continue;
+                spans.extend(render_markdown_inline(&wl, heading_with_bg, theme));
+                // Pad to full width for consistent background
+                let char_count: usize = spans.iter().map(|s| s.content.chars().count()).sum();
+                if char_count < width {
+                    spans.push(Span::styled(
+                        " ".repeat(width - char_count),
+                        Style::default().bg(theme.heading_bg),
+                    ));
+                }
+                lines.push(Line::from(spans));
             }
+            i += 1;
+            continue;
+        }
 
-            // Empty line
-            if trimmed.is_empty() {
-                if let Some(bg) = msg_bg {
-                    let pad = " ".repeat(width);
-                    lines.push(Line::from(Span::styled(
-                        pad,
-                        Style::default().bg(bg),
-                    )));
-                } else {
-                    lines.push(Line::from(""));
-                }
-                i += 1;
-                continue;
+        // Empty line
+        if trimmed.is_empty() {
+            if let Some(bg) = msg_bg {
+                let pad = " ".repeat(width);
+                lines.push(Line::from(Span::styled(
+                    pad,
+                    Style::default().bg(bg),
+                )));
+            } else {
+                lines.push(Line::from(""));
             }
+            i += 1;
+            continue;
+        }
 
-            // Normal text with markdown inline rendering
-            let wrapped = wrap_line(text_line, width);
-            for wl in wrapped {
-                let spans =
-                    render_markdown_inline(&wl, base_style, search_terms, theme);
-                let line = Line::from(spans);
-                if let Some(bg) = msg_bg {
-                    if !has_bg_set(&line) {
-                        lines.push(
-                            line.patch_style(Style::default().bg(bg)),
-                        );
-                    } else {
-                        lines.push(line);
-                    }
+        // Normal text with markdown inline rendering
+        let wrapped = wrap_line(text_line, width);
+        for wl in wrapped {
+            let spans = render_markdown_inline(&wl, base_style, theme);
+            let line = Line::from(spans);
+            if let Some(bg) = msg_bg {
+                if !has_bg_set(&line) {
+                    lines.push(
+                        line.patch_style(Style::default().bg(bg)),
+                    );
                 } else {
                     lines.push(line);
                 }
+            } else {
+                lines.push(line);
             }
-            i += 1;
         }
+        i += 1;
+    }
 
-        // Handle unclosed code fence
-        if in_code_fence {
-            for cl in &code_buffer {
-                let wrapped = wrap_line(cl, width);
-                for wl in wrapped {
-                    lines.push(Line::from(vec![Span::styled(wl, fallback_code_style)]));
-                }
+    track.map_to(lines.len(), from, i);
+
+    // Handle unclosed code fence
+    if in_code_fence {
+        let code_start = text_lines.len() - code_buffer.len();
+        for (k, cl) in code_buffer.iter().enumerate() {
+            let wrapped = wrap_line(cl, width);
+            for wl in wrapped {
+                lines.push(Line::from(vec![Span::styled(wl, fallback_code_style)]));
             }
+            track.map_to(lines.len(), code_start + k, code_start + k + 1);
         }
-
-        // Blank line after message
-        lines.push(Line::from(""));
     }
 
-    lines
+    // Blank line after message
+    lines.push(Line::from(""));
+    track.decoration(lines.len());
 }
 
 /// Check if any span in a line already has a background set.
+/// Render the combined diff of all edits to one file, one section per edit.
+fn render_combined_diff(
+    diff: &super::CombinedDiff,
+    width: usize,
+    theme: &crate::theme::Theme,
+    syntax_highlighter: &super::syntax::SyntaxHighlighter,
+) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let total = diff.edits.len();
+    for (i, (timestamp, edit_lines)) in diff.edits.iter().enumerate() {
+        if i > 0 {
+            lines.push(Line::from(""));
+        }
+        let local = timestamp.with_timezone(&chrono::Local);
+        let header = format!(" Edit {} of {total} \u{00B7} {}", i + 1, local.format("%Y-%m-%d %H:%M"));
+        let pad = width.saturating_sub(header.chars().count());
+        lines.push(Line::from(vec![
+            Span::styled(header, Style::default().fg(theme.assistant_header_fg).bg(theme.assistant_header_bg).bold()),
+            Span::styled(" ".repeat(pad), Style::default().bg(theme.assistant_header_bg)),
+        ]));
+        let refs: Vec<&str> = edit_lines.iter().map(String::as_str).collect();
+        render_diff_block(&refs, Some(&diff.path), width, theme, syntax_highlighter, &mut lines);
+    }
+    lines
+}
+
+/// Render a diff block: a file path header, then added and removed lines
+/// on tinted backgrounds, syntax-highlighted by the file's extension.
+fn render_diff_block(
+    diff_lines: &[&str],
+    path: Option<&str>,
+    width: usize,
+    theme: &crate::theme::Theme,
+    syntax_highlighter: &super::syntax::SyntaxHighlighter,
+    lines: &mut Vec<Line<'static>>,
+) {
+    if let Some(path) = path {
+        let header = format!(" \u{270E} {path}");
+        let pad = width.saturating_sub(header.chars().count());
+        lines.push(Line::from(vec![
+            Span::styled(header, Style::default().fg(theme.heading).bg(theme.code_block_bg).bold()),
+            Span::styled(" ".repeat(pad), Style::default().bg(theme.code_block_bg)),
+        ]));
+    }
+
+    let classified: Vec<(DiffLineKind, &str)> =
+        diff_lines.iter().map(|l| classify_diff_line(l)).collect();
+    let line_bg = |kind: DiffLineKind| match kind {
+        DiffLineKind::Added => theme.diff_added_bg,
+        DiffLineKind::Removed => theme.diff_removed_bg,
+        DiffLineKind::Context | DiffLineKind::Meta => theme.code_block_bg,
+    };
+    let code: Vec<(&str, Color)> = classified
+        .iter()
+        .filter(|(kind, _)| *kind != DiffLineKind::Meta)
+        .map(|(kind, content)| (*content, line_bg(*kind)))
+        .collect();
+    let code_width = width.saturating_sub(1);
+    let mut highlighted = path
+        .and_then(super::syntax::language_for_path)
+        .and_then(|lang| {
+            syntax_highlighter.highlight_lines(&code, &lang, theme.syntect_theme, code_width)
+        })
+        .map(Vec::into_iter);
+
+    for (kind, content) in classified {
+        let bg = line_bg(kind);
+        let (sign, sign_fg) = match kind {
+            DiffLineKind::Added => ("+", theme.diff_added_fg),
+            DiffLineKind::Removed => ("-", theme.diff_removed_fg),
+            DiffLineKind::Context => (" ", theme.text_dim),
+            DiffLineKind::Meta => {
+                let text = if content.starts_with(HUNK_SEPARATOR) { "\u{22EF}" } else { content };
+                let pad = width.saturating_sub(text.chars().count());
+                lines.push(Line::from(vec![Span::styled(
+                    format!("{text}{}", " ".repeat(pad)),
+                    Style::default().fg(theme.text_dim).bg(bg),
+                )]));
+                continue;
+            }
+        };
+        let mut spans = vec![Span::styled(sign, Style::default().fg(sign_fg).bg(bg).bold())];
+        match highlighted.as_mut().and_then(Iterator::next) {
+            Some(line) => spans.extend(line.spans),
+            None => {
+                let fg = match kind {
+                    DiffLineKind::Added => theme.diff_added_fg,
+                    DiffLineKind::Removed => theme.diff_removed_fg,
+                    _ => theme.text,
+                };
+                let pad = code_width.saturating_sub(content.chars().count());
+                spans.push(Span::styled(
+                    format!("{content}{}", " ".repeat(pad)),
+                    Style::default().fg(fg).bg(bg),
+                ));
+            }
+        }
+        lines.push(Line::from(spans));
+    }
+}
+
 fn has_bg_set(line: &Line) -> bool {
     line.spans
         .iter()
         .any(|s| s.style.bg.is_some())
 }
 
-/// Find line indices that contain search term matches.
-fn find_match_positions(lines: &[Line<'static>], terms: &[&str]) -> Vec<usize> {
-    if terms.is_empty() {
-        return Vec::new();
-    }
+/// Highlight the matches of `re` on `lines` and return the line on which
+/// each match starts. Lines wrapped from the same source line are searched
+/// as one text, so matches across wrapped line breaks are found.
+fn highlight_matches(
+    lines: &mut [Line<'static>],
+    sources: &[Option<SourceLines>],
+    re: &Regex,
+    width: usize,
+    theme: &crate::theme::Theme,
+) -> Vec<usize> {
     let mut positions = Vec::new();
-    for (i, line) in lines.iter().enumerate() {
-        let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
-        let text_lower = text.to_lowercase();
-        for term in terms {
-            if !term.is_empty() && text_lower.contains(&term.to_lowercase()) {
-                positions.push(i);
-                break;
+    let mut first = 0;
+    while first < lines.len() {
+        let source = sources.get(first).copied().flatten();
+        let mut end = first + 1;
+        while end < lines.len() && source.is_some() && sources[end] == source {
+            end += 1;
+        }
+
+        // Text of the group, and the line, span, offset, and searched length of each span
+        let mut text = String::new();
+        let mut pieces: Vec<(usize, usize, usize, usize)> = Vec::new();
+        for (l, line) in lines.iter().enumerate().take(end).skip(first) {
+            let line_text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+            let kept = line_text.trim_end();
+            let mut offset = 0;
+            for (s, span) in line.spans.iter().enumerate() {
+                let len = span.content.len().min(kept.len().saturating_sub(offset));
+                pieces.push((l, s, text.len(), len));
+                text.push_str(&span.content[..len]);
+                offset += span.content.len();
+            }
+            // Wrapping at a space dropped it; long words were split as is
+            if kept.chars().count() < width {
+                text.push(' ');
+            }
+        }
+
+        let marks: Vec<(usize, usize)> = re
+            .find_iter(&text)
+            .filter(|m| !m.is_empty())
+            .map(|m| (m.start(), m.end()))
+            .collect();
+        for &(start, _) in &marks {
+            if let Some(&(line, ..)) = pieces.iter().find(|p| start < p.2 + p.3) {
+                if positions.last() != Some(&line) {
+                    positions.push(line);
+                }
             }
         }
+        if !marks.is_empty() {
+            for (l, line) in lines.iter_mut().enumerate().take(end).skip(first) {
+                let spans = std::mem::take(&mut line.spans);
+                let mut highlighted = Vec::with_capacity(spans.len());
+                for (span, &(_, _, offset, len)) in
+                    spans.into_iter().zip(pieces.iter().filter(|p| p.0 == l))
+                {
+                    split_marked(span, offset, len, &marks, theme, &mut highlighted);
+                }
+                line.spans = highlighted;
+            }
+        }
+        first = end;
     }
     positions
 }
 
+/// Push `span` onto `out`, split so the parts of its first `len` bytes
+/// covered by `marks` are highlighted. The span starts at `offset` in the
+/// searched text the marks refer to.
+fn split_marked(
+    span: Span<'static>,
+    offset: usize,
+    len: usize,
+    marks: &[(usize, usize)],
+    theme: &crate::theme::Theme,
+    out: &mut Vec<Span<'static>>,
+) {
+    let highlight = span.style.bg(theme.search_highlight_bg);
+    let content = span.content.as_ref();
+    let mut pos = 0;
+    for &(start, end) in marks {
+        let from = start.saturating_sub(offset).max(pos);
+        let to = end.saturating_sub(offset).min(len);
+        if from >= to {
+            continue;
+        }
+        if from > pos {
+            out.push(Span::styled(content[pos..from].to_string(), span.style));
+        }
+        out.push(Span::styled(content[from..to].to_string(), highlight));
+        pos = to;
+    }
+    if pos == 0 {
+        out.push(span);
+    } else if pos < content.len() {
+        out.push(Span::styled(content[pos..].to_string(), span.style));
+    }
+}
+
 /// Word-wrap a single line to fit within `width` characters.
 fn wrap_line(line: &str, width: usize) -> Vec<String> {
     if width == 0 {
@@ -690,12 +2085,54 @@ fn wrap_line(line: &str, width: usize) -> Vec<String> {
 }
 
 /// Render the status/help bar at the bottom.
+/// Current sort of the list, e.g. "↓ created", with an up arrow when reversed.
+fn sort_indicator(app: &App) -> String {
+    let arrow = if app.sort_reverse { '\u{2191}' } else { '\u{2193}' };
+    // The order still changes while counts are being scanned
+    let loading = if app.sort.needs_stats() && app.stats_loader.is_loading() { "\u{2026}" } else { "" };
+    format!("{arrow} {}{loading}", app.sort.label())
+}
+
 fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     let dim = Style::default().fg(app.theme.text_dim);
     let content = match app.mode {
-        Mode::Conversation | Mode::ConversationSearch => Line::from(""),
+        Mode::Conversation | Mode::ConversationSearch | Mode::Compare => Line::from(""),
         Mode::Browsing => {
-            if let Some((msg, _)) = &app.status_message {
+            let label_style = Style::default()
+                .fg(app.theme.status_label_fg)
+                .bg(app.theme.status_label_bg)
+                .bold();
+            if let Some(line) = history_search_line(app) {
+                line
+            } else if let Some(name) = app.view_menu.as_ref().and_then(|m| m.naming.as_ref()) {
+                Line::from(vec![
+                    Span::styled(" SAVE VIEW ", label_style),
+                    Span::styled(format!(" {name}\u{2502} "), Style::default().fg(app.theme.text)),
+                    Span::raw(" "),
+                    Span::styled("Enter save  Esc cancel", dim),
+                ])
+            } else if app.relocate.is_some() {
+                Line::from(vec![
+                    Span::styled(" RELOCATE ", label_style),
+                    Span::raw(" "),
+                    Span::styled("Enter remap the project here and save the rule  Esc cancel", dim),
+                ])
+            } else if app.sort_menu.is_some() {
+                Line::from(vec![
+                    Span::styled(" SORT ", label_style),
+                    Span::raw(" "),
+                    Span::styled("Enter sort  r reverse  Esc close", dim),
+                ])
+            } else if app.view_menu.is_some() {
+                Line::from(vec![
+                    Span::styled(" VIEWS ", label_style),
+                    Span::raw(" "),
+                    Span::styled(
+                        "Enter show  s save current  d delete  c clear  Esc close",
+                        dim,
+                    ),
+                ])
+            } else if let Some((msg, _)) = &app.status_message {
                 Line::from(vec![Span::styled(
                     format!(" {msg}"),
                     Style::default().fg(Color::Green).bold(),
@@ -718,11 +2155,6 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
                     }
                 };
 
-                let label_style = Style::default()
-                    .fg(app.theme.status_label_fg)
-                    .bg(app.theme.status_label_bg)
-                    .bold();
-
                 Line::from(vec![
                     Span::styled(" / ", label_style),
                     Span::styled(
@@ -731,13 +2163,42 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
                     ),
                     Span::styled(match_info, dim),
                     Span::raw("  "),
-                    Span::styled("Esc clear  Enter select", dim),
+                    Span::styled(sort_indicator(app), dim),
+                    Span::raw("  "),
+                    Span::styled("Esc clear  Enter select  \u{2191} recall  ^R history", dim),
                 ])
             } else {
+                let mark_hint = match app.marked.len() {
+                    0 => "^T mark".to_string(),
+                    n => format!("{n} marked  ^O compare"),
+                };
+                let layout_hint = match app.layout {
+                    ListLayout::Flat => "Tab tree",
+                    ListLayout::Tree => "Tab list  \u{2190}\u{2192} fold  ^B branches",
+                };
+                let preview_hint = if app.show_preview {
+                    "^P hide preview"
+                } else {
+                    "^P preview"
+                };
                 Line::from(vec![
-                    Span::styled(" Enter ", dim),
+                    Span::styled(format!(" {}", sort_indicator(app)), dim),
+                    Span::raw("  "),
+                    Span::styled("Enter ", dim),
                     Span::styled("detail", dim),
                     Span::raw("  "),
+                    Span::styled(layout_hint, dim),
+                    Span::raw("  "),
+                    Span::styled(preview_hint, dim),
+                    Span::raw("  "),
+                    Span::styled(mark_hint, dim),
+                    Span::raw("  "),
+                    Span::styled("^V views", dim),
+                    Span::raw("  "),
+                    Span::styled("^W time", dim),
+                    Span::raw("  "),
+                    Span::styled("^S sort  ^X reverse  ^L relocate", dim),
+                    Span::raw("  "),
                     Span::styled("Esc ", dim),
                     Span::styled("quit", dim),
                     Span::raw("  "),
@@ -752,6 +2213,31 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(bar, area);
 }
 
+/// Status line of a Ctrl-R history search, if one is active.
+fn history_search_line(app: &App) -> Option<Line<'static>> {
+    let search = app.history_search.as_ref()?;
+    let label_style = Style::default()
+        .fg(app.theme.status_label_fg)
+        .bg(app.theme.status_label_bg)
+        .bold();
+    let dim = Style::default().fg(app.theme.text_dim);
+    let found = search
+        .found
+        .and_then(|i| app.active_history().entries().get(i))
+        .map(|entry| Span::styled(entry.clone(), Style::default().fg(app.theme.text)))
+        .unwrap_or_else(|| Span::styled("no match", dim));
+    Some(Line::from(vec![
+        Span::styled(" HISTORY ", label_style),
+        Span::styled(
+            format!(" {}\u{2502} ", search.query),
+            Style::default().fg(app.theme.status_label_bg),
+        ),
+        found,
+        Span::raw("  "),
+        Span::styled("^R older  Enter use  Esc cancel", dim),
+    ]))
+}
+
 /// Truncate a string to `max_len` characters, adding "..." if truncated.
 fn truncate_str(s: &str, max_len: usize) -> String {
     if max_len <= 3 {
@@ -765,12 +2251,10 @@ fn truncate_str(s: &str, max_len: usize) -> String {
     }
 }
 
-/// Render inline markdown (bold, italic, inline code) as styled spans,
-/// then apply search term highlighting on top.
+/// Render inline markdown (bold, italic, inline code) as styled spans.
 fn render_markdown_inline<'a>(
     text: &str,
     base_style: Style,
-    search_terms: &[&str],
     theme: &crate::theme::Theme,
 ) -> Vec<Span<'a>> {
     let bold_style = base_style.bold();
@@ -851,23 +2335,11 @@ fn render_markdown_inline<'a>(
     let link_style = base_style
         .fg(theme.link)
         .add_modifier(Modifier::UNDERLINED);
-    let processed: Vec<(String, Style)> = spans
+    spans
         .into_iter()
         .flat_map(|(text, style)| split_urls(&text, style, link_style))
-        .collect();
-
-    if search_terms.is_empty() {
-        return processed
-            .into_iter()
-            .map(|(text, style)| Span::styled(text, style))
-            .collect();
-    }
-
-    let mut result: Vec<Span<'a>> = Vec::new();
-    for (segment, style) in processed {
-        result.extend(highlight_terms(&segment, search_terms, style, theme));
-    }
-    result
+        .map(|(text, style)| Span::styled(text, style))
+        .collect()
 }
 
 /// Split a text segment into URL and non-URL parts.
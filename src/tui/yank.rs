@@ -0,0 +1,64 @@
+use crate::diff::{is_fence, DIFF_LANGUAGE};
+use crate::session::{ConversationMessage, MessageRole, Session};
+
+use super::syntax::extract_language;
+
+/// A fenced code block of a message, without its fences.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    pub language: Option<String>,
+    pub code: String,
+}
+
+/// Numbered code blocks of a message text, in order. Diff blocks of edit
+/// tool calls and unclosed fences are not numbered.
+pub fn code_blocks(text: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(Option<String>, Vec<&str>)> = None;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        let in_diff = current
+            .as_ref()
+            .is_some_and(|(language, _)| language.as_deref() == Some(DIFF_LANGUAGE));
+        if !is_fence(line, in_diff) {
+            if let Some((_, code)) = &mut current {
+                code.push(line);
+            }
+            continue;
+        }
+        match current.take() {
+            Some((language, code)) => {
+                if language.as_deref() != Some(DIFF_LANGUAGE) {
+                    blocks.push(CodeBlock {
+                        language,
+                        code: code.join("\n"),
+                    });
+                }
+            }
+            None => current = Some((extract_language(trimmed), Vec::new())),
+        }
+    }
+    blocks
+}
+
+/// A prompt and its reply as Markdown, attributed to the session.
+pub fn exchange_markdown(session: &Session, exchange: &[&ConversationMessage]) -> String {
+    let mut out = String::new();
+    for msg in exchange {
+        let who = match msg.role {
+            MessageRole::User => "You",
+            MessageRole::Assistant => "Claude",
+        };
+        let local = msg.timestamp.with_timezone(&chrono::Local);
+        out.push_str(&format!(
+            "**{who}** ({}):\n\n{}\n\n",
+            local.format("%Y-%m-%d %H:%M"),
+            msg.text.trim()
+        ));
+    }
+    out.push_str(&format!(
+        "_From Claude Code session `{}` in `{}`_\n",
+        session.id, session.project_path
+    ));
+    out
+}
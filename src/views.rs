@@ -0,0 +1,191 @@
+// Named views: saved list filters with a project, branch, and time scope,
+// and optionally a sort order
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::config_dir;
+use crate::session::Session;
+use crate::sort::SortKey;
+use crate::timespec::parse_time;
+
+/// Limits on which sessions the list shows, on top of the filter text.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scope {
+    /// Only sessions whose project name or path contains this.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Only sessions on a git branch containing this.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Only sessions from this time on (e.g. `2w`, `today`, `2025-03-01`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// Only sessions up to this time, including the whole day for dates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+    /// Only sessions before this time, excluding the day for dates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+}
+
+impl Scope {
+    /// Check that the time window parses.
+    pub fn validate(&self) -> Result<(), String> {
+        let now = Local::now();
+        for (flag, value) in [("since", &self.since), ("until", &self.until), ("before", &self.before)] {
+            if let Some(value) = value {
+                parse_time(value, now).map_err(|e| format!("invalid {flag} value: {e}"))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the scope limits the list at all.
+    pub fn is_empty(&self) -> bool {
+        *self == Scope::default()
+    }
+
+    /// The scope with its time bounds resolved against `now`, for checking
+    /// many sessions. Bounds that do not parse are ignored.
+    pub fn resolve(&self, now: DateTime<Local>) -> ScopeFilter {
+        let bound = |value: &Option<String>| value.as_deref().and_then(|v| parse_time(v, now).ok());
+        ScopeFilter {
+            project: self.project.as_deref().map(str::to_lowercase),
+            branch: self.branch.as_deref().map(str::to_lowercase),
+            since: bound(&self.since).map(|t| t.start(now)),
+            until: bound(&self.until).map(|t| t.end(now)),
+            before: bound(&self.before).map(|t| t.start(now)),
+        }
+    }
+
+    /// Short description, e.g. "project api · branch release · 2w".
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(project) = &self.project {
+            parts.push(format!("project {project}"));
+        }
+        if let Some(branch) = &self.branch {
+            parts.push(format!("branch {branch}"));
+        }
+        if let Some(since) = &self.since {
+            parts.push(since.clone());
+        }
+        if let Some(until) = &self.until {
+            parts.push(format!("until {until}"));
+        }
+        if let Some(before) = &self.before {
+            parts.push(format!("before {before}"));
+        }
+        parts.join(" \u{00b7} ")
+    }
+}
+
+/// A `Scope` resolved at one moment, so that bounds like `today` stay put
+/// while the list is filtered.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScopeFilter {
+    /// Lowercased project and branch needles.
+    project: Option<String>,
+    branch: Option<String>,
+    /// Sessions start at or after this.
+    since: Option<DateTime<Utc>>,
+    /// Sessions start before these.
+    until: Option<DateTime<Utc>>,
+    before: Option<DateTime<Utc>>,
+}
+
+impl ScopeFilter {
+    /// Whether `session` is within the project, branch, and time window.
+    /// Matching ignores case.
+    pub fn contains(&self, session: &Session) -> bool {
+        let contains = |haystack: &str, needle: &str| haystack.to_lowercase().contains(needle);
+        if let Some(project) = &self.project {
+            if !contains(&session.project_name, project) && !contains(&session.project_path, project) {
+                return false;
+            }
+        }
+        if let Some(branch) = &self.branch {
+            if !session.git_branch.as_deref().is_some_and(|b| contains(b, branch)) {
+                return false;
+            }
+        }
+        if self.since.is_some_and(|t| session.timestamp < t) {
+            return false;
+        }
+        if self.until.is_some_and(|t| session.timestamp >= t) {
+            return false;
+        }
+        if self.before.is_some_and(|t| session.timestamp >= t) {
+            return false;
+        }
+        true
+    }
+}
+
+/// A named, saved list filter, scope, and sort order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct View {
+    /// Filter text, also used for the deep search.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub query: String,
+    #[serde(flatten)]
+    pub scope: Scope,
+    /// Sort key; unset keeps the current one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<SortKey>,
+    /// Reverse the sort order; unset keeps the current direction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_reverse: Option<bool>,
+}
+
+impl View {
+    /// Sort key and direction of the view, taking unset fields from
+    /// `current`.
+    pub fn sort_order(&self, current: (SortKey, bool)) -> (SortKey, bool) {
+        (self.sort.unwrap_or(current.0), self.sort_reverse.unwrap_or(current.1))
+    }
+
+    /// Short description of the sort, e.g. "by tokens, reversed".
+    pub fn describe_sort(&self) -> Option<String> {
+        let reversed = if self.sort_reverse == Some(true) { ", reversed" } else { "" };
+        self.sort.map(|key| format!("by {}{reversed}", key.label()))
+    }
+}
+
+/// File the views are saved in.
+pub fn views_path() -> PathBuf {
+    config_dir().join("views.toml")
+}
+
+/// Load the views saved at `path`, by name. A missing file has none.
+pub fn load_views(path: &Path) -> Result<BTreeMap<String, View>, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(e) => return Err(format!("failed to read {}: {e}", path.display())),
+    };
+    let views: BTreeMap<String, View> =
+        toml::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?;
+    for (name, view) in &views {
+        view.scope
+            .validate()
+            .map_err(|e| format!("{}: view {name:?}: {e}", path.display()))?;
+    }
+    Ok(views)
+}
+
+/// Save `views` to `path`, replacing its contents.
+pub fn save_views(path: &Path, views: &BTreeMap<String, View>) -> Result<(), String> {
+    let text = toml::to_string(views).map_err(|e| format!("failed to encode views: {e}"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+    }
+    fs::write(path, text).map_err(|e| format!("failed to save {}: {e}", path.display()))
+}
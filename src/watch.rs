@@ -0,0 +1,66 @@
+// Filesystem watching for live session list updates
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::compress::is_session_file;
+
+/// Watches `~/.claude/projects/` for new, modified, and removed session files.
+///
+/// Uses the platform's native notification API (inotify on Linux). Events
+/// are buffered in a channel and drained by the TUI main loop.
+pub struct SessionWatcher {
+    _watcher: RecommendedWatcher,
+    receiver: mpsc::Receiver<notify::Result<Event>>,
+    projects_dir: PathBuf,
+}
+
+impl SessionWatcher {
+    /// Start watching `claude_home/projects/` recursively, so that session
+    /// files in newly created project directories are picked up too.
+    pub fn new(claude_home: &Path) -> notify::Result<Self> {
+        let projects_dir = claude_home.join("projects");
+        let (tx, receiver) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |res| {
+            let _ = tx.send(res);
+        })?;
+        watcher.watch(&projects_dir, RecursiveMode::Recursive)?;
+        Ok(Self {
+            _watcher: watcher,
+            receiver,
+            projects_dir,
+        })
+    }
+
+    /// Drain pending events and return the distinct session files that were
+    /// created, modified, or removed since the last call.
+    ///
+    /// Only top-level session files inside a project directory are reported;
+    /// subagent transcripts in nested directories are ignored.
+    pub fn changed_files(&self) -> Vec<PathBuf> {
+        let mut changed = BTreeSet::new();
+        while let Ok(res) = self.receiver.try_recv() {
+            let Ok(event) = res else { continue };
+            if !matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+            ) {
+                continue;
+            }
+            for path in event.paths {
+                if self.is_watched_session_file(&path) {
+                    changed.insert(path);
+                }
+            }
+        }
+        changed.into_iter().collect()
+    }
+
+    fn is_watched_session_file(&self, path: &Path) -> bool {
+        is_session_file(path)
+            && path.parent().and_then(Path::parent) == Some(self.projects_dir.as_path())
+    }
+}
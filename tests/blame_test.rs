@@ -0,0 +1,95 @@
+use std::fs;
+use std::path::Path;
+
+use cc_session::blame::{attribute_lines, blame_file, BlameHunk};
+use cc_session::discovery::discover_sessions;
+
+#[test]
+fn later_edits_take_precedence() {
+    let file = ["fn main() {", "    let x = compute();", "    println!(\"{x}\");", "}"];
+    let written = vec![
+        vec!["fn main() {", "    let x = 1;", "    println!(\"{x}\");", "}"],
+        vec!["    let x = compute();"],
+    ];
+    let sources = attribute_lines(&file, &written);
+    assert_eq!(sources, vec![Some(0), Some(1), Some(0), Some(0)]);
+}
+
+#[test]
+fn trivial_lines_need_an_aligned_anchor() {
+    let file = ["}", "", "fn other() {}", "}"];
+    let written = vec![vec!["}", "", "}"]];
+    assert_eq!(attribute_lines(&file, &written), vec![None, None, None, None]);
+}
+
+#[test]
+fn blame_file_reports_sessions_and_unknown_lines() {
+    let home = tempfile::tempdir().unwrap();
+    let project = tempfile::tempdir().unwrap();
+    let project_path = project.path().to_string_lossy().into_owned();
+    let file = project.path().join("lib.rs");
+    fs::write(
+        &file,
+        "// header added by hand\npub fn parse(input: &str) -> usize {\n    input.len()\n}\n",
+    )
+    .unwrap();
+
+    let write_input = serde_json::json!({
+        "file_path": file.to_string_lossy(),
+        "content": "pub fn parse(input: &str) -> usize {\n    0\n}\n",
+    });
+    let edit_input = serde_json::json!({
+        "file_path": file.to_string_lossy(),
+        "old_string": "    0",
+        "new_string": "    input.len()",
+    });
+    let entry = |kind: &str, uuid: &str, ts: &str, content: serde_json::Value| {
+        serde_json::json!({
+            "type": kind,
+            "cwd": project_path,
+            "sessionId": "blame-session",
+            "uuid": uuid,
+            "timestamp": ts,
+            "message": {"role": kind, "content": content},
+        })
+        .to_string()
+    };
+    let lines = [
+        entry("user", "u1", "2025-03-01T10:00:00Z", "Write a parser".into()),
+        entry(
+            "assistant",
+            "a1",
+            "2025-03-01T10:01:00Z",
+            serde_json::json!([{"type": "tool_use", "name": "Write", "input": write_input}]),
+        ),
+        entry("user", "u2", "2025-03-01T10:02:00Z", "Return the length".into()),
+        entry(
+            "assistant",
+            "a2",
+            "2025-03-01T10:03:00Z",
+            serde_json::json!([{"type": "tool_use", "name": "Edit", "input": edit_input}]),
+        ),
+    ];
+    let dir = home.path().join("projects/-project");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("blame-session.jsonl"), lines.join("\n") + "\n").unwrap();
+
+    let sessions = discover_sessions(home.path());
+    let blame = blame_file(home.path(), &sessions, Path::new(&file)).unwrap();
+
+    let hunks = blame.hunks();
+    assert_eq!(
+        hunks,
+        vec![
+            BlameHunk { start: 1, end: 1, source: None },
+            BlameHunk { start: 2, end: 2, source: Some(0) },
+            BlameHunk { start: 3, end: 3, source: Some(1) },
+            BlameHunk { start: 4, end: 4, source: Some(0) },
+        ]
+    );
+    assert_eq!(blame.sources[0].uuid.as_deref(), Some("a1"));
+    assert_eq!(blame.sources[0].prompt.as_deref(), Some("Write a parser"));
+    assert_eq!(blame.sources[1].uuid.as_deref(), Some("a2"));
+    assert_eq!(blame.sources[1].prompt.as_deref(), Some("Return the length"));
+    assert_eq!(blame.sources[1].session.id, "blame-session");
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{Duration, Utc};
+use ratatui::{backend::TestBackend, Terminal};
+
+use cc_session::columns::{fit_columns, Column, Density, COLUMN_GAP, DEFAULT_COLUMNS};
+use cc_session::config::Config;
+use cc_session::session::Session;
+use cc_session::sort::SessionStats;
+use cc_session::theme::Theme;
+use cc_session::tui::{view, App};
+
+fn app() -> App {
+    let timestamp = Utc::now() - Duration::hours(3);
+    let session = Session {
+        id: "0123456789abcdef".to_string(),
+        project_path: "/work/api".to_string(),
+        project_name: "api".to_string(),
+        git_branch: Some("feature/long-branch-name-for-testing".to_string()),
+        timestamp,
+        last_activity: timestamp,
+        first_message: "Fix the flaky integration test".to_string(),
+        cwd: "/work/api".to_string(),
+        project_exists: true,
+        version: Some("2.0.14".to_string()),
+        file_path: PathBuf::from("/work/0123.jsonl"),
+    };
+    let mut app = App::new(vec![session], HashMap::new(), Theme::dark());
+    app.stats.insert(
+        "0123456789abcdef".to_string(),
+        SessionStats {
+            messages: 20,
+            turns: 12,
+            tokens: 45_600,
+            duration: Duration::minutes(75),
+            model: Some("claude-sonnet-4-5-20250929".to_string()),
+        },
+    );
+    app
+}
+
+fn screen(app: &mut App, width: u16) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(width, 8)).unwrap();
+    terminal.draw(|frame| view::render(frame, app)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..buffer.area.height)
+        .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect())
+        .collect()
+}
+
+#[test]
+fn columns_are_truncated_then_dropped_by_priority() {
+    let columns = [Column::Branch, Column::Version, Column::Time];
+    let gaps = 3 * COLUMN_GAP;
+    assert_eq!(fit_columns(&columns, &[10, 7, 11], 100), vec![10, 7, 11]);
+    assert_eq!(fit_columns(&columns, &[40, 7, 11], 100), vec![24, 7, 11], "capped");
+    assert_eq!(fit_columns(&columns, &[20, 7, 11], 30 + gaps), vec![12, 7, 11], "branch shrinks");
+    assert_eq!(fit_columns(&columns, &[20, 7, 11], 19 + gaps), vec![8, 0, 11], "version goes first");
+    assert_eq!(fit_columns(&columns, &[20, 7, 0], 100), vec![20, 7, 0], "empty columns take no room");
+}
+
+#[test]
+fn configured_columns_follow_the_prompt() {
+    let mut app = app();
+    app.columns = vec![
+        Column::Branch,
+        Column::Model,
+        Column::Turns,
+        Column::Tokens,
+        Column::Duration,
+        Column::Id,
+        Column::Version,
+        Column::Time,
+    ];
+    let row = screen(&mut app, 160)[1].clone();
+    for text in [
+        "Fix the flaky integration test",
+        "feature/long-branch-n...",
+        "sonnet-4-5",
+        "12 turns",
+        "45.6k tok",
+        "1h 15m",
+        "01234567",
+        "v2.0.14",
+        "3 hours ago",
+    ] {
+        assert!(row.contains(text), "{text:?} missing from {row:?}");
+    }
+
+    // Narrow terminals keep the higher-priority columns
+    let row = screen(&mut app, 70)[1].clone();
+    assert!(row.contains("3 hours ago") && row.contains("featu...") && row.contains("12 turns"), "{row:?}");
+    assert!(!row.contains("v2.0.14") && !row.contains("01234567"), "{row:?}");
+    assert!(row.contains("Fix the flaky"), "{row:?}");
+}
+
+#[test]
+fn two_line_rows_put_columns_under_the_prompt() {
+    let mut app = app();
+    app.density = Density::TwoLine;
+    app.columns = vec![Column::Project, Column::Turns, Column::Time];
+    let lines = screen(&mut app, 60);
+    assert!(lines[1].contains("Fix the flaky integration test"));
+    assert!(!lines[1].contains("api"), "{:?}", lines[1]);
+    assert!(lines[2].trim_start_matches('│').starts_with("  api  12 turns  3 hours ago"), "{:?}", lines[2]);
+}
+
+#[test]
+fn columns_and_density_are_read_from_the_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    assert_eq!(Config::load_from(&path).unwrap().columns, DEFAULT_COLUMNS.to_vec());
+
+    fs::write(&path, "columns = [\"branch\", \"id\", \"time\"]\ndensity = \"two-line\"\n").unwrap();
+    let config = Config::load_from(&path).unwrap();
+    assert_eq!(config.columns, vec![Column::Branch, Column::Id, Column::Time]);
+    assert_eq!(config.density, Density::TwoLine);
+
+    fs::write(&path, "columns = [\"mood\"]\n").unwrap();
+    assert!(Config::load_from(&path).is_err());
+}
@@ -0,0 +1,64 @@
+use std::fs;
+use std::path::PathBuf;
+
+use cc_session::activity::scan_session_activity;
+use cc_session::discovery::discover_sessions;
+use cc_session::search::build_session_index;
+use cc_session::theme::Theme;
+use cc_session::tui::App;
+
+fn fixture_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+#[test]
+fn activity_counts_turns_tokens_and_tools() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("s.jsonl");
+    let lines = [
+        r#"{"type":"user","message":{"role":"user","content":"Fix the build"},"timestamp":"2025-03-01T10:00:00.000Z"}"#,
+        // One streamed reply split over two entries sharing a message ID
+        r#"{"type":"assistant","message":{"id":"m1","model":"claude-a","role":"assistant","content":[{"type":"text","text":"Looking"}],"usage":{"input_tokens":10,"output_tokens":5,"cache_read_input_tokens":100}},"timestamp":"2025-03-01T10:00:05.000Z"}"#,
+        r#"{"type":"assistant","message":{"id":"m1","model":"claude-a","role":"assistant","content":[{"type":"tool_use","name":"Bash","input":{"command":"cargo build"}}],"usage":{"input_tokens":10,"output_tokens":20,"cache_read_input_tokens":100}},"timestamp":"2025-03-01T10:00:06.000Z"}"#,
+        r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","content":"ok"}]},"timestamp":"2025-03-01T10:00:30.000Z"}"#,
+        r#"{"type":"assistant","message":{"id":"m2","model":"claude-a","role":"assistant","content":[{"type":"tool_use","name":"Edit","input":{"file_path":"/r/a.rs","old_string":"a","new_string":"b"}},{"type":"tool_use","name":"Bash","input":{"command":"cargo test"}}],"usage":{"input_tokens":30,"output_tokens":40}},"timestamp":"2025-03-01T10:12:00.000Z"}"#,
+    ];
+    fs::write(&path, lines.join("\n")).unwrap();
+
+    let activity = scan_session_activity(&path);
+    assert_eq!(activity.user_turns, 1, "tool results are not prompts");
+    assert_eq!(activity.assistant_turns, 2);
+    assert_eq!(activity.tokens.input, 40);
+    assert_eq!(activity.tokens.output, 60);
+    assert_eq!(activity.tokens.total_input(), 140);
+    assert_eq!(activity.models, vec!["claude-a"]);
+    assert_eq!(activity.tool_counts.get("Bash"), Some(&2));
+    assert_eq!(activity.tool_counts.get("Edit"), Some(&1));
+    assert_eq!(activity.duration(), Some(chrono::Duration::minutes(12)));
+}
+
+#[test]
+fn marking_keeps_at_most_two_sessions() {
+    let home = fixture_dir();
+    let sessions = discover_sessions(&home);
+    let index = build_session_index(&home, &sessions);
+    let mut app = App::new(sessions, index, Theme::dark());
+    let ids: Vec<String> = (0..3)
+        .map(|i| app.display_session(&app.display_entries[i].clone()).id.clone())
+        .collect();
+
+    for i in 0..3 {
+        app.selected = i;
+        app.toggle_mark();
+    }
+    assert_eq!(app.marked, vec![ids[1].clone(), ids[2].clone()]);
+    assert!(!app.is_marked(&ids[0]));
+
+    // Marking a marked session again unmarks it
+    app.toggle_mark();
+    assert_eq!(app.marked, vec![ids[1].clone()]);
+
+    // Comparing needs two marks
+    app.open_compare();
+    assert!(app.compare.is_none());
+}
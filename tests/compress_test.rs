@@ -0,0 +1,110 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use cc_session::compress::{
+    compress_file, decompress_file, find_compress_candidates, session_id_from_path, Format,
+};
+use cc_session::discovery::{discover_sessions, load_conversation};
+use cc_session::search::deep_search;
+
+fn fixture_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// Copy the fixture projects into a temporary Claude home.
+fn temp_home() -> tempfile::TempDir {
+    let home = tempfile::tempdir().unwrap();
+    for project in ["-project-a", "-project-b"] {
+        let src = fixture_dir().join("projects").join(project);
+        let dst = home.path().join("projects").join(project);
+        fs::create_dir_all(&dst).unwrap();
+        for file in fs::read_dir(&src).unwrap().flatten() {
+            fs::copy(file.path(), dst.join(file.file_name())).unwrap();
+        }
+    }
+    home
+}
+
+fn session_path(home: &Path, project: &str, id: &str) -> PathBuf {
+    home.join("projects").join(project).join(format!("{id}.jsonl"))
+}
+
+#[test]
+fn session_id_strips_compression_suffix() {
+    assert_eq!(session_id_from_path(Path::new("a/abc.jsonl")).as_deref(), Some("abc"));
+    assert_eq!(session_id_from_path(Path::new("a/abc.jsonl.gz")).as_deref(), Some("abc"));
+    assert_eq!(session_id_from_path(Path::new("a/abc.jsonl.zst")).as_deref(), Some("abc"));
+    assert_eq!(session_id_from_path(Path::new("a/abc.json")), None);
+}
+
+#[test]
+fn compressed_sessions_are_discovered_and_searchable() {
+    let home = temp_home();
+    let gz = compress_file(
+        &session_path(home.path(), "-project-a", "11111111-1111-1111-1111-111111111111"),
+        Format::Gzip,
+    )
+    .unwrap();
+    let zst = compress_file(
+        &session_path(home.path(), "-project-b", "33333333-3333-3333-3333-333333333333"),
+        Format::Zstd,
+    )
+    .unwrap();
+    assert!(gz.to_string_lossy().ends_with(".jsonl.gz"));
+    assert!(zst.to_string_lossy().ends_with(".jsonl.zst"));
+
+    let sessions = discover_sessions(home.path());
+    assert_eq!(sessions.len(), 3);
+    let s = sessions
+        .iter()
+        .find(|s| s.id == "11111111-1111-1111-1111-111111111111")
+        .unwrap();
+    assert_eq!(s.first_message, "Help me implement the list endpoints feature");
+
+    let found = deep_search(home.path(), "ConnectionRefused");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, "33333333-3333-3333-3333-333333333333");
+}
+
+#[test]
+fn compressed_conversation_loads() {
+    let home = temp_home();
+    let plain = session_path(home.path(), "-project-b", "33333333-3333-3333-3333-333333333333");
+    compress_file(&plain, Format::Zstd).unwrap();
+    assert!(!plain.exists(), "original should be removed after compression");
+
+    let sessions = discover_sessions(home.path());
+    let s = sessions
+        .iter()
+        .find(|s| s.id == "33333333-3333-3333-3333-333333333333")
+        .unwrap();
+    let messages = load_conversation(home.path(), s);
+    assert_eq!(messages.len(), 2);
+}
+
+#[test]
+fn decompress_restores_original_content() {
+    let home = temp_home();
+    let plain = session_path(home.path(), "-project-a", "11111111-1111-1111-1111-111111111111");
+    let original = fs::read(&plain).unwrap();
+
+    let compressed = compress_file(&plain, Format::Gzip).unwrap();
+    let restored = decompress_file(&compressed).unwrap();
+
+    assert_eq!(restored, plain);
+    assert!(!compressed.exists());
+    assert_eq!(fs::read(&restored).unwrap(), original);
+}
+
+#[test]
+fn candidates_skip_recent_and_compressed_files() {
+    let home = temp_home();
+    assert!(find_compress_candidates(home.path(), chrono::Duration::days(90)).is_empty());
+
+    let all = find_compress_candidates(home.path(), chrono::Duration::zero());
+    assert_eq!(all.len(), 3);
+
+    compress_file(&all[0].path, Format::Zstd).unwrap();
+    let remaining = find_compress_candidates(home.path(), chrono::Duration::zero());
+    assert_eq!(remaining.len(), 2);
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::fs;
+
+use ratatui::{backend::TestBackend, Terminal};
+
+use cc_session::discovery::discover_sessions;
+use cc_session::theme::Theme;
+use cc_session::tui::{view, App};
+
+/// An app showing a reply whose first line wraps at the 40-column width.
+fn app_with_reply(filter: &str) -> (tempfile::TempDir, App) {
+    let home = tempfile::tempdir().unwrap();
+    let dir = home.path().join("projects/-find");
+    fs::create_dir_all(&dir).unwrap();
+    let entry = |role: &str, ts: &str, text: &str| {
+        serde_json::json!({
+            "type": role, "cwd": "/find", "sessionId": "find", "timestamp": ts,
+            "message": {"role": role, "content": [{"type": "text", "text": text}]},
+        })
+        .to_string()
+    };
+    let lines = [
+        entry("user", "2025-03-01T10:00:00Z", "Why does it fail?"),
+        entry(
+            "assistant",
+            "2025-03-01T10:00:10Z",
+            "The connection to the database server was refused by the remote host\nRetry with error code 42",
+        ),
+    ];
+    fs::write(dir.join("find.jsonl"), lines.join("\n") + "\n").unwrap();
+    let sessions = discover_sessions(home.path());
+    let mut app = App::new(sessions, HashMap::new(), Theme::dark());
+    app.filter_query = filter.to_string();
+    app.enter_conversation(0);
+    draw(&mut app);
+    (home, app)
+}
+
+fn draw(app: &mut App) {
+    let mut terminal = Terminal::new(TestBackend::new(44, 20)).unwrap();
+    terminal.draw(|frame| view::render(frame, app)).unwrap();
+}
+
+/// Text of the highlighted spans of the conversation, in order.
+fn highlighted(app: &App) -> Vec<String> {
+    let conv = app.conversation.as_ref().unwrap();
+    let bg = app.theme.search_highlight_bg;
+    conv.lines
+        .iter()
+        .flat_map(|l| l.spans.iter())
+        .filter(|s| s.style.bg == Some(bg))
+        .map(|s| s.content.to_string())
+        .collect()
+}
+
+#[test]
+fn filter_regex_highlights_in_the_viewer() {
+    let (_home, app) = app_with_reply(r"code \d+");
+    let conv = app.conversation.as_ref().unwrap();
+    assert!(conv.search_options.regex, "filter terms keep deep search semantics");
+    assert_eq!(conv.match_positions.len(), 1);
+    assert_eq!(highlighted(&app), vec!["code 42"]);
+}
+
+#[test]
+fn matches_span_wrapped_lines() {
+    let (_home, mut app) = app_with_reply("");
+    let conv = app.conversation.as_mut().unwrap();
+    conv.search_query = "database server was refused".to_string();
+    conv.rendered_width = 0;
+    draw(&mut app);
+
+    let conv = app.conversation.as_ref().unwrap();
+    assert_eq!(conv.match_positions.len(), 1);
+    let parts = highlighted(&app);
+    assert!(parts.len() > 1, "match is split over the wrapped lines: {parts:?}");
+    assert_eq!(parts.join(" "), "database server was refused");
+}
+
+#[test]
+fn case_and_whole_word_modes_narrow_matches() {
+    let (_home, mut app) = app_with_reply("");
+    let conv = app.conversation.as_mut().unwrap();
+    conv.search_query = "the".to_string();
+    conv.rendered_width = 0;
+    draw(&mut app);
+    assert_eq!(highlighted(&app).len(), 3, "The, the, and the");
+
+    let conv = app.conversation.as_mut().unwrap();
+    conv.search_options.case_sensitive = true;
+    conv.rendered_width = 0;
+    draw(&mut app);
+    assert_eq!(highlighted(&app).len(), 2);
+
+    let conv = app.conversation.as_mut().unwrap();
+    conv.search_query = "re".to_string();
+    conv.search_options.whole_word = true;
+    conv.rendered_width = 0;
+    draw(&mut app);
+    assert!(highlighted(&app).is_empty());
+
+    let conv = app.conversation.as_mut().unwrap();
+    conv.search_options.regex = true;
+    conv.search_query = "re(fused|mote)".to_string();
+    conv.rendered_width = 0;
+    draw(&mut app);
+    assert_eq!(highlighted(&app), vec!["refused", "remote"]);
+}
@@ -0,0 +1,72 @@
+use cc_session::activity::TextChange;
+use cc_session::diff::{
+    classify_diff_line, diff_blocks, diff_fence_path, edit_diff_block, unified_diff, DiffLineKind,
+};
+use cc_session::discovery::merge_conversation_line;
+
+#[test]
+fn unified_diff_marks_changed_lines() {
+    let lines = unified_diff("a\nb\nc\n", "a\nB\nc\n");
+    assert_eq!(lines, vec![" a", "-b", "+B", " c"]);
+}
+
+#[test]
+fn distant_changes_are_split_into_hunks() {
+    let old: String = (1..=20).map(|i| format!("line {i}\n")).collect();
+    let new = old.replace("line 2\n", "line two\n").replace("line 19\n", "line nineteen\n");
+    let lines = unified_diff(&old, &new);
+    assert_eq!(lines.iter().filter(|l| *l == "@@").count(), 1);
+    assert!(lines.contains(&"+line two".to_string()));
+    assert!(lines.contains(&"-line 19".to_string()));
+}
+
+#[test]
+fn classifies_diff_lines() {
+    assert_eq!(classify_diff_line("+added"), (DiffLineKind::Added, "added"));
+    assert_eq!(classify_diff_line("-removed"), (DiffLineKind::Removed, "removed"));
+    assert_eq!(classify_diff_line(" same"), (DiffLineKind::Context, "same"));
+    assert_eq!(classify_diff_line("@@").0, DiffLineKind::Meta);
+}
+
+#[test]
+fn diff_blocks_round_trip_through_message_text() {
+    let block = edit_diff_block(
+        "src/main.rs",
+        &[
+            TextChange { old: "a".into(), new: "b".into() },
+            TextChange { old: "x".into(), new: "y".into() },
+        ],
+    );
+    assert_eq!(diff_fence_path(block.lines().next().unwrap()), Some("src/main.rs"));
+    assert_eq!(diff_fence_path("```rust"), None);
+
+    let text = format!("Some prose\n\n```rust\nfn main() {{}}\n```\n\n{block}");
+    let blocks = diff_blocks(&text);
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].0, "src/main.rs");
+    assert_eq!(blocks[0].1, vec!["-a", "+b", "@@", "-x", "+y"]);
+}
+
+#[test]
+fn edit_tool_calls_become_diff_blocks_in_conversation() {
+    let line = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Renaming it."},{"type":"tool_use","name":"Edit","input":{"file_path":"/repo/lib.rs","old_string":"fn old() {}","new_string":"fn new() {}"}}]},"timestamp":"2025-03-01T10:00:00.000Z"}"#;
+    let mut messages = Vec::new();
+    merge_conversation_line(&mut messages, line);
+
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].text.starts_with("Renaming it."));
+    let blocks = diff_blocks(&messages[0].text);
+    assert_eq!(blocks, vec![("/repo/lib.rs".to_string(), vec!["-fn old() {}".to_string(), "+fn new() {}".to_string()])]);
+}
+
+#[test]
+fn fences_in_edited_markdown_stay_inside_the_diff() {
+    let block = edit_diff_block(
+        "README.md",
+        &[TextChange { old: "```rust\nold()\n```\n".into(), new: "```rust\nnew()\n```\n".into() }],
+    );
+    let text = format!("{block}\n\nDone.");
+    let blocks = diff_blocks(&text);
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].1, vec![" ```rust", "-old()", "+new()", " ```"]);
+}
@@ -56,7 +56,7 @@ fn array_content_extracted() {
 #[test]
 fn apply_last_filter() {
     let sessions = discover_sessions(&fixture_dir());
-    let filtered = apply_filters(sessions, None, Some(2));
+    let filtered = apply_filters(sessions, Some(2));
     assert_eq!(filtered.len(), 2);
 }
 
@@ -65,3 +65,71 @@ fn empty_directory_returns_empty() {
     let sessions = discover_sessions(&PathBuf::from("/nonexistent/path"));
     assert!(sessions.is_empty());
 }
+
+#[test]
+fn conversation_tail_reads_only_appended_lines() {
+    use std::io::Write;
+
+    use cc_session::discovery::ConversationTail;
+    use cc_session::session::MessageRole;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("tail.jsonl");
+    let user = r#"{"type":"user","message":{"role":"user","content":"Start the build"},"timestamp":"2025-02-20T10:00:00.000Z"}"#;
+    let reply = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Building now."}]},"timestamp":"2025-02-20T10:00:05.000Z"}"#;
+    let more = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Build passed."}]},"timestamp":"2025-02-20T10:00:09.000Z"}"#;
+    std::fs::write(&path, format!("{user}\n")).unwrap();
+
+    let mut tail = ConversationTail::new(path.clone());
+    let mut messages = Vec::new();
+    assert_eq!(tail.read_appended(&mut messages), Some(0));
+    assert_eq!(messages.len(), 1);
+    assert_eq!(tail.read_appended(&mut messages), None, "nothing appended yet");
+
+    // A partially written line is left for the next read
+    let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+    write!(file, "{}", &reply[..20]).unwrap();
+    assert_eq!(tail.read_appended(&mut messages), None);
+    writeln!(file, "{}", &reply[20..]).unwrap();
+    assert_eq!(tail.read_appended(&mut messages), Some(1));
+    assert_eq!(messages[1].role, MessageRole::Assistant);
+
+    // Consecutive assistant output merges into the last message
+    writeln!(file, "{more}").unwrap();
+    assert_eq!(tail.read_appended(&mut messages), Some(1));
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[1].text, "Building now.\n\nBuild passed.");
+}
+
+#[test]
+fn conversation_tail_loads_unterminated_and_undecodable_lines() {
+    use std::io::Write;
+
+    use cc_session::discovery::ConversationTail;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("tail.jsonl");
+    let user = r#"{"type":"user","message":{"role":"user","content":"Start the build"},"timestamp":"2025-02-20T10:00:00.000Z"}"#;
+    let reply = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Building now."}]},"timestamp":"2025-02-20T10:00:05.000Z"}"#;
+    let mut bytes = format!("{user}\n").into_bytes();
+    bytes.extend_from_slice(b"\xff\xfe not utf-8\n");
+    bytes.extend_from_slice(reply.as_bytes());
+    std::fs::write(&path, bytes).unwrap();
+
+    // The final line is shown even without a newline
+    let mut tail = ConversationTail::new(path.clone());
+    let mut messages = Vec::new();
+    tail.read_all(&mut messages);
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[1].text, "Building now.");
+
+    // Following picks up after the undecodable line without repeating the reply
+    let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+    writeln!(file).unwrap();
+    tail.read_appended(&mut messages);
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[1].text, "Building now.");
+    writeln!(file, "{user}").unwrap();
+    assert_eq!(tail.read_appended(&mut messages), Some(2));
+    assert_eq!(messages.len(), 3);
+}
@@ -0,0 +1,99 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use cc_session::compress::{compress_file, Format};
+use cc_session::doctor::{audit_file, audit_sessions, version_counts, MalformedLine};
+
+fn write_file(home: &Path, name: &str, lines: &[&str]) -> PathBuf {
+    let dir = home.join("projects").join("-project");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    fs::write(&path, lines.join("\n") + "\n").unwrap();
+    path
+}
+
+const USER: &str = r#"{"type":"user","cwd":"/tmp","version":"2.1.50","message":{"role":"user","content":"hello"},"timestamp":"2025-03-01T10:00:00.000Z"}"#;
+
+#[test]
+fn clean_file_has_no_findings() {
+    let home = tempfile::tempdir().unwrap();
+    let path = write_file(home.path(), "a.jsonl", &[r#"{"type":"summary","summary":"x"}"#, USER]);
+    let audit = audit_file(&path);
+    assert!(audit.is_clean());
+    assert_eq!(audit.user_entries, 1);
+    assert_eq!(audit.version.as_deref(), Some("2.1.50"));
+}
+
+#[test]
+fn reports_malformed_lines_and_unknown_types() {
+    let home = tempfile::tempdir().unwrap();
+    let path = write_file(
+        home.path(),
+        "a.jsonl",
+        &[USER, "{truncated", "", r#"{"type":"brand-new"}"#, r#"{"no":"type"}"#, r#"{"type":"brand-new"}"#],
+    );
+    let audit = audit_file(&path);
+    assert_eq!(audit.malformed.len(), 1);
+    assert_eq!(audit.malformed[0].line, 2);
+    assert_eq!(audit.unknown_types.get("brand-new"), Some(&2));
+    assert_eq!(audit.unknown_types.get("(missing)"), Some(&1));
+    // Still listed by discovery
+    assert_eq!(audit.dropped, None);
+}
+
+#[test]
+fn explains_dropped_sessions() {
+    let home = tempfile::tempdir().unwrap();
+    let no_user = write_file(home.path(), "a.jsonl", &[r#"{"type":"summary","summary":"x"}"#]);
+    let audit = audit_file(&no_user);
+    assert!(audit.has_no_user_entries());
+
+    let bad_shape = write_file(home.path(), "b.jsonl", &[r#"{"type":"user","message":{"content":"hi"}}"#]);
+    let audit = audit_file(&bad_shape);
+    assert!(!audit.has_no_user_entries());
+    let reason = audit.dropped.unwrap();
+    assert!(reason.contains("line 1") && reason.contains("role"), "{reason}");
+
+    let mut lines = vec![r#"{"type":"progress"}"#; 60];
+    lines.push(USER);
+    let late = write_file(home.path(), "c.jsonl", &lines);
+    let reason = audit_file(&late).dropped.unwrap();
+    assert!(reason.contains("line 61"), "{reason}");
+}
+
+#[test]
+fn reads_compressed_files() {
+    let home = tempfile::tempdir().unwrap();
+    let path = write_file(home.path(), "a.jsonl", &[USER, "not json"]);
+    let gz = compress_file(&path, Format::Gzip).unwrap();
+    let audit = audit_file(&gz);
+    assert_eq!(
+        audit.malformed.iter().map(|m| m.line).collect::<Vec<_>>(),
+        vec![2]
+    );
+
+    let broken = write_file(home.path(), "b.jsonl.zst", &["garbage"]);
+    let audit = audit_file(&broken);
+    assert!(matches!(audit.malformed.as_slice(), [MalformedLine { line: 1, .. }]));
+    assert!(audit.dropped.unwrap().starts_with("read error on line 1"));
+}
+
+#[test]
+fn counts_versions_newest_first() {
+    let home = tempfile::tempdir().unwrap();
+    write_file(home.path(), "a.jsonl", &[USER]);
+    write_file(home.path(), "b.jsonl", &[&USER.replace("2.1.50", "2.1.9")]);
+    write_file(home.path(), "c.jsonl", &[&USER.replace("2.1.50", "2.1.9")]);
+    write_file(home.path(), "d.jsonl", &[r#"{"type":"user","message":{"role":"user","content":"x"}}"#]);
+
+    let audits = audit_sessions(home.path());
+    assert_eq!(audits.len(), 4);
+    assert_eq!(
+        version_counts(&audits),
+        vec![
+            ("2.1.50".to_string(), 1),
+            ("2.1.9".to_string(), 2),
+            ("unknown".to_string(), 1),
+        ]
+    );
+}
@@ -0,0 +1,127 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use cc_session::activity::{build_file_index, scan_session_activity, sessions_touching_file};
+use cc_session::discovery::discover_sessions;
+
+fn user_line(id: &str, ts: &str) -> String {
+    format!(
+        r#"{{"type":"user","cwd":"/repo","sessionId":"{id}","message":{{"role":"user","content":"Work on {id}"}},"uuid":"u-{id}","timestamp":"{ts}"}}"#
+    )
+}
+
+fn tool_line(id: &str, tool: &str, file: &str, ts: &str) -> String {
+    format!(
+        r#"{{"type":"assistant","cwd":"/repo","sessionId":"{id}","message":{{"role":"assistant","content":[{{"type":"tool_use","name":"{tool}","input":{{"file_path":"{file}"}}}}]}},"timestamp":"{ts}"}}"#
+    )
+}
+
+fn write_session(home: &Path, id: &str, lines: &[String]) -> PathBuf {
+    let dir = home.join("projects/-repo");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{id}.jsonl"));
+    fs::write(&path, lines.join("\n") + "\n").unwrap();
+    path
+}
+
+fn home_with_sessions() -> tempfile::TempDir {
+    let home = tempfile::tempdir().unwrap();
+    write_session(
+        home.path(),
+        "older",
+        &[
+            user_line("older", "2025-03-01T10:00:00.000Z"),
+            tool_line("older", "Edit", "/repo/src/view.rs", "2025-03-01T10:05:00.000Z"),
+            tool_line("older", "MultiEdit", "/repo/src/view.rs", "2025-03-01T10:06:00.000Z"),
+        ],
+    );
+    write_session(
+        home.path(),
+        "newer",
+        &[
+            user_line("newer", "2025-03-02T10:00:00.000Z"),
+            tool_line("newer", "Write", "/repo/src/view.rs", "2025-03-02T10:01:00.000Z"),
+            tool_line("newer", "Read", "/repo/src/main.rs", "2025-03-02T10:02:00.000Z"),
+        ],
+    );
+    write_session(
+        home.path(),
+        "reader",
+        &[
+            user_line("reader", "2025-03-03T10:00:00.000Z"),
+            tool_line("reader", "Read", "/repo/src/view.rs", "2025-03-03T10:01:00.000Z"),
+        ],
+    );
+    home
+}
+
+#[test]
+fn file_summary_lists_edited_files_first() {
+    let home = home_with_sessions();
+    let activity = scan_session_activity(&home.path().join("projects/-repo/newer.jsonl"));
+    let files = activity.file_summary();
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0].path, "/repo/src/view.rs");
+    assert_eq!((files[0].edits, files[0].reads), (1, 0));
+    assert_eq!(files[1].path, "/repo/src/main.rs");
+    assert_eq!((files[1].edits, files[1].reads), (0, 1));
+}
+
+#[test]
+fn lookup_finds_modifying_sessions_by_recency() {
+    let home = home_with_sessions();
+    let sessions = discover_sessions(home.path());
+    let index = build_file_index(home.path(), &sessions, None);
+
+    let found = sessions_touching_file(&sessions, &index, Path::new("/repo/src/view.rs"), false);
+    let ids: Vec<&str> = found.iter().map(|m| m.session.id.as_str()).collect();
+    assert_eq!(ids, vec!["newer", "older"]);
+    assert_eq!(found[1].edits, 2);
+}
+
+#[test]
+fn lookup_includes_readers_on_request() {
+    let home = home_with_sessions();
+    let sessions = discover_sessions(home.path());
+    let index = build_file_index(home.path(), &sessions, None);
+
+    let found = sessions_touching_file(&sessions, &index, Path::new("/repo/src/view.rs"), true);
+    assert_eq!(found.len(), 3);
+    assert_eq!(found[0].session.id, "reader");
+    assert_eq!((found[0].edits, found[0].reads), (0, 1));
+
+    let none = sessions_touching_file(&sessions, &index, Path::new("/repo/src/main.rs"), false);
+    assert!(none.is_empty());
+}
+
+#[test]
+fn lookup_reuses_cached_file_sets_until_a_session_changes() {
+    let home = home_with_sessions();
+    let cache = home.path().join("state/file_index.json");
+    let sessions = discover_sessions(home.path());
+    let index = build_file_index(home.path(), &sessions, Some(&cache));
+    assert!(cache.is_file());
+    assert_eq!(index["newer"].files["/repo/src/view.rs"].edits, 1);
+
+    // Rewriting a file without changing its mtime keeps the cached set
+    let path = home.path().join("projects/-repo/newer.jsonl");
+    let mtime = fs::metadata(&path).unwrap().modified().unwrap();
+    write_session(
+        home.path(),
+        "newer",
+        &[
+            user_line("newer", "2025-03-02T10:00:00.000Z"),
+            tool_line("newer", "Read", "/repo/src/lib.rs", "2025-03-02T10:01:00.000Z"),
+        ],
+    );
+    let file = fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(mtime).unwrap();
+    let index = build_file_index(home.path(), &sessions, Some(&cache));
+    assert!(index["newer"].files.contains_key("/repo/src/view.rs"));
+
+    // A newer mtime rescans the session
+    file.set_modified(mtime + std::time::Duration::from_secs(60)).unwrap();
+    let index = build_file_index(home.path(), &sessions, Some(&cache));
+    assert!(!index["newer"].files.contains_key("/repo/src/view.rs"));
+    assert_eq!(index["newer"].files["/repo/src/lib.rs"].reads, 1);
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::fs;
+
+use ratatui::{backend::TestBackend, Terminal};
+
+use cc_session::discovery::discover_sessions;
+use cc_session::session::MessageRole;
+use cc_session::theme::Theme;
+use cc_session::tui::{view, App, Folds};
+
+#[test]
+fn body_limit_prefers_overrides_then_fold_all_then_collapse() {
+    let mut folds = Folds {
+        collapse_after: Some(10),
+        ..Folds::default()
+    };
+    assert_eq!(folds.body_limit(0, 5, false), None);
+    assert_eq!(folds.body_limit(0, 50, false), Some(10));
+    assert_eq!(folds.body_limit(0, 50, true), None, "matches stay visible");
+
+    folds.toggle_all();
+    assert_eq!(folds.body_limit(0, 5, false), Some(0));
+
+    folds.overrides.insert(0, false);
+    assert_eq!(folds.body_limit(0, 50, false), None);
+    folds.toggle_all();
+    assert!(folds.overrides.is_empty());
+}
+
+/// An app showing a session of alternating prompts and long replies.
+fn app_with_conversation() -> (tempfile::TempDir, App) {
+    let home = tempfile::tempdir().unwrap();
+    let dir = home.path().join("projects/-fold");
+    fs::create_dir_all(&dir).unwrap();
+    let reply: Vec<String> = (1..=30).map(|i| format!("reply line {i}")).collect();
+    let mut lines = Vec::new();
+    for i in 0..3 {
+        lines.push(
+            serde_json::json!({
+                "type": "user", "cwd": "/fold", "sessionId": "fold",
+                "timestamp": format!("2025-03-01T10:0{i}:00Z"),
+                "message": {"role": "user", "content": format!("question {i}")},
+            })
+            .to_string(),
+        );
+        lines.push(
+            serde_json::json!({
+                "type": "assistant", "cwd": "/fold", "sessionId": "fold",
+                "timestamp": format!("2025-03-01T10:0{i}:30Z"),
+                "message": {"role": "assistant", "content": [{"type": "text", "text": reply.join("\n")}]},
+            })
+            .to_string(),
+        );
+    }
+    fs::write(dir.join("fold.jsonl"), lines.join("\n") + "\n").unwrap();
+
+    let sessions = discover_sessions(home.path());
+    let mut app = App::new(sessions, HashMap::new(), Theme::dark());
+    app.enter_conversation(0);
+    (home, app)
+}
+
+fn draw(app: &mut App) {
+    let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+    terminal.draw(|frame| view::render(frame, app)).unwrap();
+}
+
+fn text_of(app: &App, line: usize) -> String {
+    let conv = app.conversation.as_ref().unwrap();
+    conv.lines[line].spans.iter().map(|s| s.content.as_ref()).collect()
+}
+
+#[test]
+fn jumps_between_prompts_and_replies() {
+    let (_home, mut app) = app_with_conversation();
+    draw(&mut app);
+    let conv = app.conversation.as_mut().unwrap();
+    let starts = conv.message_starts.clone();
+    assert_eq!(starts.len(), 6);
+
+    conv.jump_to_message(MessageRole::User, true);
+    assert_eq!(conv.scroll_offset, starts[2]);
+    assert_eq!(conv.message_at(conv.scroll_offset + 1), Some(2));
+    conv.jump_to_message(MessageRole::Assistant, true);
+    assert_eq!(conv.scroll_offset, starts[3]);
+    conv.jump_to_message(MessageRole::User, false);
+    assert_eq!(conv.scroll_offset, starts[2]);
+    conv.jump_to_message(MessageRole::Assistant, false);
+    assert_eq!(conv.scroll_offset, starts[1]);
+}
+
+#[test]
+fn folding_replaces_bodies_with_a_marker() {
+    let (_home, mut app) = app_with_conversation();
+    draw(&mut app);
+    let conv = app.conversation.as_mut().unwrap();
+    let full = conv.lines.len();
+    conv.jump_to_message(MessageRole::Assistant, true);
+    conv.toggle_fold();
+    draw(&mut app);
+
+    let conv = app.conversation.as_ref().unwrap();
+    let start = conv.message_starts[1];
+    assert_eq!(conv.scroll_offset, start, "view stays on the folded message");
+    assert_eq!(text_of(&app, start + 1), " \u{2026} 30 more lines");
+    assert!(conv.lines.len() < full);
+
+    // Fold everything, then auto-collapse long messages only
+    app.conversation.as_mut().unwrap().toggle_fold_all();
+    draw(&mut app);
+    assert_eq!(app.conversation.as_ref().unwrap().lines.len(), 12);
+
+    let conv = app.conversation.as_mut().unwrap();
+    conv.toggle_fold_all();
+    conv.folds.collapse_after = Some(10);
+    conv.rendered_width = 0;
+    draw(&mut app);
+    let conv = app.conversation.as_ref().unwrap();
+    let reply = conv.message_starts[1];
+    assert_eq!(text_of(&app, reply + 11), " \u{2026} 20 more lines");
+}
@@ -0,0 +1,163 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use chrono::{DateTime, Utc};
+
+use cc_session::activity::scan_session_activity;
+use cc_session::discovery::parse_session_file;
+use cc_session::git::{commits_between, parse_git_log, CommitsLoader};
+
+fn ts(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+fn git(repo: &Path, args: &[&str], date: &str) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .env("GIT_AUTHOR_DATE", date)
+        .env("GIT_COMMITTER_DATE", date)
+        .output()
+        .unwrap();
+    assert!(status.status.success(), "git {args:?} failed");
+}
+
+fn commit(repo: &Path, file: &str, message: &str, date: &str) {
+    fs::write(repo.join(file), format!("{message}\n")).unwrap();
+    git(repo, &["add", file], date);
+    git(repo, &["commit", "-q", "-m", message], date);
+}
+
+#[test]
+fn parses_git_log_records() {
+    let output = "\u{1e}abc\u{1f}Jane\u{1f}2025-03-01T10:30:00+01:00\u{1f}Fix parser\n\nsrc/a.rs\nsrc/b.rs\n\
+                  \u{1e}def\u{1f}Joe\u{1f}2025-03-01T09:00:00+00:00\u{1f}Initial\n\nREADME.md\n";
+    let commits = parse_git_log(output);
+    assert_eq!(commits.len(), 2);
+    assert_eq!(commits[0].short_hash, "abc");
+    assert_eq!(commits[0].subject, "Fix parser");
+    assert_eq!(commits[0].timestamp, ts("2025-03-01T09:30:00Z"));
+    assert_eq!(commits[0].files, vec!["src/a.rs", "src/b.rs"]);
+    assert_eq!(commits[1].files, vec!["README.md"]);
+}
+
+#[test]
+fn scans_edited_files_and_time_window() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("s.jsonl");
+    let lines = [
+        r#"{"type":"user","message":{"role":"user","content":"Fix it"},"timestamp":"2025-03-01T10:00:00.000Z"}"#,
+        r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","name":"Read","input":{"file_path":"/repo/a.rs"}}]},"timestamp":"2025-03-01T10:01:00.000Z"}"#,
+        r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","name":"Edit","input":{"file_path":"/repo/a.rs","old_string":"x","new_string":"y"}}]},"timestamp":"2025-03-01T10:02:00.000Z"}"#,
+        r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","name":"Bash","input":{"command":"ls"}}]},"timestamp":"2025-03-01T10:03:00.000Z"}"#,
+        r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","name":"Write","input":{"file_path":"/repo/b.rs","content":""}}]},"timestamp":"2025-03-01T10:04:00.000Z"}"#,
+    ];
+    fs::write(&path, lines.join("\n")).unwrap();
+
+    let activity = scan_session_activity(&path);
+    assert_eq!(activity.first_timestamp, Some(ts("2025-03-01T10:00:00Z")));
+    assert_eq!(activity.last_timestamp, Some(ts("2025-03-01T10:04:00Z")));
+    assert_eq!(activity.file_uses.len(), 3);
+    let edited: Vec<String> = activity.edited_files().into_iter().collect();
+    assert_eq!(edited, vec!["/repo/a.rs", "/repo/b.rs"]);
+}
+
+#[test]
+fn lists_commits_in_session_window_on_branch() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path();
+    git(repo, &["init", "-q", "-b", "main"], "2025-03-01T08:00:00Z");
+    commit(repo, "before.txt", "Before session", "2025-03-01T09:00:00Z");
+    commit(repo, "edited.rs", "Touch edited file", "2025-03-01T10:30:00Z");
+    commit(repo, "other.rs", "Unrelated change", "2025-03-01T10:45:00Z");
+    commit(repo, "after.txt", "After session", "2025-03-01T12:00:00Z");
+    git(repo, &["checkout", "-q", "-b", "side", "HEAD~1"], "2025-03-01T10:50:00Z");
+    commit(repo, "side.txt", "On another branch", "2025-03-01T10:50:00Z");
+
+    let root = repo.canonicalize().unwrap();
+    let edited: BTreeSet<String> = [root.join("edited.rs").to_string_lossy().into_owned()].into();
+    let commits = commits_between(
+        repo,
+        Some("main"),
+        ts("2025-03-01T10:00:00Z"),
+        ts("2025-03-01T11:00:00Z"),
+        &edited,
+    )
+    .unwrap();
+
+    let subjects: Vec<&str> = commits.iter().map(|c| c.subject.as_str()).collect();
+    assert_eq!(subjects, vec!["Unrelated change", "Touch edited file"]);
+    assert!(!commits[0].touches_session_edits);
+    assert!(commits[1].touches_session_edits);
+}
+
+#[test]
+fn deleted_branch_falls_back_to_head() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path();
+    git(repo, &["init", "-q", "-b", "main"], "2025-03-01T08:00:00Z");
+    commit(repo, "a.txt", "During session", "2025-03-01T10:30:00Z");
+
+    let commits = commits_between(
+        repo,
+        Some("feature-gone"),
+        ts("2025-03-01T10:00:00Z"),
+        ts("2025-03-01T11:00:00Z"),
+        &BTreeSet::new(),
+    )
+    .unwrap();
+    let subjects: Vec<&str> = commits.iter().map(|c| c.subject.as_str()).collect();
+    assert_eq!(subjects, vec!["During session"]);
+}
+
+#[test]
+fn loader_looks_up_session_commits_in_background() {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = dir.path().join("repo");
+    fs::create_dir_all(&repo).unwrap();
+    git(&repo, &["init", "-q", "-b", "main"], "2025-03-01T08:00:00Z");
+    commit(&repo, "a.txt", "During session", "2025-03-01T10:30:00Z");
+
+    let home = dir.path().join("home");
+    let sessions = home.join("projects/-repo");
+    fs::create_dir_all(&sessions).unwrap();
+    let cwd = repo.to_string_lossy();
+    let lines = [
+        format!(r#"{{"type":"user","cwd":"{cwd}","sessionId":"s1","gitBranch":"main","message":{{"role":"user","content":"Go"}},"timestamp":"2025-03-01T10:00:00.000Z"}}"#),
+        format!(r#"{{"type":"user","cwd":"{cwd}","sessionId":"s1","message":{{"role":"user","content":"More"}},"timestamp":"2025-03-01T11:00:00.000Z"}}"#),
+    ];
+    let path = sessions.join("s1.jsonl");
+    fs::write(&path, lines.join("\n") + "\n").unwrap();
+    let session = parse_session_file(&path).unwrap();
+
+    let mut loader = CommitsLoader::new(home);
+    loader.request(&session);
+    let mut results = Vec::new();
+    for _ in 0..500 {
+        results.extend(loader.poll());
+        if !results.is_empty() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    let (id, commits) = results.pop().expect("lookup finished");
+    assert_eq!(id, "s1");
+    assert_eq!(commits.unwrap().len(), 1);
+}
+
+#[test]
+fn missing_repo_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let result = commits_between(
+        dir.path(),
+        None,
+        ts("2025-03-01T10:00:00Z"),
+        ts("2025-03-01T11:00:00Z"),
+        &BTreeSet::new(),
+    );
+    assert!(result.is_err());
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::fs;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use cc_session::config::Config;
+use cc_session::discovery::discover_sessions;
+use cc_session::history::History;
+use cc_session::theme::Theme;
+use cc_session::tui::input::handle_input;
+use cc_session::tui::{App, Mode};
+
+#[test]
+fn history_collapses_duplicates_and_keeps_the_newest() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("state/filter_history");
+    let mut history = History::load(&path, 3);
+    for query in ["alpha", "beta", " alpha ", "gamma", "", "delta"] {
+        history.add(query).unwrap();
+    }
+    assert_eq!(history.entries(), ["alpha", "gamma", "delta"]);
+
+    let reloaded = History::load(&path, 3);
+    assert_eq!(reloaded.entries(), history.entries());
+    assert_eq!(reloaded.find("A", 3), Some(2));
+    assert_eq!(reloaded.find("a", 2), Some(1));
+    assert_eq!(reloaded.find("zeta", 3), None);
+}
+
+#[test]
+fn history_size_comes_from_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    assert_eq!(Config::load_from(&path).unwrap(), Config::default());
+    fs::write(&path, "history_size = 20\n").unwrap();
+    assert_eq!(Config::load_from(&path).unwrap().history_size, 20);
+    fs::write(&path, "history_size = \"many\"\n").unwrap();
+    assert!(Config::load_from(&path).is_err());
+}
+
+fn app() -> (tempfile::TempDir, App) {
+    let home = tempfile::tempdir().unwrap();
+    let dir = home.path().join("projects/-hist");
+    fs::create_dir_all(&dir).unwrap();
+    let entry = serde_json::json!({
+        "type": "user", "cwd": "/hist", "sessionId": "hist",
+        "timestamp": "2025-03-01T10:00:00Z",
+        "message": {"role": "user", "content": "hello"},
+    });
+    fs::write(dir.join("hist.jsonl"), format!("{entry}\n")).unwrap();
+    let mut app = App::new(discover_sessions(home.path()), HashMap::new(), Theme::dark());
+    for query in ["flaky", "release", "deploy"] {
+        app.filter_history.add(query).unwrap();
+    }
+    (home, app)
+}
+
+fn press(app: &mut App, code: KeyCode) {
+    handle_input(app, KeyEvent::new(code, KeyModifiers::NONE));
+}
+
+#[test]
+fn up_and_down_recall_filter_queries() {
+    let (_home, mut app) = app();
+    press(&mut app, KeyCode::Char('x'));
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.filter_query, "deploy");
+    press(&mut app, KeyCode::Up);
+    press(&mut app, KeyCode::Up);
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.filter_query, "flaky", "stops at the oldest");
+    press(&mut app, KeyCode::Down);
+    assert_eq!(app.filter_query, "release");
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Down);
+    assert_eq!(app.filter_query, "x", "the typed query comes back");
+    assert!(app.recall.is_none());
+}
+
+#[test]
+fn up_without_filter_editing_keeps_the_filter() {
+    let (_home, mut app) = app();
+    assert!(!app.filter_active);
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.filter_query, "");
+    assert!(app.recall.is_none());
+}
+
+#[test]
+fn ctrl_r_searches_the_history() {
+    let (_home, mut app) = app();
+    handle_input(&mut app, KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+    press(&mut app, KeyCode::Char('e'));
+    assert_eq!(app.history_search.as_ref().unwrap().found, Some(2), "deploy");
+    handle_input(&mut app, KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+    assert_eq!(app.history_search.as_ref().unwrap().found, Some(1), "release");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.history_search.is_none());
+    assert_eq!(app.filter_query, "release");
+    assert_eq!(app.mode, Mode::Browsing);
+}
+
+#[test]
+fn confirmed_viewer_searches_are_recorded() {
+    let (_home, mut app) = app();
+    app.enter_conversation(0);
+    press(&mut app, KeyCode::Char('/'));
+    for c in "hello".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.search_history.entries(), ["hello"]);
+
+    press(&mut app, KeyCode::Char('/'));
+    press(&mut app, KeyCode::Backspace);
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.conversation.as_ref().unwrap().search_query, "hello");
+}
@@ -0,0 +1,132 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use cc_session::discovery::discover_sessions;
+use cc_session::live::{detect_live_sessions, is_claude_command, session_id_from_args, ClaudeProcess};
+
+fn fixture_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn recognizes_claude_commands() {
+    assert!(is_claude_command("claude", &args(&["claude"])));
+    assert!(is_claude_command("node", &args(&["node", "/usr/local/bin/claude", "-c"])));
+    assert!(!is_claude_command("vim", &args(&["vim", "claude.md"])));
+}
+
+#[test]
+fn extracts_session_id_from_args() {
+    assert_eq!(session_id_from_args(&args(&["claude", "-r", "abc"])).as_deref(), Some("abc"));
+    assert_eq!(session_id_from_args(&args(&["claude", "--resume=abc"])).as_deref(), Some("abc"));
+    assert_eq!(session_id_from_args(&args(&["claude", "--resume", "--verbose"])), None);
+    assert_eq!(session_id_from_args(&args(&["claude", "-c"])), None);
+}
+
+#[test]
+fn process_with_session_id_marks_session_live() {
+    let sessions = discover_sessions(&fixture_dir());
+    let processes = vec![ClaudeProcess {
+        pid: 4242,
+        cwd: PathBuf::from("/elsewhere"),
+        session_id: Some("22222222-2222-2222-2222-222222222222".to_string()),
+        tmux_pane: Some("%7".to_string()),
+        started: None,
+        open_files: Vec::new(),
+    }];
+    let live = detect_live_sessions(&fixture_dir(), &sessions, &processes);
+    let entry = live.get("22222222-2222-2222-2222-222222222222").unwrap();
+    assert_eq!(entry.pid, Some(4242));
+    assert_eq!(entry.tmux_pane.as_deref(), Some("%7"));
+}
+
+/// Copy the fixture projects into a temporary Claude home with old mtimes,
+/// so that no session counts as recently written.
+fn aged_home() -> tempfile::TempDir {
+    let home = tempfile::tempdir().unwrap();
+    let old = SystemTime::now() - Duration::from_secs(3600);
+    for project in ["-project-a", "-project-b"] {
+        let src = fixture_dir().join("projects").join(project);
+        let dst = home.path().join("projects").join(project);
+        fs::create_dir_all(&dst).unwrap();
+        for file in fs::read_dir(&src).unwrap().flatten() {
+            let target = dst.join(file.file_name());
+            fs::copy(file.path(), &target).unwrap();
+            fs::File::options()
+                .write(true)
+                .open(&target)
+                .unwrap()
+                .set_modified(old)
+                .unwrap();
+        }
+    }
+    home
+}
+
+fn process_in(cwd: &str, started: SystemTime) -> ClaudeProcess {
+    ClaudeProcess {
+        pid: 7,
+        cwd: PathBuf::from(cwd),
+        session_id: None,
+        tmux_pane: None,
+        started: Some(started),
+        open_files: Vec::new(),
+    }
+}
+
+#[test]
+fn process_cwd_matches_session_written_since_it_started() {
+    let home = aged_home();
+    let sessions = discover_sessions(home.path());
+    let two_hours_ago = SystemTime::now() - Duration::from_secs(7200);
+    let processes = vec![process_in("/Users/test/project-b", two_hours_ago)];
+    let live = detect_live_sessions(home.path(), &sessions, &processes);
+    assert!(live.contains_key("33333333-3333-3333-3333-333333333333"));
+    assert!(!live.contains_key("11111111-1111-1111-1111-111111111111"));
+}
+
+#[test]
+fn new_process_does_not_claim_older_session_in_its_directory() {
+    let home = aged_home();
+    let sessions = discover_sessions(home.path());
+    let processes = vec![process_in("/Users/test/project-b", SystemTime::now())];
+    assert!(detect_live_sessions(home.path(), &sessions, &processes).is_empty());
+}
+
+#[test]
+fn process_holding_session_file_open_marks_it_live() {
+    let home = aged_home();
+    let sessions = discover_sessions(home.path());
+    let path = home
+        .path()
+        .join("projects/-project-a/11111111-1111-1111-1111-111111111111.jsonl");
+    let mut process = process_in("/somewhere/else", SystemTime::now());
+    process.open_files = vec![path];
+    let live = detect_live_sessions(home.path(), &sessions, &[process]);
+    assert_eq!(live.len(), 1);
+    assert!(live.contains_key("11111111-1111-1111-1111-111111111111"));
+}
+
+#[test]
+fn recently_written_session_is_live_without_process() {
+    let home = aged_home();
+    let sessions = discover_sessions(home.path());
+    let path = home
+        .path()
+        .join("projects/-project-a/22222222-2222-2222-2222-222222222222.jsonl");
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(SystemTime::now())
+        .unwrap();
+
+    let live = detect_live_sessions(home.path(), &sessions, &[]);
+    assert_eq!(live.len(), 1);
+    assert!(live.contains_key("22222222-2222-2222-2222-222222222222"));
+}
@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+
+use cc_session::discovery::discover_sessions;
+use cc_session::open::{link_at, Link};
+use cc_session::search::build_session_index;
+use cc_session::theme::Theme;
+use cc_session::tui::input::handle_mouse;
+use cc_session::tui::{Action, App};
+
+fn fixture_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+    MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    }
+}
+
+#[test]
+fn finds_links_under_the_cursor() {
+    let line = "See (https://example.com/docs). Then edit src/main.rs:42 please";
+    assert_eq!(link_at(line, 10), Some(Link::Url("https://example.com/docs".into())));
+    assert_eq!(link_at(line, 45), Some(Link::Path("src/main.rs".into())));
+    assert_eq!(link_at(line, 0), None);
+    assert_eq!(link_at(line, 3), None);
+    assert_eq!(link_at(line, 200), None);
+}
+
+#[test]
+fn clicks_select_and_double_clicks_open() {
+    let home = fixture_dir();
+    let sessions = discover_sessions(&home);
+    let index = build_session_index(&home, &sessions);
+    let mut app = App::new(sessions, index, Theme::dark());
+    app.hit_areas.list = Rect::new(0, 0, 80, 10);
+
+    let click = mouse(MouseEventKind::Down(MouseButton::Left), 5, 2);
+    assert!(matches!(handle_mouse(&mut app, click), Action::Continue));
+    assert_eq!(app.selected, 1);
+    assert!(matches!(handle_mouse(&mut app, click), Action::EnterConversation(1)));
+
+    // Clicks below the last row are ignored
+    handle_mouse(&mut app, mouse(MouseEventKind::Down(MouseButton::Left), 5, 8));
+    assert_eq!(app.selected, 1);
+
+    handle_mouse(&mut app, mouse(MouseEventKind::ScrollDown, 5, 5));
+    assert_eq!(app.selected, 2);
+    handle_mouse(&mut app, mouse(MouseEventKind::ScrollUp, 5, 5));
+    assert_eq!(app.selected, 0);
+}
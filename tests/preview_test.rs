@@ -0,0 +1,93 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+use cc_session::discovery::{discover_sessions, merge_conversation_line, parse_session_file};
+use cc_session::session::Session;
+use cc_session::tui::preview::{build_preview, Preview, PreviewLoader, PREVIEW_PROMPTS};
+
+fn fixture_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+#[test]
+fn preview_keeps_first_prompts_and_last_reply() {
+    let mut messages = Vec::new();
+    for i in 1..=5 {
+        let user = format!(
+            r#"{{"type":"user","message":{{"role":"user","content":"prompt {i}"}},"timestamp":"2025-03-01T10:0{i}:00.000Z"}}"#
+        );
+        let assistant = format!(
+            r#"{{"type":"assistant","message":{{"role":"assistant","content":[{{"type":"text","text":"reply {i}"}}]}},"timestamp":"2025-03-01T10:0{i}:30.000Z"}}"#
+        );
+        merge_conversation_line(&mut messages, &user);
+        merge_conversation_line(&mut messages, &assistant);
+    }
+
+    let preview = build_preview(&messages);
+    assert_eq!(preview.user_messages, 5);
+    assert_eq!(preview.assistant_messages, 5);
+    assert_eq!(preview.prompts.len(), PREVIEW_PROMPTS);
+    assert_eq!(preview.prompts[0], "prompt 1");
+    assert_eq!(preview.last_reply.as_deref(), Some("reply 5"));
+}
+
+/// Request a preview of `session` and wait until it is loaded.
+fn wait_for_preview<'a>(loader: &'a mut PreviewLoader, session: &Session) -> &'a Preview {
+    loader.request(session);
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while loader.get(session).is_none() && Instant::now() < deadline {
+        loader.poll();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    loader.get(session).expect("preview loaded")
+}
+
+#[test]
+fn loader_fills_cache_in_background() {
+    let home = fixture_dir();
+    let sessions = discover_sessions(&home);
+    let session = sessions
+        .iter()
+        .find(|s| s.id == "11111111-1111-1111-1111-111111111111")
+        .unwrap();
+
+    let mut loader = PreviewLoader::new(home);
+    assert!(loader.get(session).is_none());
+    let preview = wait_for_preview(&mut loader, session);
+    assert_eq!(preview.prompts, vec!["Help me implement the list endpoints feature"]);
+    assert_eq!(
+        preview.last_reply.as_deref(),
+        Some("I'll help you implement the list endpoints.")
+    );
+}
+
+#[test]
+fn appended_sessions_are_reloaded() {
+    let home = tempfile::tempdir().unwrap();
+    let dir = home.path().join("projects/-live");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("live.jsonl");
+    let reply = |text: &str| {
+        format!(
+            r#"{{"type":"assistant","message":{{"role":"assistant","content":[{{"type":"text","text":"{text}"}}]}},"timestamp":"2025-03-01T10:00:30.000Z"}}"#
+        )
+    };
+    let user = r#"{"type":"user","cwd":"/live","sessionId":"live","message":{"role":"user","content":"hello"},"timestamp":"2025-03-01T10:00:00.000Z"}"#;
+    fs::write(&path, format!("{user}\n{}\n", reply("first"))).unwrap();
+
+    let mut loader = PreviewLoader::new(home.path().to_path_buf());
+    let session = parse_session_file(&path).unwrap();
+    assert_eq!(wait_for_preview(&mut loader, &session).last_reply.as_deref(), Some("first"));
+
+    let mut file = fs::File::options().append(true).open(&path).unwrap();
+    writeln!(file, "{}", reply("second")).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(60)).unwrap();
+    drop(file);
+
+    let updated = parse_session_file(&path).unwrap();
+    assert_eq!(updated.timestamp, session.timestamp);
+    assert!(loader.get(&updated).is_none());
+    assert_eq!(wait_for_preview(&mut loader, &updated).last_reply.as_deref(), Some("first\n\nsecond"));
+}
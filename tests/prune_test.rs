@@ -0,0 +1,198 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use cc_session::prune::{
+    archive_candidate, delete_candidate, find_prune_candidates, PruneCriteria, PruneReason,
+};
+use cc_session::remap::{PathRemapper, PathRule};
+
+/// Write a session whose working directory is `cwd`, with one user entry
+/// per prompt.
+fn write_session(home: &Path, id: &str, cwd: &str, prompts: &[&str]) -> PathBuf {
+    let dir = home.join("projects").join("-project");
+    fs::create_dir_all(&dir).unwrap();
+    let lines: Vec<String> = prompts
+        .iter()
+        .enumerate()
+        .map(|(i, prompt)| {
+            format!(
+                r#"{{"type":"user","cwd":"{cwd}","sessionId":"{id}","message":{{"role":"user","content":"{prompt}"}},"uuid":"u{i}","timestamp":"2025-03-01T10:0{i}:00.000Z"}}"#
+            )
+        })
+        .collect();
+    let path = dir.join(format!("{id}.jsonl"));
+    fs::write(&path, lines.join("\n") + "\n").unwrap();
+    path
+}
+
+fn ids(candidates: &[cc_session::prune::PruneCandidate]) -> Vec<(&str, PruneReason)> {
+    candidates.iter().map(|c| (c.id.as_str(), c.reason)).collect()
+}
+
+#[test]
+fn groups_sessions_by_first_matching_reason() {
+    let home = tempfile::tempdir().unwrap();
+    let project = home.path().join("work");
+    fs::create_dir_all(&project).unwrap();
+    let live = project.to_string_lossy().to_string();
+
+    write_session(home.path(), "dead", "/nonexistent/gone", &["old work"]);
+    write_session(home.path(), "empty", &live, &["<command-name>/clear</command-name>"]);
+    write_session(home.path(), "short", &live, &["one question"]);
+    write_session(home.path(), "long", &live, &["first", "second", "third"]);
+
+    let criteria = PruneCriteria {
+        dead: true,
+        empty: true,
+        min_turns: Some(2),
+        older_than: None,
+    };
+    let found = find_prune_candidates(home.path(), &criteria, &PathRemapper::default());
+    assert_eq!(
+        ids(&found),
+        vec![
+            ("dead", PruneReason::DeadProject),
+            ("empty", PruneReason::NoPrompt),
+            ("short", PruneReason::FewTurns),
+        ]
+    );
+
+    // Only the requested criteria apply
+    let dead_only = PruneCriteria {
+        dead: true,
+        ..PruneCriteria::default()
+    };
+    let found = find_prune_candidates(home.path(), &dead_only, &PathRemapper::default());
+    assert_eq!(ids(&found), vec![("dead", PruneReason::DeadProject)]);
+}
+
+#[test]
+fn remapped_projects_are_not_dead() {
+    let home = tempfile::tempdir().unwrap();
+    let project = home.path().join("moved");
+    fs::create_dir_all(&project).unwrap();
+    write_session(home.path(), "s1", "/old/place", &["hello"]);
+
+    let criteria = PruneCriteria {
+        dead: true,
+        ..PruneCriteria::default()
+    };
+    let remapper = PathRemapper::new(&[PathRule {
+        from: "/old/place".to_string(),
+        to: project.to_string_lossy().to_string(),
+        regex: false,
+    }])
+    .unwrap();
+    assert!(find_prune_candidates(home.path(), &criteria, &remapper).is_empty());
+    assert_eq!(find_prune_candidates(home.path(), &criteria, &PathRemapper::default()).len(), 1);
+}
+
+#[test]
+fn old_sessions_use_last_activity() {
+    let home = tempfile::tempdir().unwrap();
+    let project = home.path().join("work");
+    fs::create_dir_all(&project).unwrap();
+    let live = project.to_string_lossy().to_string();
+    let old = write_session(home.path(), "old", &live, &["hi"]);
+    write_session(home.path(), "recent", &live, &["hi"]);
+    let year_ago = SystemTime::now() - Duration::from_secs(365 * 86_400);
+    fs::File::options().write(true).open(&old).unwrap().set_modified(year_ago).unwrap();
+
+    let criteria = PruneCriteria {
+        older_than: Some(chrono::Utc::now() - chrono::Duration::days(30)),
+        ..PruneCriteria::default()
+    };
+    let found = find_prune_candidates(home.path(), &criteria, &PathRemapper::default());
+    assert_eq!(ids(&found), vec![("old", PruneReason::Old)]);
+}
+
+/// A dead session with a todo list, a subagent directory and sidechain,
+/// and per-session state, next to an unrelated todo list.
+fn session_with_related_files(home: &Path) {
+    let session = write_session(home, "s1", "/nonexistent/gone", &["hi"]);
+    let dir = session.parent().unwrap();
+    let related = vec![
+        home.join("todos/s1-agent-s1.json"),
+        dir.join("s1/subagents/agent-a1.jsonl"),
+        home.join("file-history/s1/abc@v1"),
+        home.join("debug/s1.txt"),
+    ];
+    for path in &related {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "[]").unwrap();
+    }
+    fs::write(
+        dir.join("agent-b2.jsonl"),
+        r#"{"type":"user","isSidechain":true,"sessionId":"s1","message":{"role":"user","content":"task"}}"#,
+    )
+    .unwrap();
+    fs::write(home.join("todos/s2-agent-s2.json"), "[]").unwrap();
+}
+
+#[test]
+fn candidates_include_related_files() {
+    let home = tempfile::tempdir().unwrap();
+    session_with_related_files(home.path());
+    let criteria = PruneCriteria {
+        dead: true,
+        ..PruneCriteria::default()
+    };
+    let found = find_prune_candidates(home.path(), &criteria, &PathRemapper::default());
+    // The sidechain file is pruned with its session, not on its own
+    assert_eq!(found.len(), 1);
+    let relative: Vec<String> = found[0]
+        .files
+        .iter()
+        .map(|p| p.strip_prefix(home.path()).unwrap().to_string_lossy().to_string())
+        .collect();
+    assert_eq!(
+        relative,
+        vec![
+            "projects/-project/s1.jsonl",
+            "projects/-project/s1",
+            "todos/s1-agent-s1.json",
+            "file-history/s1",
+            "debug/s1.txt",
+            "projects/-project/agent-b2.jsonl",
+        ]
+    );
+    let expected: u64 = found[0].files.iter().map(|p| cc_session::prune::disk_size(p)).sum();
+    assert_eq!(found[0].size, expected);
+    assert!(found[0].size > 0);
+}
+
+#[test]
+fn delete_removes_everything() {
+    let home = tempfile::tempdir().unwrap();
+    session_with_related_files(home.path());
+    let criteria = PruneCriteria {
+        dead: true,
+        ..PruneCriteria::default()
+    };
+    let found = find_prune_candidates(home.path(), &criteria, &PathRemapper::default());
+    delete_candidate(&found[0]).unwrap();
+    assert!(found[0].files.iter().all(|p| !p.exists()));
+    assert!(home.path().join("todos/s2-agent-s2.json").exists());
+    assert!(find_prune_candidates(home.path(), &criteria, &PathRemapper::default()).is_empty());
+}
+
+#[test]
+fn archive_keeps_relative_paths() {
+    let home = tempfile::tempdir().unwrap();
+    let archive = tempfile::tempdir().unwrap();
+    session_with_related_files(home.path());
+    let criteria = PruneCriteria {
+        dead: true,
+        ..PruneCriteria::default()
+    };
+    let found = find_prune_candidates(home.path(), &criteria, &PathRemapper::default());
+    archive_candidate(home.path(), &found[0], archive.path()).unwrap();
+
+    assert!(found[0].files.iter().all(|p| !p.exists()));
+    assert!(archive.path().join("projects/-project/s1.jsonl").is_file());
+    assert!(archive.path().join("projects/-project/s1/subagents/agent-a1.jsonl").is_file());
+    assert!(archive.path().join("todos/s1-agent-s1.json").is_file());
+    assert!(archive.path().join("file-history/s1/abc@v1").is_file());
+    assert!(archive.path().join("projects/-project/agent-b2.jsonl").is_file());
+}
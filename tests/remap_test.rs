@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use cc_session::config::Config;
+use cc_session::git::origin_url;
+use cc_session::remap::{remote_repo_name, suggest_locations, PathRemapper, PathRule};
+use cc_session::session::Session;
+use cc_session::theme::Theme;
+use cc_session::tui::input::handle_input;
+use cc_session::tui::App;
+
+fn rule(from: &str, to: &str, regex: bool) -> PathRule {
+    PathRule {
+        from: from.to_string(),
+        to: to.to_string(),
+        regex,
+    }
+}
+
+fn session(id: &str, cwd: &str) -> Session {
+    Session {
+        id: id.to_string(),
+        project_path: cwd.to_string(),
+        project_name: Path::new(cwd).file_name().unwrap().to_string_lossy().into_owned(),
+        git_branch: None,
+        timestamp: Utc::now(),
+        last_activity: Utc::now(),
+        first_message: "hello".to_string(),
+        cwd: cwd.to_string(),
+        project_exists: Path::new(cwd).exists(),
+        version: None,
+        file_path: PathBuf::from(format!("/sessions/{id}.jsonl")),
+    }
+}
+
+/// Make a git repository at `path` with an optional origin remote.
+fn repo(path: &Path, remote: Option<&str>) {
+    fs::create_dir_all(path.join(".git")).unwrap();
+    let mut config = "[core]\n\tbare = false\n".to_string();
+    if let Some(url) = remote {
+        config.push_str(&format!("[remote \"origin\"]\n\turl = {url}\n\tfetch = +refs/heads/*:refs/remotes/origin/*\n"));
+    }
+    fs::write(path.join(".git/config"), config).unwrap();
+}
+
+#[test]
+fn prefix_rules_match_whole_components_and_chain() {
+    let remapper = PathRemapper::new(&[
+        rule("/workspace/", "/home/me/src/api", false),
+        rule("^/home/me/src/(\\w+)$", "/home/me/code/$1", true),
+    ])
+    .unwrap();
+    assert_eq!(remapper.apply("/workspace").as_deref(), Some("/home/me/code/api"));
+    assert_eq!(remapper.apply("/workspace/web").as_deref(), Some("/home/me/src/api/web"));
+    assert_eq!(remapper.apply("/workspaces/web"), None);
+    assert_eq!(remapper.apply("/elsewhere"), None);
+
+    assert!(PathRemapper::new(&[rule("/", "/x", false)]).is_err());
+    assert!(PathRemapper::new(&[rule("(", "/x", true)]).unwrap_err().contains("invalid remap regex"));
+}
+
+#[test]
+fn remapped_sessions_show_and_resume_in_the_new_place() {
+    let dir = tempfile::tempdir().unwrap();
+    let target = dir.path().join("api");
+    fs::create_dir_all(&target).unwrap();
+    let remapper = PathRemapper::new(&[rule("/workspace", target.to_str().unwrap(), false)]).unwrap();
+
+    let mut s = session("s1", "/workspace");
+    assert!(!s.project_exists);
+    remapper.remap_session(&mut s);
+    assert_eq!(s.cwd, target.to_str().unwrap());
+    assert_eq!(s.project_name, "api");
+    assert!(s.project_exists);
+    assert!(s.resume_command().starts_with(&format!("cd '{}'", target.display())));
+}
+
+#[test]
+fn remotes_name_their_repository() {
+    assert_eq!(remote_repo_name("git@github.com:acme/api.git"), Some("api"));
+    assert_eq!(remote_repo_name("https://github.com/acme/web/"), Some("web"));
+    assert_eq!(remote_repo_name("ssh://host/srv/tool"), Some("tool"));
+
+    let dir = tempfile::tempdir().unwrap();
+    repo(dir.path(), Some("git@github.com:acme/api.git"));
+    assert_eq!(origin_url(dir.path()).as_deref(), Some("git@github.com:acme/api.git"));
+    let bare = dir.path().join("bare");
+    repo(&bare, None);
+    assert_eq!(origin_url(&bare), None);
+}
+
+#[test]
+fn suggestions_prefer_matching_remotes() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    repo(&root.join("clients/renamed"), Some("git@github.com:acme/api.git"));
+    repo(&root.join("old/api"), Some("git@github.com:acme/api-legacy.git"));
+    repo(&root.join("web"), Some("git@github.com:acme/web.git"));
+    fs::create_dir_all(root.join("notes/api")).unwrap(); // not a repository
+
+    let suggestions = suggest_locations(Path::new("/gone/api"), &[root.to_path_buf()]);
+    let paths: Vec<PathBuf> = suggestions.iter().map(|s| s.path.clone()).collect();
+    assert_eq!(paths, vec![root.join("clients/renamed"), root.join("old/api")]);
+    assert_eq!(suggestions[0].remote.as_deref(), Some("git@github.com:acme/api.git"));
+}
+
+#[test]
+fn relocating_a_project_remaps_its_sessions_and_saves_the_rule() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("src");
+    repo(&root.join("web"), Some("git@github.com:acme/web.git"));
+    repo(&root.join("api-moved"), Some("git@github.com:acme/api.git"));
+    let config_path = dir.path().join("config.toml");
+    fs::write(&config_path, "# tuned by hand\nhistory_size = 7\n").unwrap();
+
+    let sessions = vec![
+        session("gone", "/old/place/api"),
+        session("web", root.join("web").to_str().unwrap()),
+    ];
+    let mut app = App::new(sessions, HashMap::new(), Theme::dark());
+    app.config_path = Some(config_path.clone());
+
+    handle_input(&mut app, KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL));
+    assert!(app.relocate.is_none(), "the search runs in the background");
+    for _ in 0..500 {
+        app.poll_relocate();
+        if app.relocate.is_some() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    let menu = app.relocate.as_ref().expect("suggestions for the missing project");
+    assert_eq!(menu.suggestions[0].path, root.join("api-moved"));
+    handle_input(&mut app, KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    let moved = app.sessions.iter().find(|s| s.id == "gone").unwrap();
+    assert_eq!(Path::new(&moved.cwd), root.join("api-moved"));
+    assert!(moved.project_exists);
+
+    let config = Config::load_from(&config_path).unwrap();
+    assert_eq!(config.history_size, 7, "other settings are kept");
+    assert_eq!(config.remap, vec![rule("/old/place/api", root.join("api-moved").to_str().unwrap(), false)]);
+    let text = fs::read_to_string(&config_path).unwrap();
+    assert!(text.starts_with("# tuned by hand\nhistory_size = 7\n"), "{text}");
+    assert!(text.contains("[[remap]]"));
+    assert!(!text.contains("sort"), "only the rules are written: {text}");
+
+    // Existing projects have nothing to relocate
+    app.selected = app.display_entries.iter().position(|e| app.display_session(e).id == "web").unwrap();
+    handle_input(&mut app, KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL));
+    assert!(app.relocate.is_none());
+}
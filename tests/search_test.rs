@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use cc_session::search::deep_search;
+use cc_session::search::{build_pattern_regex, deep_search, SearchOptions};
 
 fn fixture_dir() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
@@ -36,3 +36,23 @@ fn invalid_regex_returns_empty() {
     let sessions = deep_search(&fixture_dir(), "[invalid(regex");
     assert!(sessions.is_empty());
 }
+
+#[test]
+fn pattern_options_control_matching() {
+    let literal = SearchOptions::default();
+    assert!(build_pattern_regex("a.b", literal).unwrap().is_match("A.B"));
+    assert!(!build_pattern_regex("a.b", literal).unwrap().is_match("axb"));
+
+    let regex = SearchOptions { regex: true, ..literal };
+    assert!(build_pattern_regex("a.b", regex).unwrap().is_match("AxB"));
+    assert!(!build_pattern_regex("(?-i)a.b", regex).unwrap().is_match("AxB"));
+    assert!(build_pattern_regex("[invalid", regex).is_err());
+
+    let case = SearchOptions { case_sensitive: true, ..literal };
+    assert!(!build_pattern_regex("error", case).unwrap().is_match("Error"));
+
+    let word = SearchOptions { whole_word: true, regex: true, ..literal };
+    let re = build_pattern_regex("err|warn", word).unwrap();
+    assert!(re.is_match("an err here"));
+    assert!(!re.is_match("stderr and warnings"));
+}
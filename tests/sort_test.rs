@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, Utc};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, Terminal};
+
+use cc_session::config::Config;
+use cc_session::session::Session;
+use cc_session::sort::SortKey;
+use cc_session::theme::Theme;
+use cc_session::tui::input::handle_input;
+use cc_session::tui::{view, App};
+
+fn session(id: &str, project: &str, branch: Option<&str>, days_ago: i64, dir: &Path) -> Session {
+    let timestamp = Utc::now() - Duration::days(days_ago);
+    Session {
+        id: id.to_string(),
+        project_path: format!("/work/{project}"),
+        project_name: project.to_string(),
+        git_branch: branch.map(str::to_string),
+        timestamp,
+        last_activity: timestamp,
+        first_message: format!("prompt for {id}"),
+        cwd: format!("/work/{project}"),
+        project_exists: true,
+        version: None,
+        file_path: dir.join(format!("{id}.jsonl")),
+    }
+}
+
+/// Write a session file with `turns` prompt-and-reply pairs, a minute apart.
+fn write_turns(path: &Path, turns: usize, tokens: u64) {
+    let mut lines = Vec::new();
+    for i in 0..turns {
+        let ts = |s: usize| format!("2025-03-01T10:{:02}:{:02}Z", i, s);
+        lines.push(
+            serde_json::json!({
+                "type": "user", "timestamp": ts(0),
+                "message": {"role": "user", "content": format!("question {i}")},
+            })
+            .to_string(),
+        );
+        lines.push(
+            serde_json::json!({
+                "type": "assistant", "timestamp": ts(30),
+                "message": {
+                    "id": format!("msg{i}"), "role": "assistant", "model": "claude",
+                    "content": [{"type": "text", "text": "answer"}],
+                    "usage": {"input_tokens": tokens, "output_tokens": 0},
+                },
+            })
+            .to_string(),
+        );
+    }
+    fs::write(path, lines.join("\n") + "\n").unwrap();
+}
+
+/// Wait for the background stats scan to finish.
+fn wait_for_stats(app: &mut App) {
+    for _ in 0..500 {
+        app.poll_stats();
+        if !app.stats_loader.is_loading() {
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    panic!("stats scan did not finish");
+}
+
+fn order(app: &App) -> Vec<String> {
+    app.display_entries
+        .iter()
+        .map(|e| app.display_session(e).id.clone())
+        .collect()
+}
+
+fn app(dir: &Path) -> App {
+    let mut sessions = vec![
+        session("a", "zeta", Some("main"), 1, dir),
+        session("b", "alpha", None, 2, dir),
+        session("c", "Mid", Some("feature"), 3, dir),
+    ];
+    sessions[2].last_activity = Utc::now();
+    write_turns(&dir.join("a.jsonl"), 1, 10);
+    write_turns(&dir.join("b.jsonl"), 3, 1);
+    write_turns(&dir.join("c.jsonl"), 2, 100);
+    App::new(sessions, HashMap::new(), Theme::dark())
+}
+
+#[test]
+fn each_key_orders_the_list() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = app(dir.path());
+    assert_eq!(app.sort, SortKey::Created);
+
+    let mut orders = HashMap::new();
+    for key in SortKey::ALL {
+        app.set_sort(key, false);
+        wait_for_stats(&mut app);
+        orders.insert(key, order(&app));
+    }
+    let ids = |ids: &[&str]| ids.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    assert_eq!(orders[&SortKey::Created], ids(&["a", "b", "c"]));
+    assert_eq!(orders[&SortKey::LastActivity], ids(&["c", "a", "b"]));
+    assert_eq!(orders[&SortKey::Project], ids(&["b", "c", "a"]));
+    assert_eq!(orders[&SortKey::Branch], ids(&["c", "a", "b"]), "no branch goes last");
+    assert_eq!(orders[&SortKey::Messages], ids(&["b", "c", "a"]));
+    assert_eq!(orders[&SortKey::Tokens], ids(&["c", "a", "b"]));
+    assert_eq!(orders[&SortKey::Duration], ids(&["b", "c", "a"]));
+    assert_eq!(orders[&SortKey::Relevance], ids(&["a", "b", "c"]), "ties go to the newest");
+
+    app.set_sort(SortKey::Messages, true);
+    assert_eq!(order(&app), ids(&["a", "c", "b"]));
+}
+
+#[test]
+fn relevance_prefers_project_then_branch_then_prompt() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut sessions = vec![
+        session("prompt", "web", None, 1, dir.path()),
+        session("branch", "web", Some("api-fix"), 2, dir.path()),
+        session("project", "api", None, 3, dir.path()),
+    ];
+    sessions[0].first_message = "call the api".into();
+    let mut app = App::new(sessions, HashMap::new(), Theme::dark());
+    app.set_sort(SortKey::Relevance, false);
+    app.set_filter_query("api".into());
+    assert_eq!(order(&app), vec!["project", "branch", "prompt"]);
+}
+
+#[test]
+fn sort_menu_picks_and_reverses_and_persists_the_sort() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path: PathBuf = dir.path().join("config/config.toml");
+    let mut app = app(dir.path());
+    app.config_path = Some(config_path.clone());
+
+    let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+    let press = |app: &mut App, code| handle_input(app, KeyEvent::new(code, KeyModifiers::NONE));
+    handle_input(&mut app, ctrl('s'));
+    let cursor = app.sort_menu.as_ref().unwrap().cursor;
+    assert_eq!(SortKey::ALL[cursor], SortKey::Created, "opens on the current key");
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 16)).unwrap();
+    terminal.draw(|frame| view::render(frame, &mut app)).unwrap();
+    let screen: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(screen.contains("Sort by") && screen.contains("tokens"), "{screen}");
+
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('r'));
+    assert!(app.sort_reverse);
+    assert!(app.sort_menu.is_some(), "reversing keeps the menu open");
+    press(&mut app, KeyCode::Enter);
+    assert!(app.sort_menu.is_none());
+    assert_eq!(app.sort, SortKey::Project);
+    assert_eq!(order(&app), vec!["a", "c", "b"]);
+
+    let config = Config::load_from(&config_path).unwrap();
+    assert_eq!((config.sort, config.sort_reverse), (SortKey::Project, true));
+    assert!(fs::read_to_string(&config_path).unwrap().contains("sort = \"project\""));
+
+    handle_input(&mut app, ctrl('x'));
+    assert!(!app.sort_reverse);
+    handle_input(&mut app, ctrl('s'));
+    press(&mut app, KeyCode::Esc);
+    assert!(app.sort_menu.is_none());
+    assert_eq!(app.sort, SortKey::Project, "Esc keeps the sort");
+}
+
+#[test]
+fn saving_the_sort_keeps_the_rest_of_the_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    let original = "# my settings\nhistory_size = 50  # keep it short\nsort = \"created\"\n\n[[remap]]\nfrom = \"/a\"\nto = \"/b\"\n";
+    fs::write(&config_path, original).unwrap();
+    let mut app = app(dir.path());
+    app.config_path = Some(config_path.clone());
+    app.set_sort(SortKey::Project, true);
+
+    let text = fs::read_to_string(&config_path).unwrap();
+    assert!(text.starts_with("# my settings\nhistory_size = 50  # keep it short\n"), "{text}");
+    assert!(text.contains("sort = \"project\""), "{text}");
+    assert!(text.contains("sort_reverse = true"), "{text}");
+    assert!(!text.contains("columns"), "defaults are not written out: {text}");
+    let config = Config::load_from(&config_path).unwrap();
+    assert_eq!(config.history_size, 50);
+    assert_eq!(config.remap.len(), 1);
+}
+
+#[test]
+fn stats_of_forgotten_scans_are_dropped() {
+    use cc_session::tui::stats::StatsLoader;
+
+    let dir = tempfile::tempdir().unwrap();
+    let a = session("a", "zeta", None, 1, dir.path());
+    write_turns(&a.file_path, 2, 5);
+    let mut loader = StatsLoader::new();
+    loader.request(&[&a]);
+    assert!(loader.is_pending("a"));
+    loader.forget("a");
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert!(loader.poll().is_empty());
+
+    loader.request(&[&a]);
+    let mut scanned = Vec::new();
+    for _ in 0..500 {
+        scanned.extend(loader.poll());
+        if !loader.is_loading() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert_eq!(scanned.len(), 1);
+    assert_eq!(scanned[0].1.turns, 2);
+}
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use chrono::{Duration, Local, TimeZone, Utc};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use cc_session::session::Session;
+use cc_session::theme::Theme;
+use cc_session::timespec::{parse_duration, parse_time, TimeSpec};
+use cc_session::tui::input::handle_input;
+use cc_session::tui::App;
+use cc_session::views::Scope;
+
+fn session(id: &str, timestamp: chrono::DateTime<Utc>) -> Session {
+    Session {
+        id: id.to_string(),
+        project_path: "/work/api".to_string(),
+        project_name: "api".to_string(),
+        git_branch: None,
+        timestamp,
+        last_activity: timestamp,
+        first_message: "hello".to_string(),
+        cwd: "/work/api".to_string(),
+        project_exists: true,
+        version: None,
+        file_path: PathBuf::from(format!("/work/{id}.jsonl")),
+    }
+}
+
+fn local(y: i32, m: u32, d: u32, h: u32) -> chrono::DateTime<Local> {
+    Local.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap()
+}
+
+#[test]
+fn durations_accept_hours_minutes_and_combined_units() {
+    assert_eq!(parse_duration("12h").unwrap(), Duration::hours(12));
+    assert_eq!(parse_duration("90min").unwrap(), Duration::minutes(90));
+    assert_eq!(parse_duration("1w3d").unwrap(), Duration::days(10));
+    assert_eq!(parse_duration("1m").unwrap(), Duration::days(30));
+    assert_eq!(parse_duration("2d6h").unwrap(), Duration::hours(54));
+    assert!(parse_duration("3").is_err());
+    assert!(parse_duration("3y").is_err());
+    assert!(parse_duration("").is_err());
+}
+
+#[test]
+fn dates_and_words_are_whole_local_days() {
+    let now = local(2025, 3, 5, 15); // a Wednesday
+    let midnight = |d| local(2025, 3, d, 0).with_timezone(&Utc);
+
+    let today = parse_time("today", now).unwrap();
+    assert_eq!(
+        (today.start(now), today.end(now)),
+        (midnight(5), midnight(6))
+    );
+    let yesterday = parse_time("yesterday", now).unwrap();
+    assert_eq!(
+        (yesterday.start(now), yesterday.end(now)),
+        (midnight(4), midnight(5))
+    );
+    let week = parse_time("this-week", now).unwrap();
+    assert_eq!(
+        (week.start(now), week.end(now)),
+        (midnight(3), midnight(10))
+    );
+    let month = parse_time("this month", now).unwrap();
+    assert_eq!(month.start(now), midnight(1));
+
+    let date = parse_time("2025-03-01", now).unwrap();
+    assert_eq!((date.start(now), date.end(now)), (midnight(1), midnight(2)));
+}
+
+#[test]
+fn times_and_durations_are_instants() {
+    let now = local(2025, 3, 5, 15);
+    let at = parse_time("2025-03-01T14:30", now).unwrap();
+    assert_eq!(
+        at.start(now),
+        Local
+            .with_ymd_and_hms(2025, 3, 1, 14, 30, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    );
+    let rfc = parse_time("2025-03-01T14:30:00Z", now).unwrap();
+    assert_eq!(
+        rfc,
+        TimeSpec::At(Utc.with_ymd_and_hms(2025, 3, 1, 14, 30, 0).unwrap())
+    );
+    let ago = parse_time("1d", now).unwrap();
+    assert_eq!(
+        ago.start(now),
+        (now - Duration::days(1)).with_timezone(&Utc)
+    );
+    assert!(parse_time("someday", now).is_err());
+}
+
+#[test]
+fn until_includes_the_day_and_before_excludes_it() {
+    let day = local(2025, 3, 1, 12).with_timezone(&Utc);
+    let next = local(2025, 3, 2, 12).with_timezone(&Utc);
+    let ids = |scope: &Scope| -> Vec<String> {
+        let filter = scope.resolve(chrono::Local::now());
+        [session("day", day), session("next", next)]
+            .into_iter()
+            .filter(|s| filter.contains(s))
+            .map(|s| s.id)
+            .collect()
+    };
+    let until = Scope {
+        until: Some("2025-03-01".into()),
+        ..Scope::default()
+    };
+    assert_eq!(ids(&until), vec!["day"]);
+    let before = Scope {
+        before: Some("2025-03-01".into()),
+        ..Scope::default()
+    };
+    assert!(ids(&before).is_empty());
+    let since = Scope {
+        since: Some("2025-03-02".into()),
+        ..Scope::default()
+    };
+    assert_eq!(ids(&since), vec!["next"]);
+    assert_eq!(until.describe(), "until 2025-03-01");
+    assert!(Scope {
+        before: Some("tomorrowish".into()),
+        ..Scope::default()
+    }
+    .validate()
+    .is_err());
+}
+
+#[test]
+fn ctrl_w_cycles_the_time_window() {
+    let now = Utc::now();
+    let sessions = vec![
+        session("now", now),
+        session("old", now - Duration::days(400)),
+    ];
+    let mut app = App::new(sessions, HashMap::new(), Theme::dark());
+    app.scope.until = Some("2w".into());
+    let ctrl_w = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
+
+    let mut windows = Vec::new();
+    for _ in 0..4 {
+        handle_input(&mut app, ctrl_w);
+        windows.push(app.scope.since.clone());
+        assert_eq!(app.scope.until, None);
+    }
+    let windows: Vec<Option<&str>> = windows.iter().map(|w| w.as_deref()).collect();
+    assert_eq!(
+        windows,
+        vec![Some("today"), Some("this-week"), Some("this-month"), None]
+    );
+    assert_eq!(app.filtered_indices.len(), 2);
+
+    handle_input(&mut app, ctrl_w);
+    assert_eq!(app.filtered_indices, vec![0]);
+}
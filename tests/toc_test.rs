@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+use std::fs;
+
+use ratatui::{backend::TestBackend, Terminal};
+
+use cc_session::activity::scan_session_activity;
+use cc_session::discovery::discover_sessions;
+use cc_session::theme::Theme;
+use cc_session::tui::{view, App};
+
+const SESSION: &[&str] = &[
+    r#"{"type":"user","sessionId":"toc","cwd":"/toc","message":{"role":"user","content":"Fix the build"},"timestamp":"2025-03-01T10:00:00Z"}"#,
+    r#"{"type":"assistant","sessionId":"toc","cwd":"/toc","message":{"id":"m1","role":"assistant","content":[{"type":"text","text":"Looking"}]},"timestamp":"2025-03-01T10:00:05Z"}"#,
+    r#"{"type":"assistant","sessionId":"toc","cwd":"/toc","message":{"id":"m1","role":"assistant","content":[{"type":"tool_use","name":"Bash","input":{"command":"cargo build"}}]},"timestamp":"2025-03-01T10:00:06Z"}"#,
+    r#"{"type":"user","sessionId":"toc","cwd":"/toc","message":{"role":"user","content":[{"type":"tool_result","content":"ok"}]},"timestamp":"2025-03-01T10:00:10Z"}"#,
+    r#"{"type":"assistant","sessionId":"toc","cwd":"/toc","message":{"id":"m2","role":"assistant","content":[{"type":"text","text":"Fixed."}]},"timestamp":"2025-03-01T10:00:20Z"}"#,
+    r#"{"type":"user","sessionId":"toc","cwd":"/toc","message":{"role":"user","content":"Now add a test"},"timestamp":"2025-03-01T10:05:00Z"}"#,
+    r#"{"type":"assistant","sessionId":"toc","cwd":"/toc","message":{"id":"m3","role":"assistant","content":[{"type":"text","text":"Added."}]},"timestamp":"2025-03-01T10:05:30Z"}"#,
+];
+
+fn write_session() -> tempfile::TempDir {
+    let home = tempfile::tempdir().unwrap();
+    let dir = home.path().join("projects/-toc");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("toc.jsonl"), SESSION.join("\n") + "\n").unwrap();
+    home
+}
+
+#[test]
+fn prompts_count_the_replies_that_followed() {
+    let home = write_session();
+    let activity = scan_session_activity(&home.path().join("projects/-toc/toc.jsonl"));
+    let prompts: Vec<(&str, usize)> = activity
+        .prompts
+        .iter()
+        .map(|p| (p.text.as_str(), p.assistant_turns))
+        .collect();
+    assert_eq!(prompts, vec![("Fix the build", 2), ("Now add a test", 1)]);
+}
+
+#[test]
+fn toc_follows_the_view_and_jumps_to_prompts() {
+    let home = write_session();
+    let sessions = discover_sessions(home.path());
+    let mut app = App::new(sessions, HashMap::new(), Theme::dark());
+    app.enter_conversation(0);
+    app.toggle_toc();
+
+    let mut terminal = Terminal::new(TestBackend::new(100, 8)).unwrap();
+    terminal.draw(|frame| view::render(frame, &mut app)).unwrap();
+
+    let conv = app.conversation.as_mut().unwrap();
+    assert_eq!(conv.prompt_messages(), vec![Some(0), Some(2)]);
+    assert_eq!(conv.current_prompt(), Some(0));
+
+    conv.move_toc(5);
+    assert_eq!(conv.toc.as_ref().unwrap().cursor, 1, "cursor stops at the last prompt");
+    conv.jump_to_toc_cursor();
+    assert_eq!(conv.scroll_offset, conv.message_starts[2]);
+    assert_eq!(conv.current_prompt(), Some(1));
+    assert!(!conv.toc.as_ref().unwrap().focused, "jumping returns focus");
+
+    // Focus again, then close
+    app.toggle_toc();
+    assert!(app.conversation.as_ref().unwrap().toc.as_ref().unwrap().focused);
+    app.toggle_toc();
+    assert!(app.conversation.as_ref().unwrap().toc.is_none());
+}
@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use cc_session::discovery::discover_sessions;
+use cc_session::search::build_session_index;
+use cc_session::session::Session;
+use cc_session::theme::Theme;
+use cc_session::tui::tree::{build_tree, ListLayout, TreeRow};
+use cc_session::tui::App;
+
+fn fixture_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn sessions() -> Vec<Session> {
+    let mut sessions = discover_sessions(&fixture_dir());
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.timestamp));
+    sessions
+}
+
+fn project_counts(rows: &[TreeRow]) -> Vec<(String, usize)> {
+    rows.iter()
+        .filter_map(|row| match row {
+            TreeRow::Project { name, count, .. } => Some((name.clone(), *count)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn collapsed_tree_shows_one_row_per_project() {
+    let sessions = sessions();
+    let refs: Vec<&Session> = sessions.iter().collect();
+    let rows = build_tree(&refs, &HashSet::new(), false, false);
+
+    assert_eq!(rows.len(), 2);
+    let mut counts = project_counts(&rows);
+    counts.sort();
+    assert_eq!(
+        counts,
+        vec![("project-a".to_string(), 2), ("project-b".to_string(), 1)]
+    );
+}
+
+#[test]
+fn expanded_project_lists_its_sessions() {
+    let sessions = sessions();
+    let refs: Vec<&Session> = sessions.iter().collect();
+    let expanded: HashSet<String> = ["/Users/test/project-a".to_string()].into();
+    let rows = build_tree(&refs, &expanded, false, false);
+
+    assert_eq!(rows.len(), 4);
+    let session_rows = rows
+        .iter()
+        .filter(|row| matches!(row, TreeRow::Session { depth: 1, .. }))
+        .count();
+    assert_eq!(session_rows, 2);
+
+    let all = build_tree(&refs, &HashSet::new(), true, false);
+    assert_eq!(all.len(), 5);
+}
+
+#[test]
+fn branch_grouping_nests_sessions_under_branches() {
+    let sessions = sessions();
+    let refs: Vec<&Session> = sessions.iter().collect();
+    let rows = build_tree(&refs, &HashSet::new(), true, true);
+
+    let branches: Vec<(&str, usize)> = rows
+        .iter()
+        .filter_map(|row| match row {
+            TreeRow::Branch { branch, count, .. } => Some((branch.as_str(), *count)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(branches.len(), 3);
+    assert!(branches.iter().all(|(_, count)| *count == 1));
+    assert!(rows
+        .iter()
+        .all(|row| !matches!(row, TreeRow::Session { depth: 1, .. })));
+}
+
+#[test]
+fn toggling_layout_keeps_selected_session() {
+    let home = fixture_dir();
+    let sessions = discover_sessions(&home);
+    let index = build_session_index(&home, &sessions);
+    let mut app = App::new(sessions, index, Theme::dark());
+    app.selected = app.display_entries.len() - 1;
+    let selected_id = app.selected_session().unwrap().id.clone();
+
+    app.toggle_layout();
+    assert_eq!(app.layout, ListLayout::Tree);
+    assert_eq!(app.selected_session().unwrap().id, selected_id);
+
+    // Collapsing moves the cursor to the project row
+    app.set_selected_project_expanded(Some(false));
+    assert!(app.selected_session().is_none());
+    assert!(matches!(app.tree_rows[app.selected], TreeRow::Project { .. }));
+
+    app.toggle_layout();
+    assert_eq!(app.layout, ListLayout::Flat);
+    assert!(app.selected_session().is_some());
+}
@@ -0,0 +1,168 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{Duration, Utc};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, Terminal};
+
+use cc_session::session::Session;
+use cc_session::sort::SortKey;
+use cc_session::theme::Theme;
+use cc_session::tui::input::handle_input;
+use cc_session::tui::{view, App};
+use cc_session::views::{load_views, save_views, Scope, View};
+
+fn session(id: &str, project: &str, branch: &str, days_ago: i64, prompt: &str) -> Session {
+    Session {
+        id: id.to_string(),
+        project_path: format!("/work/{project}"),
+        project_name: project.to_string(),
+        git_branch: Some(branch.to_string()),
+        timestamp: Utc::now() - Duration::days(days_ago),
+        last_activity: Utc::now() - Duration::days(days_ago),
+        first_message: prompt.to_string(),
+        cwd: format!("/work/{project}"),
+        project_exists: true,
+        version: None,
+        file_path: PathBuf::from(format!("/work/{id}.jsonl")),
+    }
+}
+
+fn sessions() -> Vec<Session> {
+    vec![
+        session("a", "api", "release", 1, "fix flaky test"),
+        session("b", "api", "main", 2, "flaky again"),
+        session("c", "web", "release", 3, "flaky deploy"),
+        session("d", "api", "release", 30, "old flaky run"),
+    ]
+}
+
+#[test]
+fn scope_limits_project_branch_and_age() {
+    let scope = Scope {
+        project: Some("API".into()),
+        branch: Some("release".into()),
+        since: Some("2w".into()),
+        ..Scope::default()
+    };
+    let filter = scope.resolve(chrono::Local::now());
+    let ids: Vec<String> = sessions()
+        .into_iter()
+        .filter(|s| filter.contains(s))
+        .map(|s| s.id)
+        .collect();
+    assert_eq!(ids, vec!["a"]);
+    assert_eq!(scope.describe(), "project API \u{b7} branch release \u{b7} 2w");
+    assert!(Scope::default().is_empty());
+}
+
+#[test]
+fn views_round_trip_through_toml() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("views.toml");
+    assert!(load_views(&path).unwrap().is_empty());
+
+    let mut views = BTreeMap::new();
+    views.insert(
+        "flaky".to_string(),
+        View {
+            query: "flaky".into(),
+            scope: Scope {
+                branch: Some("release".into()),
+                since: Some("2w".into()),
+                ..Scope::default()
+            },
+            sort: Some(SortKey::Tokens),
+            sort_reverse: Some(true),
+        },
+    );
+    save_views(&path, &views).unwrap();
+    let text = fs::read_to_string(&path).unwrap();
+    assert!(text.contains("[flaky]"), "{text}");
+    assert!(!text.contains("project"), "unset fields are left out: {text}");
+    assert!(text.contains("sort = \"tokens\""), "{text}");
+    assert_eq!(load_views(&path).unwrap(), views);
+
+    fs::write(&path, "[bad]\nsince = \"soon\"\n").unwrap();
+    assert!(load_views(&path).unwrap_err().contains("view \"bad\""));
+}
+
+#[test]
+fn views_are_saved_and_shown_from_the_menu() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut app = App::new(sessions(), HashMap::new(), Theme::dark());
+    app.views_path = Some(dir.path().join("views.toml"));
+    app.scope = Scope {
+        project: Some("api".into()),
+        since: Some("2w".into()),
+        ..Scope::default()
+    };
+    app.set_filter_query("flaky".into());
+    app.sort = SortKey::Project;
+    app.sort_reverse = true;
+    assert_eq!(app.display_entries.len(), 2);
+
+    let press = |app: &mut App, code| handle_input(app, KeyEvent::new(code, KeyModifiers::NONE));
+    handle_input(&mut app, KeyEvent::new(KeyCode::Char('v'), KeyModifiers::CONTROL));
+    press(&mut app, KeyCode::Char('s'));
+    for c in "api-flaky".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Enter);
+    assert!(app.view_menu.is_none());
+    assert_eq!(load_views(app.views_path.as_ref().unwrap()).unwrap().len(), 1);
+
+    // Clear, then bring the view back from the menu
+    handle_input(&mut app, KeyEvent::new(KeyCode::Char('v'), KeyModifiers::CONTROL));
+    press(&mut app, KeyCode::Char('c'));
+    app.sort = SortKey::Created;
+    app.sort_reverse = false;
+    assert_eq!(app.display_entries.len(), 4);
+    assert!(app.filter_query.is_empty());
+
+    handle_input(&mut app, KeyEvent::new(KeyCode::Char('v'), KeyModifiers::CONTROL));
+    let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+    terminal.draw(|frame| view::render(frame, &mut app)).unwrap();
+    let screen: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+    assert!(screen.contains("api-flaky"));
+    assert!(screen.contains("by project, reversed"), "{screen}");
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.filter_query, "flaky");
+    assert_eq!(app.scope.project.as_deref(), Some("api"));
+    assert_eq!((app.sort, app.sort_reverse), (SortKey::Project, true));
+    assert_eq!(app.display_entries.len(), 2);
+}
+
+#[test]
+fn views_without_a_sort_keep_the_current_one() {
+    let current = (SortKey::Messages, true);
+    assert_eq!(View::default().sort_order(current), current);
+    let by_tokens = View {
+        sort: Some(SortKey::Tokens),
+        ..View::default()
+    };
+    assert_eq!(by_tokens.sort_order(current), (SortKey::Tokens, true));
+    assert_eq!(by_tokens.describe_sort().as_deref(), Some("by tokens"));
+    let ascending = View {
+        sort: Some(SortKey::Created),
+        sort_reverse: Some(false),
+        ..View::default()
+    };
+    assert_eq!(ascending.sort_order(current), (SortKey::Created, false));
+}
+
+#[test]
+fn resolved_scope_keeps_its_bounds() {
+    let scope = Scope {
+        since: Some("today".into()),
+        ..Scope::default()
+    };
+    let now = chrono::Local::now();
+    let filter = scope.resolve(now);
+    // Resolving at the same moment gives the same bounds
+    assert_eq!(filter, scope.resolve(now));
+    let later = scope.resolve(now + chrono::Duration::days(2));
+    assert_ne!(filter, later, "today moves with the time it is resolved at");
+}
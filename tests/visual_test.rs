@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::fs;
+
+use ratatui::{backend::TestBackend, Terminal};
+
+use cc_session::discovery::discover_sessions;
+use cc_session::theme::Theme;
+use cc_session::tui::{view, App, SourceLines};
+
+fn app_with_session() -> (tempfile::TempDir, App) {
+    let home = tempfile::tempdir().unwrap();
+    let dir = home.path().join("projects/-visual");
+    fs::create_dir_all(&dir).unwrap();
+    let entry = |role: &str, ts: &str, text: &str| {
+        serde_json::json!({
+            "type": role, "cwd": "/visual", "sessionId": "visual", "timestamp": ts,
+            "message": {"role": role, "content": [{"type": "text", "text": text}]},
+        })
+        .to_string()
+    };
+    let long = "word ".repeat(30);
+    let reply = format!("{}\n```rust\nfn main() {{}}\n```\nDone.", long.trim_end());
+    let lines = [
+        entry("user", "2025-03-01T10:00:00Z", "Write **main**"),
+        entry("assistant", "2025-03-01T10:00:10Z", &reply),
+    ];
+    fs::write(dir.join("visual.jsonl"), lines.join("\n") + "\n").unwrap();
+    let sessions = discover_sessions(home.path());
+    let mut app = App::new(sessions, HashMap::new(), Theme::dark());
+    app.enter_conversation(0);
+    let mut terminal = Terminal::new(TestBackend::new(60, 30)).unwrap();
+    terminal.draw(|frame| view::render(frame, &mut app)).unwrap();
+    (home, app)
+}
+
+#[test]
+fn rendered_lines_map_back_to_source_lines() {
+    let (_home, app) = app_with_session();
+    let conv = app.conversation.as_ref().unwrap();
+    assert_eq!(conv.line_sources.len(), conv.lines.len());
+
+    let reply = conv.message_starts[1];
+    assert_eq!(conv.line_sources[reply], None, "headers have no source");
+    let source = |message, start| Some(SourceLines { message, start, end: start + 1 });
+    // The long first line wraps onto several rendered lines
+    assert_eq!(conv.line_sources[reply + 1], source(1, 0));
+    assert_eq!(conv.line_sources[reply + 2], source(1, 0));
+    let code = conv
+        .line_sources
+        .iter()
+        .position(|s| *s == source(1, 2))
+        .expect("code line is mapped");
+    assert_eq!(conv.line_sources[code - 1], None, "code block label has no source");
+}
+
+#[test]
+fn selection_copies_source_text_across_messages() {
+    let (_home, mut app) = app_with_session();
+    let conv = app.conversation.as_mut().unwrap();
+    conv.start_visual();
+    assert_eq!(conv.visual.unwrap().range(), (0, 0));
+
+    conv.move_visual(isize::MAX);
+    let (first, last) = conv.visual.unwrap().range();
+    assert_eq!(last, conv.lines.len() - 1);
+    let text = conv.selection_text(first, last).unwrap();
+    let long = "word ".repeat(30);
+    assert_eq!(
+        text,
+        format!("Write **main**\n\n{}\n```rust\nfn main() {{}}\n```\nDone.", long.trim_end())
+    );
+
+    // Part of a wrapped line selects the whole source line, without markup loss
+    let reply = conv.message_starts[1];
+    assert_eq!(conv.selection_text(reply + 2, reply + 2).unwrap(), long.trim_end());
+    assert_eq!(conv.selection_text(reply, reply), None, "header only");
+}
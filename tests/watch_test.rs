@@ -0,0 +1,123 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use cc_session::discovery::discover_sessions;
+use cc_session::search::build_session_index;
+use cc_session::theme::Theme;
+use cc_session::tui::App;
+use cc_session::watch::SessionWatcher;
+
+fn fixture_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// Copy the fixture projects into a temporary Claude home.
+fn temp_home() -> tempfile::TempDir {
+    let home = tempfile::tempdir().unwrap();
+    for project in ["-project-a", "-project-b"] {
+        let src = fixture_dir().join("projects").join(project);
+        let dst = home.path().join("projects").join(project);
+        fs::create_dir_all(&dst).unwrap();
+        for file in fs::read_dir(&src).unwrap().flatten() {
+            fs::copy(file.path(), dst.join(file.file_name())).unwrap();
+        }
+    }
+    home
+}
+
+fn write_session(home: &Path, id: &str, message: &str) -> PathBuf {
+    let path = home.join("projects/-project-a").join(format!("{id}.jsonl"));
+    let line = format!(
+        r#"{{"type":"user","cwd":"/Users/test/project-a","sessionId":"{id}","gitBranch":"main","message":{{"role":"user","content":"{message}"}},"uuid":"m1","timestamp":"2025-03-01T10:00:00.000Z"}}"#
+    );
+    fs::write(&path, format!("{line}\n")).unwrap();
+    path
+}
+
+fn app_for(home: &Path) -> App {
+    let sessions = discover_sessions(home);
+    let index = build_session_index(home, &sessions);
+    App::new(sessions, index, Theme::dark())
+}
+
+#[test]
+fn merge_adds_new_session_and_keeps_selection() {
+    let home = temp_home();
+    let mut app = app_for(home.path());
+    app.selected = 1;
+    let selected_id = app.display_session(&app.display_entries[1].clone()).id.clone();
+
+    let path = write_session(home.path(), "44444444-4444-4444-4444-444444444444", "Newest work");
+    app.merge_changed_files(&[path]);
+
+    assert_eq!(app.sessions.len(), 4);
+    assert_eq!(app.sessions[0].id, "44444444-4444-4444-4444-444444444444");
+    let now_selected = app.display_session(&app.display_entries[app.selected].clone()).id.clone();
+    assert_eq!(now_selected, selected_id, "selection should follow the same session");
+    assert!(app.is_recently_updated("44444444-4444-4444-4444-444444444444"));
+}
+
+#[test]
+fn merge_keeps_the_last_limit() {
+    let home = temp_home();
+    let mut app = app_for(home.path());
+    assert_eq!(app.sessions.len(), 3);
+    app.last = Some(3);
+    let oldest = app.sessions[2].id.clone();
+
+    let path = write_session(home.path(), "44444444-4444-4444-4444-444444444444", "Newest work");
+    app.merge_changed_files(&[path]);
+
+    assert_eq!(app.sessions.len(), 3);
+    assert_eq!(app.sessions[0].id, "44444444-4444-4444-4444-444444444444");
+    assert!(app.sessions.iter().all(|s| s.id != oldest), "the oldest session drops out");
+    assert!(app.session_index.values().all(|s| s.id != oldest));
+    assert_eq!(app.display_entries.len(), 3);
+}
+
+#[test]
+fn merge_respects_active_filter() {
+    let home = temp_home();
+    let mut app = app_for(home.path());
+    app.filter_query = "OAuth2".to_string();
+    app.apply_filter();
+    assert_eq!(app.display_entries.len(), 1);
+
+    let path = write_session(home.path(), "55555555-5555-5555-5555-555555555555", "Unrelated");
+    app.merge_changed_files(&[path]);
+
+    assert_eq!(app.sessions.len(), 4);
+    assert_eq!(app.display_entries.len(), 1, "filter should still apply to merged sessions");
+}
+
+#[test]
+fn merge_removes_deleted_session() {
+    let home = temp_home();
+    let mut app = app_for(home.path());
+    let path = home
+        .path()
+        .join("projects/-project-b/33333333-3333-3333-3333-333333333333.jsonl");
+    fs::remove_file(&path).unwrap();
+
+    app.merge_changed_files(&[path]);
+
+    assert_eq!(app.sessions.len(), 2);
+    assert!(app.sessions.iter().all(|s| s.id != "33333333-3333-3333-3333-333333333333"));
+}
+
+#[test]
+fn watcher_reports_new_session_files() {
+    let home = temp_home();
+    let watcher = SessionWatcher::new(home.path()).unwrap();
+    let path = write_session(home.path(), "66666666-6666-6666-6666-666666666666", "Watch me");
+    fs::write(home.path().join("projects/-project-a/notes.txt"), "ignored").unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut changed = Vec::new();
+    while changed.is_empty() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(50));
+        changed = watcher.changed_files();
+    }
+    assert_eq!(changed, vec![path]);
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::fs;
+
+use ratatui::{backend::TestBackend, Terminal};
+
+use cc_session::discovery::discover_sessions;
+use cc_session::theme::Theme;
+use cc_session::tui::yank::{code_blocks, CodeBlock};
+use cc_session::tui::{view, App};
+
+#[test]
+fn code_blocks_skip_diffs_and_unclosed_fences() {
+    let text = "Intro\n```rust\nfn main() {}\n```\n```diff src/a.rs\n-a\n+b\n```\n```\nplain\ntext\n```\n```sh\nunclosed";
+    assert_eq!(
+        code_blocks(text),
+        vec![
+            CodeBlock { language: Some("rust".into()), code: "fn main() {}".into() },
+            CodeBlock { language: None, code: "plain\ntext".into() },
+        ]
+    );
+}
+
+#[test]
+fn fences_inside_a_diff_do_not_end_it() {
+    let text = "```diff README.md\n ```rust\n-old\n+new\n ```\n```\n```sh\nls\n```";
+    assert_eq!(
+        code_blocks(text),
+        vec![CodeBlock { language: Some("sh".into()), code: "ls".into() }]
+    );
+}
+
+fn app_with_code() -> (tempfile::TempDir, App) {
+    let home = tempfile::tempdir().unwrap();
+    let dir = home.path().join("projects/-yank");
+    fs::create_dir_all(&dir).unwrap();
+    let entry = |role: &str, ts: &str, text: &str| {
+        serde_json::json!({
+            "type": role, "cwd": "/yank", "sessionId": "yank", "timestamp": ts,
+            "message": {"role": role, "content": [{"type": "text", "text": text}]},
+        })
+        .to_string()
+    };
+    let lines = [
+        entry("user", "2025-03-01T10:00:00Z", "Show me hello world"),
+        entry("assistant", "2025-03-01T10:00:10Z", "Here:\n```rust\nfn main() {}\n```"),
+        entry("user", "2025-03-01T10:01:00Z", "And in Python?"),
+        entry("assistant", "2025-03-01T10:01:10Z", "```python\nprint('hi')\n```"),
+    ];
+    fs::write(dir.join("yank.jsonl"), lines.join("\n") + "\n").unwrap();
+    let sessions = discover_sessions(home.path());
+    let mut app = App::new(sessions, HashMap::new(), Theme::dark());
+    app.enter_conversation(0);
+    let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+    terminal.draw(|frame| view::render(frame, &mut app)).unwrap();
+    (home, app)
+}
+
+#[test]
+fn code_blocks_are_numbered_across_the_conversation() {
+    let (_home, app) = app_with_code();
+    let conv = app.conversation.as_ref().unwrap();
+    assert_eq!(conv.code_block_count(), 2);
+    assert_eq!(conv.code_block(2).unwrap().code, "print('hi')");
+    assert!(conv.code_block(3).is_none());
+    assert!(conv.code_block(0).is_none());
+
+    let labels: Vec<String> = conv
+        .lines
+        .iter()
+        .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect::<String>())
+        .filter(|t| t.starts_with(" ["))
+        .map(|t| t.trim_end().to_string())
+        .collect();
+    assert_eq!(labels, vec![" [1] rust", " [2] python"]);
+}
+
+#[test]
+fn exchange_is_copied_as_markdown_with_attribution() {
+    let (_home, mut app) = app_with_code();
+    let conv = app.conversation.as_mut().unwrap();
+    conv.scroll_offset = conv.message_starts[3];
+    assert_eq!(conv.message_text().unwrap(), "```python\nprint('hi')\n```");
+
+    let markdown = conv.exchange_markdown().unwrap();
+    assert!(markdown.starts_with("**You** ("));
+    assert!(markdown.contains("And in Python?\n\n**Claude** ("));
+    assert!(markdown.contains("print('hi')"));
+    assert!(!markdown.contains("hello world"));
+    assert!(markdown.ends_with("_From Claude Code session `yank` in `/yank`_\n"));
+}